@@ -8,13 +8,16 @@ use crate::level_manager;
 use crate::level_manager::LevelTile;
 use crate::level_manager::TileData;
 use crate::texture_manager;
-use crate::projectile_manager;
 use crate::gui_manager;
 use crate::tower_manager;
 use crate::enemy_manager;
 use crate::upgrade_manager;
+use crate::pathfinding_manager;
+use crate::wave_manager;
+use crate::replay_manager;
+use crate::utilities;
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum BuildingType {
     Base,
     None,
@@ -53,7 +56,6 @@ impl BuildingManager {
     pub fn create_building (
         &mut self, 
         game: &mut game_manager::GameManager,
-        gui_manager: &mut gui_manager::GUIManager,
         building_type: BuildingType,
         temp_tile: &mut LevelTile,
         col_index: usize, 
@@ -61,7 +63,7 @@ impl BuildingManager {
     ) {
         match building_type {
             BuildingType::Base => {
-                // temp_tile.tile_type = constants::TILE_TYPE_BASE;
+                // temp_tile.tile_type = level_manager::TileKind::Base;
                 // temp_tile.tile_data = TileData::Base;
                 // TODO: just make a derived impl for bigger sizes
                 let building = self::Building {
@@ -107,7 +109,7 @@ impl BuildingManager {
 
                 if !self.base_created {
                     self.base_created = true;
-                    gui_manager.create_message("base created, make sure you keep it safe".to_string(), 256);
+                    game.create_message("base created, make sure you keep it safe".to_string(), 256);
                     self.building_vec.push(building);
                     game.base_location = Some((col_index, row_index));
                     if let Some(base_location) = game.base_location {
@@ -115,7 +117,7 @@ impl BuildingManager {
                     }
                 }
                 else {
-                    gui_manager.create_message("base already created".to_string(), 128);
+                    game.create_message("base already created".to_string(), 128);
                 }
             },
             BuildingType::None => {
@@ -166,6 +168,7 @@ impl BuildingManager {
     pub fn render_buildings (
         &mut self,
         game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
         gui_manager: &mut gui_manager::GUIManager,
     ) -> Result<(), String> {
@@ -185,7 +188,7 @@ impl BuildingManager {
             let texture_top_right = tex_man.load(&building.texture_path_top_right)?;
 
 
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &texture_bottom_left,
                 None,
                 building.bottom_left_rect,
@@ -194,7 +197,7 @@ impl BuildingManager {
                 false,
                 false,
             )?;
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &texture_bottom_right,
                 None,
                 building.bottom_right_rect,
@@ -203,7 +206,7 @@ impl BuildingManager {
                 false,
                 false,
             )?;
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &texture_top_left,
                 None,
                 building.top_left_rect,
@@ -212,7 +215,7 @@ impl BuildingManager {
                 false,
                 false,
             )?;
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &texture_top_right,
                 None,
                 building.top_right_rect,
@@ -222,14 +225,30 @@ impl BuildingManager {
                 false,
             )?;
             if building.health < building.max_health {
-                gui_manager.render_health_bar_buildings(game, building);
+                gui_manager.render_health_bar_buildings(canvas, building);
+            }
+        }
+        Ok(())
+    }
+
+    //damaged buildings slowly heal once they stop getting hit
+    pub fn regen_buildings (&mut self) {
+        for building in &mut self.building_vec {
+            if building.health < building.max_health {
                 building.last_damaged += 1;
                 if building.last_damaged > 256 {
                     building.health += 1;
                 }
             }
         }
-        Ok(())
+    }
+
+    pub fn grow_farms (level: &mut level_manager::LevelManager) {
+        for col in &mut level.level_vec {
+            for temp_tile in col {
+                Self::update_farms(temp_tile);
+            }
+        }
     }
 
     pub fn update_buildings(
@@ -239,66 +258,85 @@ impl BuildingManager {
         level: &mut level_manager::LevelManager, 
         player: &mut player_manager::PlayerManager,
         towers: &mut tower_manager::TowerManager, 
-        enemies: &mut enemy_manager::EnemyManager, 
         upgrade_manager: &mut upgrade_manager::UpgradeManager,
         gui_manager: &mut gui_manager::GUIManager,
         minimap_manager: &mut minimap_manager::MinimapManager,
         seed_buttons: &mut button_manager::ButtonManager, 
         build_buttons: &mut button_manager::ButtonManager,
-        projectiles: &mut projectile_manager::ProjectileManager,
+        pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        wave_manager: &wave_manager::WaveManager,
+        replay_manager: &mut replay_manager::ReplayManager,
         // temp_tile: &mut LevelTile,
         // col_index: usize,
         // row_index: usize,
     ) {
         for col_index in 0..level.level_vec.len() {
             for row_index in 0..level.level_vec[col_index].len() {
+                let is_hovered = !game.hovering_button 
+                && sdl2::rect::Rect::contains_point(&level.level_vec[col_index][row_index].rect, game.mouse_point);
+
+                //checked before borrowing the tile, the whole level is needed
+                let seals_path = is_hovered && game.build_mode
+                && Self::placement_blocks_movement(game.current_build)
+                && !pathfinding_manager.placement_keeps_path(
+                    (col_index, row_index),
+                    game.base_location,
+                    &wave_manager.spawn_points,
+                    &level.level_vec
+                );
                 let temp_tile = &mut level.level_vec[col_index][row_index];
 
-                if !game.hovering_button && sdl2::rect::Rect::contains_point(&temp_tile.rect, game.mouse_point){
+                if is_hovered {
                     if game.build_mode {
                         //sooo many arguments....
-                        self.build_mode(game, events, towers, enemies, gui_manager, minimap_manager, build_buttons, temp_tile, col_index, row_index);
+                        self.build_mode(game, gui_manager, minimap_manager, build_buttons, replay_manager, temp_tile, col_index, row_index, seals_path);
                     }
                     else if game.seed_mode {
-                        self.seed_mode(game, events, player, gui_manager, seed_buttons, projectiles, temp_tile, col_index, row_index);
+                        self.seed_mode(game, events, player, gui_manager, seed_buttons, replay_manager, temp_tile, col_index, row_index);
                     }
                     else if game.mouse_button == sdl2::mouse::MouseButton::Left {
-                        upgrade_manager.check_upgrade(game, towers, self, gui_manager, temp_tile, (col_index, row_index));
+                        upgrade_manager.check_upgrade(game, towers, self, temp_tile, (col_index, row_index));
                         //upgrade mode
                     }
                     else if game.mouse_button == sdl2::mouse::MouseButton::Right {
                         println!("INSPECT TILE TYPE: {:?}", temp_tile.tile_type);
                     }
                 }
-                //check for farm updates
-                Self::update_farms(temp_tile);
             }
         }
     }
 
+    //towers stay walkable, only walls can cut enemies off from the base
+    fn placement_blocks_movement(current_build: usize) -> bool {
+        current_build == constants::CURRENT_BUILD_WALL
+    }
+
     fn build_mode(
         &mut self,
         game: &mut game_manager::GameManager,
-        events: &mut event_manager::EventManager,
-        towers: &mut tower_manager::TowerManager,
-        enemies: &mut enemy_manager::EnemyManager,
         gui_manager: &mut gui_manager::GUIManager,
         minimap_manager: &mut minimap_manager::MinimapManager,
         build_buttons: &mut button_manager::ButtonManager,
+        replay_manager: &mut replay_manager::ReplayManager,
         temp_tile: &mut LevelTile,
         col_index: usize,
         row_index: usize,
+        seals_path: bool,
     ) {
-        let is_not_a_tower: bool = temp_tile.tile_type != constants::TILE_TYPE_ARCHER_BOTTOM && temp_tile.tile_type != constants::TILE_TYPE_FIREBALL_BOTTOM;
+        gui_manager.preview.is_blocked = seals_path;
+        let is_not_a_tower: bool = temp_tile.tile_type != level_manager::TileKind::ArcherBottom && temp_tile.tile_type != level_manager::TileKind::FireballBottom;
         match game.current_build {
             constants::CURRENT_BUILD_ARCHER_TOWER => {
-                if !game.placed && temp_tile.tile_type == constants::TILE_TYPE_GRASS && 
+                if !game.placed && temp_tile.tile_type == level_manager::TileKind::Grass && 
                 is_not_a_tower {
-                    if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
+                    if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left && seals_path {
+                        game.create_unique_message("that would block the path to the base".to_string(), 128);
+                    } else if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        temp_tile.tile_type = constants::TILE_TYPE_ARCHER_BOTTOM;
-                        temp_tile.tile_data = TileData::ArcherTowerBottom;
-                        towers.place_tower(game, &temp_tile, (col_index, row_index));
+                        replay_manager.queue_action(replay_manager::ReplayAction::Build {
+                            build: game.current_build,
+                            index: (col_index, row_index),
+                        });
                     } else if game.build_mode && build_buttons.button_vec[constants::CURRENT_BUILD_ARCHER_TOWER].outline_visible {
                         game.preview_mode = true;
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_TOWER_ARCHER_BOTTOM.to_string();
@@ -314,12 +352,15 @@ impl BuildingManager {
                 }
             }
             constants::CURRENT_BUILD_FIREBALL_TOWER => {
-                if !game.placed && temp_tile.tile_type == constants::TILE_TYPE_GRASS && is_not_a_tower {
-                    if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
+                if !game.placed && temp_tile.tile_type == level_manager::TileKind::Grass && is_not_a_tower {
+                    if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left && seals_path {
+                        game.create_unique_message("that would block the path to the base".to_string(), 128);
+                    } else if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        temp_tile.tile_type = constants::TILE_TYPE_FIREBALL_BOTTOM;
-                        temp_tile.tile_data = TileData::FireballTowerBottom;
-                        towers.place_tower(game, &temp_tile, (col_index, row_index));
+                        replay_manager.queue_action(replay_manager::ReplayAction::Build {
+                            build: game.current_build,
+                            index: (col_index, row_index),
+                        });
                     } else if game.build_mode && build_buttons.button_vec[constants::CURRENT_BUILD_FIREBALL_TOWER].outline_visible {
                         game.preview_mode = true;
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_TOWER_FIREBALL_BOTTOM.to_string();
@@ -337,10 +378,13 @@ impl BuildingManager {
 
             }
             constants::CURRENT_BUILD_GOBLIN => {
-                if temp_tile.tile_type == constants::TILE_TYPE_GRASS {
+                if temp_tile.tile_type == level_manager::TileKind::Grass {
                     if /* !game.placed &&  */game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        enemies.place_enemy(game, temp_tile, TileData::Goblin, (col_index, row_index));
+                        replay_manager.queue_action(replay_manager::ReplayAction::Build {
+                            build: game.current_build,
+                            index: (col_index, row_index),
+                        });
                     } else if game.build_mode && build_buttons.button_vec[constants::CURRENT_BUILD_GOBLIN].outline_visible {
                         game.preview_mode = true;
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_GOBLIN_ENEMY.to_string();
@@ -356,12 +400,15 @@ impl BuildingManager {
                 }
             }
             constants::CURRENT_BUILD_WALL => {
-                if !game.placed && !temp_tile.is_occupied && temp_tile.tile_type == constants::TILE_TYPE_GRASS && temp_tile.tile_type != constants::TILE_TYPE_GOBLIN {
-                    if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
+                if !game.placed && !temp_tile.is_occupied && temp_tile.tile_type == level_manager::TileKind::Grass && temp_tile.tile_type != level_manager::TileKind::Goblin {
+                    if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left && seals_path {
+                        game.create_unique_message("that would block the path to the base".to_string(), 128);
+                    } else if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        temp_tile.tile_type = constants::TILE_TYPE_WALL;
-                        temp_tile.texture_path = constants::TEXTURE_TILE_WALL.to_string();
-                        temp_tile.tile_data = TileData::None;
+                        replay_manager.queue_action(replay_manager::ReplayAction::Build {
+                            build: game.current_build,
+                            index: (col_index, row_index),
+                        });
                     } else if game.build_mode && build_buttons.button_vec[constants::CURRENT_BUILD_WALL].outline_visible {
                         game.preview_mode = true;
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_COBBLESTONE.to_string();
@@ -378,10 +425,13 @@ impl BuildingManager {
             }
             constants::CURRENT_BUILD_BASE => {
                 //add check if tiles in 2x2 area are occupied
-                if !game.placed && !temp_tile.is_occupied && temp_tile.tile_type == constants::TILE_TYPE_GRASS && temp_tile.tile_type != constants::TILE_TYPE_BASE {
+                if !game.placed && !temp_tile.is_occupied && temp_tile.tile_type == level_manager::TileKind::Grass && temp_tile.tile_type != level_manager::TileKind::Base {
                     if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        self.create_building(game, gui_manager, BuildingType::Base, temp_tile, col_index, row_index);
+                        replay_manager.queue_action(replay_manager::ReplayAction::Build {
+                            build: game.current_build,
+                            index: (col_index, row_index),
+                        });
                     } else if game.build_mode && build_buttons.button_vec[constants::CURRENT_BUILD_BASE].outline_visible {
                         game.preview_mode = true;
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_HOUSE_BOTTOM_LEFT.to_string();
@@ -398,11 +448,63 @@ impl BuildingManager {
             }
             _ => {}
         }
-        if game.placed {
-            enemy_manager::EnemyManager::repath_all_enemies(enemies);
-            events.level_updated = true;
-            /*             minimap_manager.update_minimap(); */
+    }
+
+    //validated again, the tile can change between the click and the step that places it
+    pub fn place_build(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &mut level_manager::LevelManager,
+        towers: &mut tower_manager::TowerManager,
+        enemies: &mut enemy_manager::EnemyManager,
+        pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        wave_manager: &wave_manager::WaveManager,
+        build: usize,
+        index: (usize, usize),
+    ) {
+        let (col_index, row_index) = index;
+        if col_index >= level.level_vec.len() || row_index >= level.level_vec[col_index].len() {
+            return
+        }
+        let seals_path = Self::placement_blocks_movement(build)
+        && !pathfinding_manager.placement_keeps_path(
+            index,
+            game.base_location,
+            &wave_manager.spawn_points,
+            &level.level_vec
+        );
+        let temp_tile = &mut level.level_vec[col_index][row_index];
+        if temp_tile.tile_type != level_manager::TileKind::Grass || seals_path {
+            return
         }
+
+        match build {
+            constants::CURRENT_BUILD_ARCHER_TOWER => {
+                temp_tile.tile_type = level_manager::TileKind::ArcherBottom;
+                temp_tile.tile_data = TileData::ArcherTowerBottom;
+                towers.place_tower(game, temp_tile, index);
+            }
+            constants::CURRENT_BUILD_FIREBALL_TOWER => {
+                temp_tile.tile_type = level_manager::TileKind::FireballBottom;
+                temp_tile.tile_data = TileData::FireballTowerBottom;
+                towers.place_tower(game, temp_tile, index);
+            }
+            constants::CURRENT_BUILD_GOBLIN => {
+                enemies.place_enemy(game, temp_tile, enemy_manager::EnemyType::Goblin, index);
+            }
+            constants::CURRENT_BUILD_WALL if !temp_tile.is_occupied => {
+                temp_tile.tile_type = level_manager::TileKind::Wall;
+                temp_tile.texture_path = constants::TEXTURE_TILE_WALL.to_string();
+                temp_tile.tile_data = TileData::None;
+                temp_tile.health = constants::WALL_HEALTH;
+            }
+            constants::CURRENT_BUILD_BASE if !temp_tile.is_occupied => {
+                self.create_building(game, BuildingType::Base, temp_tile, col_index, row_index);
+            }
+            _ => return,
+        }
+        enemy_manager::EnemyManager::repath_all_enemies(enemies, pathfinding_manager, index);
+        game.level_updated = true;
     }
 
     fn seed_mode (
@@ -412,7 +514,7 @@ impl BuildingManager {
         player: &mut player_manager::PlayerManager,
         gui_manager: &mut gui_manager::GUIManager,
         seed_buttons: &mut button_manager::ButtonManager,
-        projectiles: &mut projectile_manager::ProjectileManager,
+        replay_manager: &mut replay_manager::ReplayManager,
         temp_tile: &mut LevelTile,
         col_index: usize,
         row_index: usize, 
@@ -423,10 +525,11 @@ impl BuildingManager {
                     let start = (player.rect.x() + player.x, player.rect.y() + player.y);
                     println!("spawned gun projectile");
 
-                    projectiles.spawn_player_projectile(player, start, start, (
-                        events.mouse_point.x + game.cam_x,
-                        events.mouse_point.y + game.cam_y
-                    ));
+                    replay_manager.queue_action(replay_manager::ReplayAction::Shoot {
+                        start,
+                        target: (events.mouse_point.x + game.cam_x,
+                            events.mouse_point.y + game.cam_y),
+                    });
 
                     let texture = constants::TEXTURE_PREVIEW_GUN.to_string();
                     gui_manager.preview.texture_path_bottom_left = texture;
@@ -448,12 +551,10 @@ impl BuildingManager {
             }
             seed if seed == constants::CURRENT_SEED_SHOVEL => {
                 if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
-                    temp_tile.tile_type = temp_tile.original_type;
-                    match temp_tile.tile_type {
-                        constants::TILE_TYPE_GRASS => temp_tile.texture_path = constants::TEXTURE_TILE_GRASS.to_string(),
-                        _ => {},
-                    }
-                    temp_tile.tile_data = TileData::None;
+                    replay_manager.queue_action(replay_manager::ReplayAction::Seed {
+                        seed: game.current_seed,
+                        index: (col_index, row_index),
+                    });
                     let texture = constants::TEXTURE_BUTTON_SHOVEL.to_string();
                     gui_manager.preview.texture_path_bottom_left = texture;
                     gui_manager.preview.texture_path_bottom_right = "".to_string();
@@ -476,19 +577,13 @@ impl BuildingManager {
                 }
             }
             seed if seed == constants::CURRENT_SEED_HO => {
-                if temp_tile.tile_type == constants::TILE_TYPE_GRASS || temp_tile.tile_type == constants::TILE_TYPE_FIELD_HARVESTABLE || temp_tile.tile_type == constants::TILE_TYPE_FIELD_GROWING || temp_tile.tile_type == constants::TILE_TYPE_FIELD_EMPTY {
+                if temp_tile.tile_type == level_manager::TileKind::Grass || temp_tile.tile_type == level_manager::TileKind::FieldHarvestable || temp_tile.tile_type == level_manager::TileKind::FieldGrowing || temp_tile.tile_type == level_manager::TileKind::FieldEmpty {
                     if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        if temp_tile.tile_type == constants::TILE_TYPE_FIELD_HARVESTABLE {
-                            match temp_tile.tile_data {
-                                TileData::Carrots => game.carrot_amount += 1,
-                                TileData::Tomatoes => game.tomato_amount += 1,
-                                _ => {},
-                            }
-                        }                    
-                        temp_tile.tile_type = constants::TILE_TYPE_FIELD_EMPTY;
-                        temp_tile.texture_path = constants::TEXTURE_FIELD_EMPTY.to_string();
-                        temp_tile.tile_data = TileData::None;
+                        replay_manager.queue_action(replay_manager::ReplayAction::Seed {
+                            seed: game.current_seed,
+                            index: (col_index, row_index),
+                        });
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_BUTTON_HO.to_string();
                         gui_manager.preview.texture_path_bottom_right = "".to_string();
                         gui_manager.preview.texture_path_top_left = "".to_string();
@@ -514,15 +609,16 @@ impl BuildingManager {
                 }
             }
             seed if seed == constants::CURRENT_SEED_CARROT => {
-                if game.mouse_button == sdl2::mouse::MouseButton::Left && temp_tile.tile_type != constants::TILE_TYPE_FIELD_EMPTY {
-                    gui_manager.create_unique_message("you need to plant those on a field...".to_string(), 128);
+                if game.mouse_button == sdl2::mouse::MouseButton::Left && temp_tile.tile_type != level_manager::TileKind::FieldEmpty {
+                    game.create_unique_message("you need to plant those on a field...".to_string(), 128);
                 }
-                if temp_tile.tile_type == constants::TILE_TYPE_FIELD_EMPTY {
+                if temp_tile.tile_type == level_manager::TileKind::FieldEmpty {
                     if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        temp_tile.tile_type = constants::TILE_TYPE_FIELD_EMPTY;
-                        temp_tile.texture_path = constants::TEXTURE_FIELD_SEEDS.to_string();
-                        temp_tile.tile_data = TileData::Carrots;
+                        replay_manager.queue_action(replay_manager::ReplayAction::Seed {
+                            seed: game.current_seed,
+                            index: (col_index, row_index),
+                        });
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_FIELD_CARROT.to_string();
                         gui_manager.preview.texture_path_bottom_right = "".to_string();
                         gui_manager.preview.texture_path_top_left = "".to_string();
@@ -547,15 +643,16 @@ impl BuildingManager {
                 }
             }
             seed if seed == constants::CURRENT_SEED_TOMATO => {
-                if game.mouse_button == sdl2::mouse::MouseButton::Left && temp_tile.tile_type != constants::TILE_TYPE_FIELD_EMPTY {
-                    gui_manager.create_unique_message("you need to plant those on a field...".to_string(), 128);
+                if game.mouse_button == sdl2::mouse::MouseButton::Left && temp_tile.tile_type != level_manager::TileKind::FieldEmpty {
+                    game.create_unique_message("you need to plant those on a field...".to_string(), 128);
                 }
-                if temp_tile.tile_type == constants::TILE_TYPE_FIELD_EMPTY {
+                if temp_tile.tile_type == level_manager::TileKind::FieldEmpty {
                     if game.preview_mode && game.mouse_button == sdl2::mouse::MouseButton::Left {
                         game.placed = true;
-                        temp_tile.tile_type = constants::TILE_TYPE_FIELD_EMPTY;
-                        temp_tile.texture_path = constants::TEXTURE_FIELD_SEEDS.to_string();
-                        temp_tile.tile_data = TileData::Tomatoes;
+                        replay_manager.queue_action(replay_manager::ReplayAction::Seed {
+                            seed: game.current_seed,
+                            index: (col_index, row_index),
+                        });
                         gui_manager.preview.texture_path_bottom_left = constants::TEXTURE_FIELD_TOMATO.to_string();
                         gui_manager.preview.texture_path_bottom_right = "".to_string();
                         gui_manager.preview.texture_path_top_left = "".to_string();
@@ -582,12 +679,67 @@ impl BuildingManager {
             _ => {}
         }
     }
+    //what a seed click does to its tile once the simulation steps
+    pub fn use_seed (
+        game: &mut game_manager::GameManager, 
+        level: &mut level_manager::LevelManager,
+        pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        seed: usize,
+        index: (usize, usize),
+    ) {
+        let Some(temp_tile) = level.level_vec.get_mut(index.0).and_then(|col| col.get_mut(index.1)) else {
+            return
+        };
+        let tile_cost = utilities::tile_cost(temp_tile);
+        match seed {
+            seed if seed == constants::CURRENT_SEED_SHOVEL => {
+                temp_tile.tile_type = temp_tile.original_type;
+                match temp_tile.tile_type {
+                    level_manager::TileKind::Grass => temp_tile.texture_path = constants::TEXTURE_TILE_GRASS.to_string(),
+                    _ => {},
+                }
+                temp_tile.tile_data = TileData::None;
+            }
+            seed if seed == constants::CURRENT_SEED_HO && matches!(
+                temp_tile.tile_type,
+                level_manager::TileKind::Grass
+                | level_manager::TileKind::FieldHarvestable
+                | level_manager::TileKind::FieldGrowing
+                | level_manager::TileKind::FieldEmpty
+            ) => {
+                if temp_tile.tile_type == level_manager::TileKind::FieldHarvestable {
+                    match temp_tile.tile_data {
+                        TileData::Carrots => game.carrot_amount += 1,
+                        TileData::Tomatoes => game.tomato_amount += 1,
+                        _ => {},
+                    }
+                }
+                temp_tile.tile_type = level_manager::TileKind::FieldEmpty;
+                temp_tile.texture_path = constants::TEXTURE_FIELD_EMPTY.to_string();
+                temp_tile.tile_data = TileData::None;
+            }
+            seed if seed == constants::CURRENT_SEED_CARROT && temp_tile.tile_type == level_manager::TileKind::FieldEmpty => {
+                temp_tile.texture_path = constants::TEXTURE_FIELD_SEEDS.to_string();
+                temp_tile.tile_data = TileData::Carrots;
+            }
+            seed if seed == constants::CURRENT_SEED_TOMATO && temp_tile.tile_type == level_manager::TileKind::FieldEmpty => {
+                temp_tile.texture_path = constants::TEXTURE_FIELD_SEEDS.to_string();
+                temp_tile.tile_data = TileData::Tomatoes;
+            }
+            _ => {}
+        }
+        //tilling changes how fast enemies cross the tile
+        if utilities::tile_cost(temp_tile) != tile_cost {
+            pathfinding_manager.mark_dirty(index);
+        }
+    }
+
     fn update_farms (temp_tile: &mut LevelTile) {
         //INCREASE ALL FARM STATE
         match temp_tile.tile_data {
             TileData::Carrots | TileData::Tomatoes => {
                 match temp_tile.tile_type {
-                    constants::TILE_TYPE_FIELD_EMPTY | constants::TILE_TYPE_FIELD_GROWING | constants::TILE_TYPE_FIELD_HARVESTABLE => temp_tile.state += 1,
+                    level_manager::TileKind::FieldEmpty | level_manager::TileKind::FieldGrowing | level_manager::TileKind::FieldHarvestable => temp_tile.state += 1,
                     _ => {},
                 }
             }
@@ -595,15 +747,15 @@ impl BuildingManager {
         }
 
         //CHANGE TO GROWING STATE
-        if temp_tile.tile_type == constants::TILE_TYPE_FIELD_EMPTY && temp_tile.state == constants::CROP_TIME {
+        if temp_tile.tile_type == level_manager::TileKind::FieldEmpty && temp_tile.state == constants::CROP_TIME {
             match temp_tile.tile_data {
                 TileData::Carrots | TileData::Tomatoes => {
-                    temp_tile.tile_type = constants::TILE_TYPE_FIELD_GROWING;
+                    temp_tile.tile_type = level_manager::TileKind::FieldGrowing;
                     temp_tile.texture_path = constants::TEXTURE_FIELD_GROWING.to_string();
                     temp_tile.state = 0;
                 }
                 _ => {
-                    temp_tile.tile_type = constants::TILE_TYPE_FIELD_EMPTY;
+                    temp_tile.tile_type = level_manager::TileKind::FieldEmpty;
                     temp_tile.texture_path = constants::TEXTURE_DEFAULT.to_string();
                     temp_tile.state = 0;
                 }
@@ -611,20 +763,20 @@ impl BuildingManager {
         }
 
         //CHANGE TO HARVEST FARM STATE
-        if temp_tile.tile_type == constants::TILE_TYPE_FIELD_GROWING && temp_tile.state == constants::CROP_TIME {
+        if temp_tile.tile_type == level_manager::TileKind::FieldGrowing && temp_tile.state == constants::CROP_TIME {
             match temp_tile.tile_data {
                 TileData::Carrots => {
-                    temp_tile.tile_type = constants::TILE_TYPE_FIELD_HARVESTABLE;
+                    temp_tile.tile_type = level_manager::TileKind::FieldHarvestable;
                     temp_tile.texture_path = constants::TEXTURE_FIELD_CARROT.to_string();
                     temp_tile.state = 0;
                 }
                 TileData::Tomatoes => {
-                    temp_tile.tile_type = constants::TILE_TYPE_FIELD_HARVESTABLE;
+                    temp_tile.tile_type = level_manager::TileKind::FieldHarvestable;
                     temp_tile.texture_path = constants::TEXTURE_FIELD_TOMATO.to_string();
                     temp_tile.state = 0;
                 }
                 _ => {
-                    temp_tile.tile_type = constants::TILE_TYPE_GRASS;
+                    temp_tile.tile_type = level_manager::TileKind::Grass;
                     temp_tile.texture_path = constants::TEXTURE_DEFAULT.to_string();
                     temp_tile.state = 0;
                 }
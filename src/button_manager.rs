@@ -110,7 +110,6 @@ impl ButtonManager {
             button.outline_visible = true;
         }
         if button.outline_visible {
-            utilities::draw_rect_outline(game, button.rect);
             for other_button_index in 0..self.button_vec.len() {
                 let other_button = &mut self.button_vec[other_button_index];
                 if other_button_index != button_index {
@@ -136,7 +135,8 @@ impl ButtonManager {
         player: &mut player_manager::PlayerManager,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
         events: &mut event_manager::EventManager,
-        game: &mut game_manager::GameManager
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>
     ) -> Result<(), String> {
         if game.build_mode {
             for button_index in 0..self.button_vec.len() {
@@ -155,7 +155,7 @@ impl ButtonManager {
                 );
 
                 let texture = tex_man.load(&self.button_vec[button_index].texture_path)?;
-                game.canvas.copy_ex(
+                canvas.copy_ex(
                     &texture,
                     None,
                     self.button_vec[button_index].rect,
@@ -165,6 +165,9 @@ impl ButtonManager {
                     false,
                 )?;
                 self.update_buttons(button_index, game);
+                if self.button_vec[button_index].outline_visible {
+                    utilities::draw_rect_outline(canvas, self.button_vec[button_index].rect);
+                }
             }
         }
         Ok(())
@@ -175,7 +178,8 @@ impl ButtonManager {
         player: &mut player_manager::PlayerManager,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
         events: &mut event_manager::EventManager,
-        game: &mut game_manager::GameManager
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>
     ) -> Result<(), String> {
         if game.seed_mode {
             for button_index in 0..self.button_vec.len() {
@@ -194,7 +198,7 @@ impl ButtonManager {
                 );
 
                 let texture = tex_man.load(&self.button_vec[button_index].texture_path)?;
-                game.canvas.copy_ex(
+                canvas.copy_ex(
                     &texture,
                     None,
                     self.button_vec[button_index].rect,
@@ -204,6 +208,9 @@ impl ButtonManager {
                     false,
                 )?;
                 self.update_buttons(button_index, game);
+                if self.button_vec[button_index].outline_visible {
+                    utilities::draw_rect_outline(canvas, self.button_vec[button_index].rect);
+                }
             }
         }
         Ok(())
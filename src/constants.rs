@@ -14,19 +14,51 @@ pub const COLOR_OUTLINE: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(252, 18
 pub const COLOR_RED: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(255, 0, 0, 255);
 pub const COLOR_GREEN: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(0, 255, 0, 255);
 pub const COLOR_WHITE: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(255, 255, 255, 255);
+pub const COLOR_PREVIEW_BLOCKED: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(255, 0, 0, 128);
+pub const COLOR_BURN: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(255, 110, 0, 110);
+pub const COLOR_STATUS_SLOW: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(80, 160, 255, 255);
+pub const COLOR_STATUS_BURN: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(255, 110, 0, 255);
+pub const COLOR_STATUS_POISON: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(120, 220, 40, 255);
+pub const COLOR_STATUS_STUN: sdl2::pixels::Color = sdl2::pixels::Color::RGBA(255, 230, 0, 255);
 
 pub const MIN_GAME_RATE: f64 = 1.0 / 180.0;
+//gameplay always steps at this rate, rendering runs as fast as it can
+pub const SIMULATION_RATE: f64 = 60.0;
+pub const SIMULATION_STEP: f64 = 1.0 / SIMULATION_RATE;
+//a long hitch only catches up this many steps instead of freezing to catch up
+pub const MAX_SIMULATION_STEPS: u32 = 8;
+//a fast forwarded replay steps this many times as fast
+pub const REPLAY_FAST_FORWARD_SPEED: u32 = 8;
 
 pub const TILE_SIZE: u32 = 32;
-pub const MAX_HEIGHT: u16 = 150;
-pub const MAX_WIDTH: u16 = 300;
+//pixels along the longest side of the minimap
+pub const MINIMAP_SIZE: u32 = 150;
 
 pub const PLAYER_SPEED: u16 = 800;
+pub const PLAYER_PROJECTILE_DAMAGE: u8 = 20;
 pub const CROP_TIME: u16 = 500;
+//ripe tomatoes are sticky, enemies walking through them are slowed by this percent
+pub const TOMATO_SLOW: u8 = 50;
+pub const TOMATO_SLOW_DURATION: u16 = 30;
 
 pub const SEED_BUTTON_AMT: usize = 8;
 pub const BUILD_BUTTON_AMT: usize = 6;
 
+pub static SAVE_PATH: &str = "saves/save.bin";
+pub static REPLAY_PATH: &str = "saves/replay.txt";
+pub static LEVELS_DIRECTORY: &str = "levels";
+pub static LEVEL_FILE_EXTENSION: &str = "txt";
+pub static DEFAULT_LEVEL_PATH: &str = "levels/farm.txt";
+pub static DEFAULT_MUSIC_PATH: &str = "assets/music/song5.mp3";
+pub const STARTING_GOLD: u32 = 9999;
+pub const LEVEL_THUMBNAIL_SIZE: u32 = 64;
+pub const EDITOR_NEW_LEVEL_SIZE: (usize, usize) = (40, 30);
+pub const EDITOR_UNDO_LIMIT: usize = 100;
+pub const SAVE_MAGIC: &[u8; 4] = b"FDSV";
+pub const SAVE_VERSION: u32 = 14;
+//no list in a save is longer, bigger counts mean a corrupt file
+pub const SAVE_MAX_COUNT: usize = 1 << 20;
+
 pub static FONT_PATH: &str = "assets/font/slkscr.ttf";
 pub static TEXTURE_DEFAULT: &str = "assets/default-texture.png";
 
@@ -78,6 +110,9 @@ pub static TEXTURE_PREVIEW_HOUSE_TOP_RIGHT: &str = "assets/preview-house-top-rig
 pub static TEXTURE_TILE_GRASS: &str = "assets/grass-1.png";
 pub static TEXTURE_TILE_WALL: &str = "assets/cobblestone-dark.png";
 pub static TEXTURE_TILE_FLOOR: &str = "assets/cobblestone.png";
+pub static TEXTURE_TILE_SPAWN: &str = "assets/spawn.png";
+pub static TEXTURE_TILE_MUD: &str = "assets/mud.png";
+pub static TEXTURE_TILE_WATER: &str = "assets/water.png";
 pub static TEXTURE_PREVIEW_COBBLESTONE: &str = "assets/preview-cobblestone-dark.png";
 
 pub static TEXTURE_TOWER_ARCHER_FRONT: &str = "assets/archer-tower-front-top.png";
@@ -98,9 +133,18 @@ pub static TEXTURE_PREVIEW_TOWER_FIREBALL_TOP: &str = "assets/preview-fireball-t
 
 pub static TEXTURE_GOBLIN_ENEMY_FRONT: &str = "assets/goblin-enemy-front.png";
 pub static TEXTURE_PREVIEW_GOBLIN_ENEMY: &str = "assets/preview-goblin-enemy.png";
+pub static TEXTURE_RUNNER_ENEMY_FRONT: &str = "assets/runner-enemy-front.png";
+pub static TEXTURE_BRUTE_ENEMY_FRONT: &str = "assets/brute-enemy-front.png";
+pub static TEXTURE_ARCHER_ENEMY_FRONT: &str = "assets/archer-enemy-front.png";
+pub static TEXTURE_BAT_ENEMY_FRONT: &str = "assets/bat-enemy-front.png";
+pub static TEXTURE_OGRE_BOSS_TOP_LEFT: &str = "assets/ogre-boss-top-left.png";
+pub static TEXTURE_OGRE_BOSS_TOP_RIGHT: &str = "assets/ogre-boss-top-right.png";
+pub static TEXTURE_OGRE_BOSS_BOTTOM_LEFT: &str = "assets/ogre-boss-bottom-left.png";
+pub static TEXTURE_OGRE_BOSS_BOTTOM_RIGHT: &str = "assets/ogre-boss-bottom-right.png";
 
 pub static TEXTURE_PROJECTILE_ARROW: &str = "assets/archer-arrow-large.png";
 pub static TEXTURE_PROJECTILE_FIREBALL: &str = "assets/projectile-fireball.png";
+pub static TEXTURE_PROJECTILE_ENEMY_ARROW: &str = "assets/enemy-arrow.png";
 
 pub const CURRENT_BUTTON_MENU_TITLE: usize = 0;
 pub const CURRENT_BUTTON_MENU_PLAY: usize = 1;
@@ -114,6 +158,11 @@ pub const CURRENT_BUTTON_SETTINGS_BACK: usize = 3;
 pub const CURRENT_BUTTON_SETTINGS_RESOLUTION_MINUS: usize = 4;
 pub const CURRENT_BUTTON_SETTINGS_RESOLUTION_PLUS: usize = 5;
 
+pub const CURRENT_BUTTON_LEVELS_PLAY: usize = 0;
+pub const CURRENT_BUTTON_LEVELS_EDIT: usize = 1;
+pub const CURRENT_BUTTON_LEVELS_NEW: usize = 2;
+pub const CURRENT_BUTTON_LEVELS_BACK: usize = 3;
+
 pub const CURRENT_BUILD_ARCHER_TOWER: usize = 0;
 pub const CURRENT_BUILD_FIREBALL_TOWER: usize = 1;
 pub const CURRENT_BUILD_GOBLIN: usize = 2;
@@ -126,31 +175,43 @@ pub const CURRENT_SEED_HO: usize = 2;
 pub const CURRENT_SEED_CARROT: usize = 3;
 pub const CURRENT_SEED_TOMATO: usize = 4;
 
-pub const TILE_TYPE_GRASS: char = '0';
-pub const TILE_TYPE_WALL: char = '2';
-pub const TILE_TYPE_FLOOR: char = '3';
-pub const TILE_TYPE_FIELD_EMPTY: char = 'F';
-pub const TILE_TYPE_FIELD_GROWING: char = 'G';
-pub const TILE_TYPE_FIELD_HARVESTABLE: char = 'H';
-pub const TILE_TYPE_ARCHER_TOP: char = 'A';
-pub const TILE_TYPE_ARCHER_BOTTOM: char = 'a';
-pub const TILE_TYPE_FIREBALL_TOP: char = 'F';
-pub const TILE_TYPE_FIREBALL_BOTTOM: char = 'f';
-pub const TILE_TYPE_GOBLIN: char = 'G';
-pub const TILE_TYPE_BASE: char = 'B';
-
-pub const ENEMY_GOBLIN_HEALTH: u16 = 100;
-pub const ENEMY_GOBLIN_RADIUS: u8 = 64;
-pub const ENEMY_GOBLIN_SPEED: u16 = 900;
-pub const ENEMY_GOBLIN_DAMAGE: u8 = 1;
-pub const ENEMY_GOBLIN_ATTACK_SPEED: u8 = 16;
-pub const ENEMY_GOBLIN_HEALTH_BAR_WIDTH: u32 = 24;
-pub const ENEMY_GOBLIN_HEALTH_BAR_HEIGHT: u32 = 4;
+//movement cost per tile, enemy speed is scaled by TILE_COST_GRASS / cost
+pub const TILE_COST_FLOOR: u32 = 5;
+pub const TILE_COST_GRASS: u32 = 10;
+pub const TILE_COST_FIELD: u32 = 20;
+pub const TILE_COST_MUD: u32 = 40;
+pub const TILE_COST_WATER: u32 = 60;
+//extra cost of breaking through a full health wall
+pub const TILE_COST_WALL_BREACH: u32 = 200;
+
+//step lengths scaled by 10 so diagonals stay integers
+pub const PATH_STEP_STRAIGHT: u32 = 10;
+pub const PATH_STEP_DIAGONAL: u32 = 14;
+//how many flow field steps ground enemies look ahead before smoothing
+pub const PATH_LOOKAHEAD: usize = 8;
+//a* nodes expanded per frame across all queued path requests
+pub const PATH_NODE_BUDGET: usize = 1000;
+//pixels per side of an enemy grid cell, a few tiles so tower ranges cover a handful of cells
+pub const SPATIAL_CELL_SIZE: i32 = 128;
+
+pub const WALL_HEALTH: u16 = 300;
+pub const WALL_REPATH_RADIUS: usize = 8;
+
+pub const ENEMY_HEALTH_BAR_WIDTH: u32 = 24;
+pub const ENEMY_HEALTH_BAR_HEIGHT: u32 = 4;
+pub const BOSS_HEALTH_BAR_WIDTH: u32 = 640;
+pub const BOSS_HEALTH_BAR_HEIGHT: u32 = 16;
 
 pub const TOWER_ARCHER_DAMAGE: u8 = 10;
 pub const TOWER_ARCHER_ATTACK_SPEED: u8 = 16;
 pub const TOWER_ARCHER_RADIUS: i32 = 256;
 pub const TOWER_ARCHER_HEALTH: u16 = 1000;
+pub const TOWER_ARCHER_HOMING: bool = false;
+//arrows from the extra training upgrade go through this many enemies
+pub const TOWER_ARCHER_PIERCE_HITS: u8 = 3;
+//arrows from the broadheads upgrade poison
+pub const TOWER_ARCHER_POISON_DAMAGE: u8 = 3;
+pub const TOWER_ARCHER_POISON_DURATION: u16 = 240;
 pub const TOWER_ARCHER_HEALTH_BAR_WIDTH: u32 = 32;
 pub const TOWER_ARCHER_HEALTH_BAR_HEIGHT: u32 = 6;
 
@@ -158,16 +219,48 @@ pub const TOWER_FIREBALL_DAMAGE: u8 = 50;
 pub const TOWER_FIREBALL_ATTACK_SPEED: u8 = 32;
 pub const TOWER_FIREBALL_RADIUS: i32 = 128;
 pub const TOWER_FIREBALL_HEALTH: u16 = 2000;
+pub const TOWER_FIREBALL_HOMING: bool = true;
+pub const TOWER_FIREBALL_SPLASH_RADIUS: i32 = 64;
+//percent of the damage left at the edge of the splash
+pub const TOWER_FIREBALL_SPLASH_FALLOFF: u8 = 25;
+//fireballs from the extra hot upgrade burn instead of exploding
+pub const TOWER_FIREBALL_BURN_DAMAGE: u8 = 5;
+pub const TOWER_FIREBALL_BURN_DURATION: u16 = 180;
+//fireballs from the bigger balls upgrade stun everything they splash
+pub const TOWER_FIREBALL_STUN_DURATION: u16 = 10;
 pub const TOWER_FIREBALL_HEALTH_BAR_WIDTH: u32 = 32;
 pub const TOWER_FIREBALL_HEALTH_BAR_HEIGHT: u32 = 6;
 
 pub const PROJECTILE_DESPAWN_DURATION: u8 = 32;
 pub const PROJECTILE_HIT_DESPAWN_DURATION: u8 = 2;
+//steps between burn and poison damage
+pub const BURN_INTERVAL: u16 = 15;
+pub const POISON_INTERVAL: u16 = 30;
+//poison stacks, every other status keeps only its strongest
+pub const POISON_MAX_STACKS: usize = 3;
+//pixels per side of the status icons over enemy health bars
+pub const STATUS_ICON_SIZE: u32 = 6;
+//steps a splash explosion is drawn for
+pub const EXPLOSION_DURATION: u8 = 12;
+//steps ahead a tower looks for where its projectile meets a moving enemy
+pub const PROJECTILE_LEAD_STEPS: usize = 90;
 
 pub const PROJECTILE_ARROW_SPEED: f64 = 600.0;
 pub const PROJECTILE_ARROW_RADIUS: u8 = 32;
 pub const PROJECTILE_FIREBALL_SPEED: f64 = 600.0;
 pub const PROJECTILE_FIREBALL_RADIUS: u8 = 32;
+pub const PROJECTILE_ENEMY_ARROW_SPEED: f64 = 400.0;
+pub const PROJECTILE_ENEMY_ARROW_RADIUS: u8 = 24;
+
+pub static WAVE_SCRIPT_EXTENSION: &str = "waves";
+pub const WAVE_BUILD_TIME: f64 = 30.0;
+pub const WAVE_SPAWN_INTERVAL: f64 = 1.0;
+pub const WAVE_MIN_SPAWN_INTERVAL: f64 = 0.25;
+pub const WAVE_SPAWN_INTERVAL_DECAY: f64 = 0.9;
+pub const WAVE_BASE_ENEMY_AMOUNT: u16 = 5;
+pub const WAVE_ENEMY_GROWTH: u16 = 3;
+pub const WAVE_HEALTH_GROWTH: f64 = 0.15;
+pub const WAVE_BOSS_INTERVAL: u32 = 5;
 
 pub const BUILDING_BASE_HEALTH: u16 = 2000;
 pub const BUILDING_BASE_HEALTH_BAR_WIDTH: u32 = 64;
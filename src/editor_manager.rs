@@ -0,0 +1,705 @@
+use crate::{constants, event_manager, game_manager, gui_manager, level_manager, player_manager, texture_manager, utilities, building_manager};
+use crate::level_manager::{LevelEntity, LevelTile, TileData, TileKind};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum EditorTool {
+    Paint,
+    Fill,
+    Rectangle,
+    Spawn,
+    Base,
+    ArcherTower,
+    FireballTower,
+}
+
+impl EditorTool {
+    //number keys 1-7 pick tools in this order
+    pub const ALL: [EditorTool; 7] = [
+        EditorTool::Paint,
+        EditorTool::Fill,
+        EditorTool::Rectangle,
+        EditorTool::Spawn,
+        EditorTool::Base,
+        EditorTool::ArcherTower,
+        EditorTool::FireballTower,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EditorTool::Paint => "paint",
+            EditorTool::Fill => "fill",
+            EditorTool::Rectangle => "rectangle",
+            EditorTool::Spawn => "spawn",
+            EditorTool::Base => "base",
+            EditorTool::ArcherTower => "archer tower",
+            EditorTool::FireballTower => "fireball tower",
+        }
+    }
+
+    fn uses_tile_kind(self) -> bool {
+        matches!(self, EditorTool::Paint | EditorTool::Fill | EditorTool::Rectangle)
+    }
+}
+
+//one undoable edit, keeps what was there before so it can be put back
+#[derive(PartialEq, Clone, Copy)]
+pub enum EditorChange {
+    Tile {
+        index: (usize, usize),
+        before: TileKind,
+        after: TileKind,
+    },
+    Base {
+        before: Option<(usize, usize)>,
+        after: Option<(usize, usize)>,
+    },
+    Tower {
+        index: (usize, usize),
+        before: Option<TileData>,
+        after: Option<TileData>,
+    },
+}
+
+pub struct EditorManager {
+    pub tool: EditorTool,
+    pub tile_kind: TileKind,
+    //each entry is one click, drag or fill
+    pub undo_vec: Vec<Vec<EditorChange>>,
+    pub redo_vec: Vec<Vec<EditorChange>>,
+    pub is_modified: bool,
+    pub hover_index: Option<(usize, usize)>,
+    //paint changes are applied while dragging and become one undo step on release
+    stroke: Vec<EditorChange>,
+    is_painting: bool,
+    rect_start: Option<(usize, usize)>,
+    rect_end: (usize, usize),
+    confirm_close: bool,
+}
+
+impl EditorManager {
+    //tile kinds the paint tools cycle through, towers and bases have their own tools
+    pub const TILE_PALETTE: [TileKind; 7] = [
+        TileKind::Grass,
+        TileKind::Wall,
+        TileKind::Floor,
+        TileKind::FieldEmpty,
+        TileKind::Mud,
+        TileKind::Water,
+        TileKind::Spawn,
+    ];
+
+    pub fn new() -> EditorManager {
+        let editor = EditorManager {
+            tool: EditorTool::Paint,
+            tile_kind: TileKind::Wall,
+            undo_vec: Vec::new(),
+            redo_vec: Vec::new(),
+            is_modified: false,
+            hover_index: None,
+            stroke: Vec::new(),
+            is_painting: false,
+            rect_start: None,
+            rect_end: (0, 0),
+            confirm_close: false,
+        };
+        editor
+    }
+
+    pub fn open_editor(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &level_manager::LevelManager,
+    ) {
+        *self = Self::new();
+        game.editor_mode = true;
+        game.build_mode = false;
+        game.seed_mode = false;
+        game.upgrade_mode = false;
+        game.preview_mode = false;
+        game.hovering_button = false;
+        //the click that opened the editor shouldn't paint
+        game.placed = true;
+        game.create_message(format!("editing {}", level.header.name), 128);
+    }
+
+    pub fn update_editor(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        events: &mut event_manager::EventManager,
+        player: &mut player_manager::PlayerManager,
+        level: &mut level_manager::LevelManager,
+        gui_manager: &mut gui_manager::GUIManager,
+    ) {
+        for (keycode, keymod) in std::mem::take(&mut events.editor_keys) {
+            self.do_editor_key(game, events, level, keycode, keymod);
+        }
+        Self::move_camera(events, player, level);
+        game.update_camera(player, level, events);
+
+        self.hover_index = Self::hovered_index(game, level);
+        if let Some(index) = self.hover_index {
+            if game.mouse_button == sdl2::mouse::MouseButton::Left {
+                self.use_tool(game, level, index);
+            }
+            else if game.mouse_button == sdl2::mouse::MouseButton::Right && !game.placed {
+                game.placed = true;
+                self.use_tool_secondary(level, index);
+            }
+        }
+        if game.mouse_button != sdl2::mouse::MouseButton::Left {
+            self.finish_drag(level);
+        }
+        self.update_preview(game, gui_manager);
+    }
+
+    fn do_editor_key(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        events: &mut event_manager::EventManager,
+        level: &mut level_manager::LevelManager,
+        keycode: sdl2::keyboard::Keycode,
+        keymod: sdl2::keyboard::Mod,
+    ) {
+        let is_ctrl = keymod.intersects(sdl2::keyboard::Mod::LCTRLMOD | sdl2::keyboard::Mod::RCTRLMOD);
+        let is_shift = keymod.intersects(sdl2::keyboard::Mod::LSHIFTMOD | sdl2::keyboard::Mod::RSHIFTMOD);
+        let tool_index = match keycode {
+            sdl2::keyboard::Keycode::Num1 => Some(0),
+            sdl2::keyboard::Keycode::Num2 => Some(1),
+            sdl2::keyboard::Keycode::Num3 => Some(2),
+            sdl2::keyboard::Keycode::Num4 => Some(3),
+            sdl2::keyboard::Keycode::Num5 => Some(4),
+            sdl2::keyboard::Keycode::Num6 => Some(5),
+            sdl2::keyboard::Keycode::Num7 => Some(6),
+            _ => None,
+        };
+        if let Some(tool_index) = tool_index {
+            self.tool = EditorTool::ALL[tool_index];
+            self.rect_start = None;
+            return
+        }
+        if keycode != sdl2::keyboard::Keycode::Escape {
+            self.confirm_close = false;
+        }
+        match keycode {
+            sdl2::keyboard::Keycode::Z if is_ctrl && is_shift => self.redo(level),
+            sdl2::keyboard::Keycode::Z if is_ctrl => self.undo(level),
+            sdl2::keyboard::Keycode::Y if is_ctrl => self.redo(level),
+            sdl2::keyboard::Keycode::S if is_ctrl => events.levels_changed |= self.save_level(game, level),
+            sdl2::keyboard::Keycode::Q => self.cycle_tile_kind(Self::TILE_PALETTE.len() - 1),
+            sdl2::keyboard::Keycode::E => self.cycle_tile_kind(1),
+            sdl2::keyboard::Keycode::Escape => {
+                if self.is_modified && !self.confirm_close {
+                    self.confirm_close = true;
+                    game.create_unique_message("unsaved changes, press escape again to discard".to_string(), 256);
+                }
+                else {
+                    game.editor_mode = false;
+                    game.preview_mode = false;
+                    events.editor_closing = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn cycle_tile_kind(&mut self, step: usize) {
+        let current = Self::TILE_PALETTE.iter().position(|kind| *kind == self.tile_kind).unwrap_or(0);
+        self.tile_kind = Self::TILE_PALETTE[(current + step) % Self::TILE_PALETTE.len()];
+    }
+
+    pub fn save_level(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &level_manager::LevelManager,
+    ) -> bool {
+        match level.write_file(&level.path) {
+            Ok(()) => {
+                self.is_modified = false;
+                game.create_message(format!("saved {}", level.path), 128);
+                true
+            }
+            Err(error) => {
+                eprintln!("Failed to save level: {}", error);
+                game.create_message("failed to save level".to_string(), 256);
+                false
+            }
+        }
+    }
+
+    //free camera, the editor doesn't collide with walls
+    fn move_camera(
+        events: &event_manager::EventManager,
+        player: &mut player_manager::PlayerManager,
+        level: &level_manager::LevelManager,
+    ) {
+        let speed: i32 = (
+            constants::PLAYER_SPEED as f64
+            * events.delta_time.max(constants::MIN_GAME_RATE)
+        ) as i32;
+        let speed = speed.min(constants::TILE_SIZE as i32);
+        if events.up {
+            player.y -= speed;
+        }
+        if events.down {
+            player.y += speed;
+        }
+        if events.left {
+            player.x -= speed;
+        }
+        if events.right {
+            player.x += speed;
+        }
+        let (level_width, level_height) = level.pixel_size();
+        player.x = player.x.min(level_width - events.screen_size.0 / 2).max(-events.screen_size.0 / 2);
+        player.y = player.y.min(level_height - events.screen_size.1 / 2).max(-events.screen_size.1 / 2);
+    }
+
+    fn hovered_index(
+        game: &game_manager::GameManager,
+        level: &level_manager::LevelManager,
+    ) -> Option<(usize, usize)> {
+        let x = game.mouse_point.x + game.cam_x;
+        let y = game.mouse_point.y + game.cam_y;
+        if x < 0 || y < 0 {
+            return None
+        }
+        let index = (x as usize / constants::TILE_SIZE as usize, y as usize / constants::TILE_SIZE as usize);
+        if index.0 < level.width() && index.1 < level.height() {
+            Some(index)
+        }
+        else {
+            None
+        }
+    }
+
+    fn use_tool(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &mut level_manager::LevelManager,
+        index: (usize, usize),
+    ) {
+        match self.tool {
+            EditorTool::Paint => {
+                if !game.placed {
+                    game.placed = true;
+                    self.is_painting = true;
+                }
+                if self.is_painting {
+                    if let Some(change) = Self::tile_change(level, index, self.tile_kind) {
+                        Self::apply_change(level, change, true);
+                        self.stroke.push(change);
+                    }
+                }
+            }
+            EditorTool::Rectangle => {
+                if self.rect_start.is_none() && !game.placed {
+                    game.placed = true;
+                    self.rect_start = Some(index);
+                }
+                self.rect_end = index;
+            }
+            _ if !game.placed => {
+                game.placed = true;
+                let changes = match self.tool {
+                    EditorTool::Fill => Self::flood_fill(level, index, self.tile_kind),
+                    EditorTool::Spawn => Self::tile_change(level, index, TileKind::Spawn).into_iter().collect(),
+                    _ if level.level_vec[index.0][index.1].tile_type == TileKind::Wall => {
+                        game.create_unique_message("can't place that on a wall".to_string(), 128);
+                        Vec::new()
+                    }
+                    //same rules parse_level checks, so the level can be read back
+                    EditorTool::ArcherTower | EditorTool::FireballTower if index.1 == 0 => {
+                        game.create_unique_message("towers need a row above them".to_string(), 128);
+                        Vec::new()
+                    }
+                    EditorTool::Base if index.0 + 1 >= level.width() || index.1 + 1 >= level.height() => {
+                        game.create_unique_message("the base doesn't fit there".to_string(), 128);
+                        Vec::new()
+                    }
+                    EditorTool::Base if level.header.base_location == Some(index) => Vec::new(),
+                    EditorTool::Base => {
+                        let change = EditorChange::Base {
+                            before: level.header.base_location,
+                            after: Some(index),
+                        };
+                        vec![change]
+                    }
+                    EditorTool::ArcherTower => Self::tower_change(level, index, Some(TileData::ArcherTowerBottom)).into_iter().collect(),
+                    _ => Self::tower_change(level, index, Some(TileData::FireballTowerBottom)).into_iter().collect(),
+                };
+                self.commit(level, changes);
+            }
+            _ => {}
+        }
+    }
+
+    //right click picks the hovered tile kind for tile tools and removes things for the others
+    fn use_tool_secondary(
+        &mut self,
+        level: &mut level_manager::LevelManager,
+        index: (usize, usize),
+    ) {
+        let changes: Vec<EditorChange> = match self.tool {
+            EditorTool::Paint | EditorTool::Fill | EditorTool::Rectangle => {
+                let tile_kind = level.level_vec[index.0][index.1].tile_type;
+                if Self::TILE_PALETTE.contains(&tile_kind) {
+                    self.tile_kind = tile_kind;
+                }
+                Vec::new()
+            }
+            EditorTool::Spawn if level.level_vec[index.0][index.1].tile_type == TileKind::Spawn => {
+                Self::tile_change(level, index, TileKind::Grass).into_iter().collect()
+            }
+            EditorTool::Spawn => Vec::new(),
+            EditorTool::Base if level.header.base_location == Some(index) => {
+                let change = EditorChange::Base {
+                    before: Some(index),
+                    after: None,
+                };
+                vec![change]
+            }
+            EditorTool::Base => Vec::new(),
+            EditorTool::ArcherTower | EditorTool::FireballTower => Self::tower_change(level, index, None).into_iter().collect(),
+        };
+        self.commit(level, changes);
+    }
+
+    fn finish_drag(&mut self, level: &mut level_manager::LevelManager) {
+        self.is_painting = false;
+        if !self.stroke.is_empty() {
+            let stroke = std::mem::take(&mut self.stroke);
+            self.push_undo(stroke);
+        }
+        if let Some(rect_start) = self.rect_start.take() {
+            let changes = Self::rectangle_fill(level, rect_start, self.rect_end, self.tile_kind);
+            self.commit(level, changes);
+        }
+    }
+
+    //applies the changes as one undo step
+    pub fn commit(&mut self, level: &mut level_manager::LevelManager, changes: Vec<EditorChange>) {
+        for change in &changes {
+            Self::apply_change(level, *change, true);
+        }
+        self.push_undo(changes);
+    }
+
+    fn push_undo(&mut self, changes: Vec<EditorChange>) {
+        if changes.is_empty() {
+            return
+        }
+        self.undo_vec.push(changes);
+        if self.undo_vec.len() > constants::EDITOR_UNDO_LIMIT {
+            self.undo_vec.remove(0);
+        }
+        self.redo_vec.clear();
+        self.is_modified = true;
+    }
+
+    pub fn undo(&mut self, level: &mut level_manager::LevelManager) {
+        if let Some(changes) = self.undo_vec.pop() {
+            for change in changes.iter().rev() {
+                Self::apply_change(level, *change, false);
+            }
+            self.redo_vec.push(changes);
+            self.is_modified = true;
+        }
+    }
+
+    pub fn redo(&mut self, level: &mut level_manager::LevelManager) {
+        if let Some(changes) = self.redo_vec.pop() {
+            for change in &changes {
+                Self::apply_change(level, *change, true);
+            }
+            self.undo_vec.push(changes);
+            self.is_modified = true;
+        }
+    }
+
+    pub fn apply_change(level: &mut level_manager::LevelManager, change: EditorChange, forward: bool) {
+        match change {
+            EditorChange::Tile { index, before, after } => {
+                let tile_kind = if forward { after } else { before };
+                let rect = level.level_vec[index.0][index.1].rect;
+                level.level_vec[index.0][index.1] = LevelTile::new(tile_kind, rect);
+            }
+            EditorChange::Base { before, after } => {
+                let base_location = if forward { after } else { before };
+                level.entities.retain(|entity| !matches!(
+                    entity,
+                    LevelEntity::Building { building_type: building_manager::BuildingType::Base, .. }
+                ));
+                level.header.base_location = base_location;
+                if let Some(index) = base_location {
+                    level.entities.push(LevelEntity::Building {
+                        building_type: building_manager::BuildingType::Base,
+                        index,
+                    });
+                }
+            }
+            EditorChange::Tower { index, before, after } => {
+                let tile_data = if forward { after } else { before };
+                level.entities.retain(|entity| !matches!(
+                    entity,
+                    LevelEntity::Tower { index: tower_index, .. } if *tower_index == index
+                ));
+                if let Some(tile_data) = tile_data {
+                    level.entities.push(LevelEntity::Tower { tile_data, index });
+                }
+            }
+        }
+    }
+
+    fn tile_change(
+        level: &level_manager::LevelManager,
+        index: (usize, usize),
+        tile_kind: TileKind,
+    ) -> Option<EditorChange> {
+        let before = level.level_vec[index.0][index.1].tile_type;
+        if before == tile_kind {
+            return None
+        }
+        Some(EditorChange::Tile {
+            index,
+            before,
+            after: tile_kind,
+        })
+    }
+
+    fn tower_change(
+        level: &level_manager::LevelManager,
+        index: (usize, usize),
+        tile_data: Option<TileData>,
+    ) -> Option<EditorChange> {
+        let before = level.entities.iter().find_map(|entity| match *entity {
+            LevelEntity::Tower { tile_data, index: tower_index } if tower_index == index => Some(tile_data),
+            _ => None,
+        });
+        if before == tile_data {
+            return None
+        }
+        Some(EditorChange::Tower {
+            index,
+            before,
+            after: tile_data,
+        })
+    }
+
+    //4-connected, so a fill doesn't leak through diagonal gaps in walls
+    pub fn flood_fill(
+        level: &level_manager::LevelManager,
+        start: (usize, usize),
+        tile_kind: TileKind,
+    ) -> Vec<EditorChange> {
+        let target = level.level_vec[start.0][start.1].tile_type;
+        let mut changes = Vec::new();
+        if target == tile_kind {
+            return changes
+        }
+        let mut visited = vec![vec![false; level.height()]; level.width()];
+        let mut stack = vec![start];
+        visited[start.0][start.1] = true;
+        while let Some(index) = stack.pop() {
+            changes.push(EditorChange::Tile {
+                index,
+                before: target,
+                after: tile_kind,
+            });
+            let neighbors = [
+                (index.0.wrapping_sub(1), index.1),
+                (index.0 + 1, index.1),
+                (index.0, index.1.wrapping_sub(1)),
+                (index.0, index.1 + 1),
+            ];
+            for neighbor in neighbors {
+                let is_target = level.level_vec.get(neighbor.0)
+                    .and_then(|col| col.get(neighbor.1))
+                    .is_some_and(|tile| tile.tile_type == target);
+                if is_target && !visited[neighbor.0][neighbor.1] {
+                    visited[neighbor.0][neighbor.1] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        changes
+    }
+
+    pub fn rectangle_fill(
+        level: &level_manager::LevelManager,
+        corner: (usize, usize),
+        other_corner: (usize, usize),
+        tile_kind: TileKind,
+    ) -> Vec<EditorChange> {
+        let mut changes = Vec::new();
+        for col_index in corner.0.min(other_corner.0)..=corner.0.max(other_corner.0) {
+            for row_index in corner.1.min(other_corner.1)..=corner.1.max(other_corner.1) {
+                changes.extend(Self::tile_change(level, (col_index, row_index), tile_kind));
+            }
+        }
+        changes
+    }
+
+    //same preview as build mode, drawn by GUIManager::render_preview
+    fn update_preview(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        gui_manager: &mut gui_manager::GUIManager,
+    ) {
+        let Some(index) = self.hover_index else {
+            game.preview_mode = false;
+            return
+        };
+        game.preview_mode = true;
+        let preview = &mut gui_manager.preview;
+        preview.index = index;
+        preview.is_blocked = false;
+        preview.texture_path_bottom_right = "".to_string();
+        preview.texture_path_top_left = "".to_string();
+        preview.texture_path_top_right = "".to_string();
+        match self.tool {
+            EditorTool::Paint | EditorTool::Fill | EditorTool::Rectangle => {
+                preview.texture_path_bottom_left = match self.tile_kind {
+                    TileKind::Wall => constants::TEXTURE_PREVIEW_COBBLESTONE,
+                    tile_kind => tile_kind.texture_path(),
+                }.to_string();
+            }
+            EditorTool::Spawn => {
+                preview.texture_path_bottom_left = constants::TEXTURE_TILE_SPAWN.to_string();
+            }
+            EditorTool::Base => {
+                preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_HOUSE_BOTTOM_LEFT.to_string();
+                preview.texture_path_bottom_right = constants::TEXTURE_PREVIEW_HOUSE_BOTTOM_RIGHT.to_string();
+                preview.texture_path_top_left = constants::TEXTURE_PREVIEW_HOUSE_TOP_LEFT.to_string();
+                preview.texture_path_top_right = constants::TEXTURE_PREVIEW_HOUSE_TOP_RIGHT.to_string();
+            }
+            EditorTool::ArcherTower => {
+                preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_TOWER_ARCHER_BOTTOM.to_string();
+                preview.texture_path_top_left = constants::TEXTURE_PREVIEW_TOWER_ARCHER_TOP.to_string();
+            }
+            EditorTool::FireballTower => {
+                preview.texture_path_bottom_left = constants::TEXTURE_PREVIEW_TOWER_FIREBALL_BOTTOM.to_string();
+                preview.texture_path_top_left = constants::TEXTURE_PREVIEW_TOWER_FIREBALL_TOP.to_string();
+            }
+        }
+    }
+
+    pub fn render_editor(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
+        events: &mut event_manager::EventManager,
+        player: &mut player_manager::PlayerManager,
+        level: &mut level_manager::LevelManager,
+        gui_manager: &mut gui_manager::GUIManager,
+    ) -> Result<(), String> {
+        level.render_level(game, canvas, tex_man, player, events)?;
+
+        //entities are only markers here, they get placed when the level is played
+        for entity in &level.entities {
+            match *entity {
+                LevelEntity::Tower { tile_data, index } => {
+                    let (bottom, top) = match tile_data {
+                        TileData::ArcherTowerBottom => (constants::TEXTURE_TOWER_ARCHER_BOTTOM, constants::TEXTURE_TOWER_ARCHER_FRONT),
+                        _ => (constants::TEXTURE_TOWER_FIREBALL_BOTTOM, constants::TEXTURE_TOWER_FIREBALL_FRONT),
+                    };
+                    Self::render_marker(game, canvas, tex_man, bottom, index, (0, 0))?;
+                    Self::render_marker(game, canvas, tex_man, top, index, (0, -1))?;
+                }
+                LevelEntity::Building { index, .. } => {
+                    Self::render_marker(game, canvas, tex_man, constants::TEXTURE_BUILDING_HOUSE_BOTTOM_LEFT, index, (0, 0))?;
+                    Self::render_marker(game, canvas, tex_man, constants::TEXTURE_BUILDING_HOUSE_BOTTOM_RIGHT, index, (1, 0))?;
+                    Self::render_marker(game, canvas, tex_man, constants::TEXTURE_BUILDING_HOUSE_TOP_LEFT, index, (0, -1))?;
+                    Self::render_marker(game, canvas, tex_man, constants::TEXTURE_BUILDING_HOUSE_TOP_RIGHT, index, (1, -1))?;
+                }
+                LevelEntity::Crop { index, .. } => {
+                    Self::render_marker(game, canvas, tex_man, constants::TEXTURE_FIELD_SEEDS, index, (0, 0))?;
+                }
+                LevelEntity::Enemy { enemy_type, index } => {
+                    Self::render_marker(game, canvas, tex_man, enemy_type.stats().texture_path, index, (0, 0))?;
+                }
+            }
+        }
+
+        gui_manager.render_preview(game, canvas, tex_man)?;
+        if let Some(rect_start) = self.rect_start {
+            let tile_size = constants::TILE_SIZE as i32;
+            let left = rect_start.0.min(self.rect_end.0) as i32;
+            let top = rect_start.1.min(self.rect_end.1) as i32;
+            let selection = sdl2::rect::Rect::new(
+                left * tile_size - game.cam_x,
+                top * tile_size - game.cam_y,
+                (rect_start.0.abs_diff(self.rect_end.0) as u32 + 1) * constants::TILE_SIZE,
+                (rect_start.1.abs_diff(self.rect_end.1) as u32 + 1) * constants::TILE_SIZE
+            );
+            utilities::draw_rect_outline(canvas, selection);
+        }
+        else if let Some(index) = self.hover_index {
+            let hovered = sdl2::rect::Rect::new(
+                index.0 as i32 * constants::TILE_SIZE as i32 - game.cam_x,
+                index.1 as i32 * constants::TILE_SIZE as i32 - game.cam_y,
+                constants::TILE_SIZE,
+                constants::TILE_SIZE
+            );
+            utilities::draw_rect_outline(canvas, hovered);
+        }
+
+        self.render_editor_hud(canvas, level, gui_manager)?;
+        gui_manager.render_messages(game, canvas, events, tex_man)?;
+        Ok(())
+    }
+
+    fn render_marker(
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
+        texture_path: &str,
+        index: (usize, usize),
+        offset: (i32, i32),
+    ) -> Result<(), String> {
+        let rect = sdl2::rect::Rect::new(
+            (index.0 as i32 + offset.0) * constants::TILE_SIZE as i32 - game.cam_x,
+            (index.1 as i32 + offset.1) * constants::TILE_SIZE as i32 - game.cam_y,
+            constants::TILE_SIZE,
+            constants::TILE_SIZE
+        );
+        let texture = tex_man.load(texture_path)?;
+        canvas.copy(&texture, None, rect)?;
+        Ok(())
+    }
+
+    fn render_editor_hud(
+        &self,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        level: &level_manager::LevelManager,
+        gui_manager: &mut gui_manager::GUIManager,
+    ) -> Result<(), String> {
+        let modified = if self.is_modified { " *" } else { "" };
+        let mut hud_lines = vec![
+            format!("editing {}{}", level.header.name, modified),
+            format!("tool: {} (1-7)", self.tool.name()),
+        ];
+        if self.tool.uses_tile_kind() {
+            hud_lines.push(format!("tile: {} (q/e, right click to pick)", self.tile_kind.name()));
+        }
+        hud_lines.push("ctrl+z undo, ctrl+y redo, ctrl+s save, esc exit".to_string());
+
+        let mut text_y = constants::TILE_SIZE as i32;
+        for hud_line in hud_lines {
+            let text_surface = gui_manager.font.render(&hud_line)
+                .blended(constants::COLOR_WHITE)
+                .map_err(|e| e.to_string())?;
+            let texture = gui_manager.texture_creator.create_texture_from_surface(&text_surface)
+                .map_err(|e| e.to_string())?;
+            let dest = sdl2::rect::Rect::new(
+                constants::TILE_SIZE as i32,
+                text_y,
+                text_surface.width(),
+                text_surface.height()
+            );
+            canvas.copy(&texture, None, dest)?;
+            text_y += text_surface.height() as i32;
+        }
+        Ok(())
+    }
+}
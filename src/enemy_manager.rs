@@ -1,19 +1,201 @@
 use crate::constants;
-use crate::event_manager;
 use crate::player_manager;
 use crate::game_manager;
 use crate::level_manager;
-use crate::level_manager::TileData;
 use crate::texture_manager;
 use crate::gui_manager;
 use crate::pathfinding_manager;
+use crate::spatial_manager;
+use crate::status_manager;
+use crate::tower_manager;
+use crate::utilities;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum EnemyType {
+    Goblin,
+    Runner,
+    Brute,
+    Archer,
+    Bat,
+    Ogre,
+}
+
+//a boss switches to the next phase once its health drops below health_threshold
+pub struct BossPhase {
+    pub health_threshold: f64,
+    pub movement_speed: u16,
+    pub attack_speed: u8,
+    pub summon_type: Option<EnemyType>,
+    pub summon_amount: u8,
+    pub message: &'static str,
+}
+
+pub struct EnemyStats {
+    pub name: &'static str,
+    pub max_health: u16,
+    pub movement_speed: u16,
+    pub attack_damage: u8,
+    pub attack_radius: u8,
+    pub attack_speed: u8,
+    pub texture_path: &'static str,
+    //brutes go for towers instead of the base
+    pub targets_towers: bool,
+    //ranged enemies stop at attack_radius and shoot projectiles
+    pub is_ranged: bool,
+    //flying enemies path over walls
+    pub is_flying: bool,
+    //bosses take up 2x2 tiles: top left, top right, bottom left, bottom right
+    pub boss_textures: Option<[&'static str; 4]>,
+    pub boss_phases: &'static [BossPhase],
+}
+
+pub struct BossParts {
+    pub bottom_right_rect: sdl2::rect::Rect,
+    pub top_left_rect: sdl2::rect::Rect,
+    pub top_right_rect: sdl2::rect::Rect,
+    pub texture_path_bottom_right: String,
+    pub texture_path_top_left: String,
+    pub texture_path_top_right: String,
+}
+
+impl EnemyType {
+    pub const ALL: [EnemyType; 6] = [
+        EnemyType::Goblin,
+        EnemyType::Runner,
+        EnemyType::Brute,
+        EnemyType::Archer,
+        EnemyType::Bat,
+        EnemyType::Ogre,
+    ];
+
+    //enemy catalogue, every enemy type is defined here
+    pub fn stats(&self) -> EnemyStats {
+        match self {
+            EnemyType::Goblin => EnemyStats {
+                name: "goblin",
+                max_health: 100,
+                movement_speed: 900,
+                attack_damage: 1,
+                attack_radius: 64,
+                attack_speed: 16,
+                texture_path: constants::TEXTURE_GOBLIN_ENEMY_FRONT,
+                targets_towers: false,
+                is_ranged: false,
+                is_flying: false,
+                boss_textures: None,
+                boss_phases: &[],
+            },
+            EnemyType::Runner => EnemyStats {
+                name: "runner",
+                max_health: 50,
+                movement_speed: 1800,
+                attack_damage: 1,
+                attack_radius: 48,
+                attack_speed: 8,
+                texture_path: constants::TEXTURE_RUNNER_ENEMY_FRONT,
+                targets_towers: false,
+                is_ranged: false,
+                is_flying: false,
+                boss_textures: None,
+                boss_phases: &[],
+            },
+            EnemyType::Brute => EnemyStats {
+                name: "brute",
+                max_health: 400,
+                movement_speed: 450,
+                attack_damage: 8,
+                attack_radius: 64,
+                attack_speed: 32,
+                texture_path: constants::TEXTURE_BRUTE_ENEMY_FRONT,
+                targets_towers: true,
+                is_ranged: false,
+                is_flying: false,
+                boss_textures: None,
+                boss_phases: &[],
+            },
+            EnemyType::Archer => EnemyStats {
+                name: "archer",
+                max_health: 80,
+                movement_speed: 700,
+                attack_damage: 5,
+                attack_radius: 192,
+                attack_speed: 48,
+                texture_path: constants::TEXTURE_ARCHER_ENEMY_FRONT,
+                targets_towers: false,
+                is_ranged: true,
+                is_flying: false,
+                boss_textures: None,
+                boss_phases: &[],
+            },
+            EnemyType::Bat => EnemyStats {
+                name: "bat",
+                max_health: 60,
+                movement_speed: 1200,
+                attack_damage: 1,
+                attack_radius: 48,
+                attack_speed: 16,
+                texture_path: constants::TEXTURE_BAT_ENEMY_FRONT,
+                targets_towers: false,
+                is_ranged: false,
+                is_flying: true,
+                boss_textures: None,
+                boss_phases: &[],
+            },
+            EnemyType::Ogre => EnemyStats {
+                name: "ogre",
+                max_health: 3000,
+                movement_speed: 300,
+                attack_damage: 20,
+                attack_radius: 96,
+                attack_speed: 32,
+                texture_path: constants::TEXTURE_OGRE_BOSS_BOTTOM_LEFT,
+                targets_towers: false,
+                is_ranged: false,
+                is_flying: false,
+                boss_textures: Some([
+                    constants::TEXTURE_OGRE_BOSS_TOP_LEFT,
+                    constants::TEXTURE_OGRE_BOSS_TOP_RIGHT,
+                    constants::TEXTURE_OGRE_BOSS_BOTTOM_LEFT,
+                    constants::TEXTURE_OGRE_BOSS_BOTTOM_RIGHT,
+                ]),
+                boss_phases: &[
+                    BossPhase {
+                        health_threshold: 0.66,
+                        movement_speed: 300,
+                        attack_speed: 32,
+                        summon_type: Some(EnemyType::Goblin),
+                        summon_amount: 4,
+                        message: "the ogre calls for help",
+                    },
+                    BossPhase {
+                        health_threshold: 0.33,
+                        movement_speed: 700,
+                        attack_speed: 16,
+                        summon_type: Some(EnemyType::Runner),
+                        summon_amount: 3,
+                        message: "the ogre is enraged",
+                    },
+                ],
+            },
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<EnemyType> {
+        Self::ALL.iter().copied().find(|enemy_type| enemy_type.stats().name == name)
+    }
+}
 
 pub struct Enemy {
+    //unique for the run, unlike the enemy_vec position
+    pub id: u32,
+    pub enemy_type: EnemyType,
     pub final_path: Option<Vec<(usize, usize)>>,
     pub cost_total: f32,
     pub current_target: Option<(usize, usize)>,
     pub grid_index: (usize, usize),
     pub pixel_index: (u32, u32),
+    //pixel_index before the last simulation step, rendering blends between the two
+    pub previous_pixel_index: (u32, u32),
     pub max_health: u16,
     pub health: u16,
     pub movement_speed: u16,
@@ -23,190 +205,570 @@ pub struct Enemy {
     pub direction: player_manager::Direction,
     pub rect: sdl2::rect::Rect,
     pub texture_path: String,
+    //boss only, rect and texture_path are the bottom left part
+    pub boss_parts: Option<BossParts>,
+    pub boss_phase: u8,
+    //wall being broken when there is no open path to the target
+    pub siege_target: Option<(usize, usize)>,
+    //queued a* search, see PathfindingManager::request_path
+    pub path_request: Option<u32>,
+    //slows, burns, poison and stuns, added with status_manager::apply_status
+    pub status_vec: Vec<status_manager::StatusEffect>,
 }
 
 pub struct EnemyManager {
     pub enemy_vec: Vec<Enemy>,
+    //enemy_vec indices by pixel_index, only valid right after update_enemy_grid
+    pub enemy_grid: spatial_manager::SpatialGrid,
+    pub next_enemy_id: u32,
 }
 
 impl EnemyManager {
     pub fn new () -> EnemyManager {
         let enemies = EnemyManager {
             enemy_vec: Vec::new(),
+            enemy_grid: spatial_manager::SpatialGrid::new(constants::SPATIAL_CELL_SIZE),
+            next_enemy_id: 0,
         };
         enemies
     }
 
+    pub fn update_status_effects(&mut self) {
+        for enemy in &mut self.enemy_vec {
+            let damage = status_manager::tick_statuses(&mut enemy.status_vec);
+            enemy.health = enemy.health.saturating_sub(damage);
+        }
+    }
+
+    pub fn update_enemy_grid(&mut self) {
+        self.enemy_grid.rebuild(self.enemy_vec.iter().map(|enemy| (
+            enemy.pixel_index.0 as i32,
+            enemy.pixel_index.1 as i32
+        )));
+    }
+
     pub fn place_enemy(
         &mut self, 
         game: &mut game_manager::GameManager,
         temp_tile: &level_manager::LevelTile,
-        tile_data: level_manager::TileData,
+        enemy_type: EnemyType,
         index: (usize, usize),
     ) {
-        match tile_data {
-            TileData::Goblin => {
-                let temp_enemy = self::Enemy {
-                    final_path: None,
-                    cost_total: 0.0,
-                    movement_speed: constants::ENEMY_GOBLIN_SPEED,
-                    attack_damage: constants::ENEMY_GOBLIN_DAMAGE,
-                    attack_radius: constants::ENEMY_GOBLIN_RADIUS,
-                    attack_speed: constants::ENEMY_GOBLIN_ATTACK_SPEED,
-                    max_health: constants::ENEMY_GOBLIN_HEALTH,
-                    health: constants::ENEMY_GOBLIN_HEALTH,
-                    current_target: None,
-                    grid_index: index,
-                    pixel_index: (
-                        index.0 as u32 * constants::TILE_SIZE,
-                        index.1 as u32 * constants::TILE_SIZE
-                    ),
-                    direction: player_manager::Direction::Down,
-                    rect: sdl2::rect::Rect::new(
-                        temp_tile.rect.x(),
-                        temp_tile.rect.y(),
-                        constants::TILE_SIZE,
-                        constants::TILE_SIZE
-                    ),
-                    texture_path: constants::TEXTURE_GOBLIN_ENEMY_FRONT.to_string(),
-                };
-                self.enemy_vec.push(temp_enemy);
-            },
-            _=> {
-                let temp_enemy = self::Enemy {
-                    final_path: None,
-                    cost_total: 0.0,
-                    movement_speed: 1,
-                    attack_damage: 1,
-                    attack_radius: 1,
-                    attack_speed: 1,
-                    max_health: 1,
-                    health: 1,
-                    current_target: None,
-                    grid_index: index,
-                    pixel_index: (
-                        index.0 as u32 * constants::TILE_SIZE,
-                        index.1 as u32 * constants::TILE_SIZE
-                    ),
-                    direction: player_manager::Direction::Down,
-                    rect: sdl2::rect::Rect::new(
-                        temp_tile.rect.x(),
-                        temp_tile.rect.y(),
-                        constants::TILE_SIZE,
-                        constants::TILE_SIZE
-                    ),
-                    texture_path: constants::TEXTURE_DEFAULT.to_string(),
-                };
-                self.enemy_vec.push(temp_enemy);
+        let stats = enemy_type.stats();
+        let temp_enemy = self::Enemy {
+            id: self.next_enemy_id,
+            enemy_type,
+            final_path: None,
+            cost_total: 0.0,
+            movement_speed: stats.movement_speed,
+            attack_damage: stats.attack_damage,
+            attack_radius: stats.attack_radius,
+            attack_speed: stats.attack_speed,
+            max_health: stats.max_health,
+            health: stats.max_health,
+            current_target: None,
+            grid_index: index,
+            pixel_index: (
+                index.0 as u32 * constants::TILE_SIZE,
+                index.1 as u32 * constants::TILE_SIZE
+            ),
+            previous_pixel_index: (
+                index.0 as u32 * constants::TILE_SIZE,
+                index.1 as u32 * constants::TILE_SIZE
+            ),
+            direction: player_manager::Direction::Down,
+            rect: sdl2::rect::Rect::new(
+                temp_tile.rect.x(),
+                temp_tile.rect.y(),
+                constants::TILE_SIZE,
+                constants::TILE_SIZE
+            ),
+            texture_path: stats.texture_path.to_string(),
+            boss_parts: Self::create_boss_parts(enemy_type),
+            boss_phase: 0,
+            siege_target: None,
+            path_request: None,
+            status_vec: Vec::new(),
+        };
+        self.next_enemy_id += 1;
+        self.enemy_vec.push(temp_enemy);
+    }
+
+    pub fn create_boss_parts(enemy_type: EnemyType) -> Option<BossParts> {
+        let boss_textures = enemy_type.stats().boss_textures?;
+        let tile_rect = sdl2::rect::Rect::new(
+            0,
+            0,
+            constants::TILE_SIZE,
+            constants::TILE_SIZE
+        );
+        let boss_parts = BossParts {
+            bottom_right_rect: tile_rect,
+            top_left_rect: tile_rect,
+            top_right_rect: tile_rect,
+            texture_path_bottom_right: boss_textures[3].to_string(),
+            texture_path_top_left: boss_textures[0].to_string(),
+            texture_path_top_right: boss_textures[1].to_string(),
+        };
+        Some(boss_parts)
+    }
+
+    pub fn update_bosses(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &mut level_manager::LevelManager,
+    ) {
+        let mut summons: Vec<(EnemyType, (usize, usize))> = Vec::new();
+
+        for enemy in &mut self.enemy_vec {
+            let stats = enemy.enemy_type.stats();
+            if enemy.boss_parts.is_none() || enemy.health == 0 {
+                continue;
+            }
+            let health_percentage = enemy.health as f64 / enemy.max_health as f64;
+
+            while let Some(phase) = stats.boss_phases.get(enemy.boss_phase as usize) {
+                if health_percentage > phase.health_threshold {
+                    break;
+                }
+                enemy.boss_phase += 1;
+                enemy.movement_speed = phase.movement_speed;
+                enemy.attack_speed = phase.attack_speed;
+                game.create_message(phase.message.to_string(), 256);
+
+                if let Some(summon_type) = phase.summon_type {
+                    let summon_tiles = Self::summon_tiles(enemy.grid_index, level);
+                    for summon_index in 0..phase.summon_amount as usize {
+                        summons.push((summon_type, summon_tiles[summon_index % summon_tiles.len()]));
+                    }
+                }
             }
         }
+
+        for (summon_type, index) in summons {
+            let temp_tile = &level.level_vec[index.0][index.1];
+            self.place_enemy(game, temp_tile, summon_type, index);
+        }
+    }
+
+    //walkable tiles around a boss, falls back to the boss tile itself
+    fn summon_tiles(
+        grid_index: (usize, usize),
+        level: &level_manager::LevelManager,
+    ) -> Vec<(usize, usize)> {
+        let mut summon_tiles = Vec::new();
+        for dx in -1..=2_isize {
+            for dy in -2..=1_isize {
+                let x = grid_index.0 as isize + dx;
+                let y = grid_index.1 as isize + dy;
+                if x < 0 || y < 0 {
+                    continue;
+                }
+                if let Some(temp_tile) = level.level_vec.get(x as usize)
+                    .and_then(|col| col.get(y as usize)) {
+                    if utilities::tile_pathable(temp_tile) {
+                        summon_tiles.push((x as usize, y as usize));
+                    }
+                }
+            }
+        }
+        if summon_tiles.is_empty() {
+            summon_tiles.push(grid_index);
+        }
+        summon_tiles
     }
 
     pub fn render_enemies(
         &mut self,
-        game: &mut game_manager::GameManager, 
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>, 
         gui_manager: &mut gui_manager::GUIManager,
     ) -> Result<(), String> {
         for enemy in &mut self.enemy_vec {
-            enemy.rect.set_x(enemy.pixel_index.0 as i32 - game.cam_x);
-            enemy.rect.set_y(enemy.pixel_index.1 as i32 - game.cam_y);
+            let position = utilities::interpolate(
+                (enemy.previous_pixel_index.0 as i32, enemy.previous_pixel_index.1 as i32),
+                (enemy.pixel_index.0 as i32, enemy.pixel_index.1 as i32),
+                game.interpolation
+            );
+            enemy.rect.set_x(position.0 - game.cam_x);
+            enemy.rect.set_y(position.1 - game.cam_y);
 
             let texture = tex_man.load(&enemy.texture_path)?;
+            //enemy sprites face right, mirror them when walking left
+            let is_facing_left = enemy.boss_parts.is_none() && matches!(
+                enemy.direction,
+                player_manager::Direction::Left
+                | player_manager::Direction::UpLeft
+                | player_manager::Direction::DownLeft
+            );
 
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &texture,
                 None,
                 enemy.rect,
                 0.0,
                 None,
-                false,
+                is_facing_left,
                 false,
             )?;
+            if status_manager::has_status(&enemy.status_vec, status_manager::StatusKind::Burn) {
+                canvas.set_draw_color(constants::COLOR_BURN);
+                canvas.fill_rect(enemy.rect)?;
+            }
+            if let Some(boss_parts) = &mut enemy.boss_parts {
+                boss_parts.bottom_right_rect.set_x(enemy.rect.x() + constants::TILE_SIZE as i32);
+                boss_parts.bottom_right_rect.set_y(enemy.rect.y());
+                boss_parts.top_left_rect.set_x(enemy.rect.x());
+                boss_parts.top_left_rect.set_y(enemy.rect.y() - constants::TILE_SIZE as i32);
+                boss_parts.top_right_rect.set_x(enemy.rect.x() + constants::TILE_SIZE as i32);
+                boss_parts.top_right_rect.set_y(enemy.rect.y() - constants::TILE_SIZE as i32);
+
+                let texture_bottom_right = tex_man.load(&boss_parts.texture_path_bottom_right)?;
+                let texture_top_left = tex_man.load(&boss_parts.texture_path_top_left)?;
+                let texture_top_right = tex_man.load(&boss_parts.texture_path_top_right)?;
+                canvas.copy(&texture_bottom_right, None, boss_parts.bottom_right_rect)?;
+                canvas.copy(&texture_top_left, None, boss_parts.top_left_rect)?;
+                canvas.copy(&texture_top_right, None, boss_parts.top_right_rect)?;
+                //bosses use the screen space bar from render_boss_health_bars
+                continue;
+            }
             if enemy.health < enemy.max_health {
-                gui_manager.render_health_bar_enemy(game, enemy);
+                gui_manager.render_health_bar_enemy(canvas, enemy);
+            }
+            if !enemy.status_vec.is_empty() {
+                gui_manager.render_status_icons_enemy(canvas, enemy);
             }
         }
         Ok(())
     }
-    pub fn repath_all_enemies (&mut self) {
+    pub fn repath_all_enemies (
+        &mut self,
+        pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        changed_tile: (usize, usize),
+    ) {
+        //flow fields only recompute around the changed tile on the next move
+        pathfinding_manager.mark_dirty(changed_tile);
         for enemy in &mut self.enemy_vec {
             //add check if path intersects placed object
             //*called in building manager
             enemy.final_path = None;
             enemy.current_target = None;
+            enemy.siege_target = None;
+            enemy.path_request = None;
         }
     }
 
     pub fn move_enemies (
         &mut self,
-        events: &mut event_manager::EventManager,
         game: &mut game_manager::GameManager,
         level: &mut level_manager::LevelManager, 
+        towers: &tower_manager::TowerManager,
         pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
     ) {
+        pathfinding_manager.update_flow_fields(&game.target_vec, &level.level_vec);
+        let path_requests: Vec<u32> = self.enemy_vec.iter()
+            .filter_map(|enemy| enemy.path_request)
+            .collect();
+        pathfinding_manager.retain_path_requests(&path_requests);
+        pathfinding_manager.process_path_queue(&level.level_vec);
+        let mut broken_walls: Vec<(usize, usize)> = Vec::new();
+
         for enemy in &mut self.enemy_vec {
+            enemy.previous_pixel_index = enemy.pixel_index;
             let is_targets: bool = !game.target_vec.is_empty();
+            let stats = enemy.enemy_type.stats();
 
-            if let Some(enemy_path) = enemy.final_path.take().as_mut() {
-                if enemy_path.is_empty() {
-                    enemy.final_path = None;
-                    return;
-}
-                let speed: u16 = 
-                (enemy.movement_speed as f64 * (events.delta_time as f64)
-                    .max(constants::MIN_GAME_RATE)) as u16;
+            //ripe tomatoes are sticky
+            let current_tile = &level.level_vec[enemy.grid_index.0][enemy.grid_index.1];
+            if !stats.is_flying
+            && current_tile.tile_type == level_manager::TileKind::FieldHarvestable
+            && current_tile.tile_data == level_manager::TileData::Tomatoes {
+                status_manager::apply_status(&mut enemy.status_vec, status_manager::StatusEffect {
+                    kind: status_manager::StatusKind::Slow,
+                    strength: constants::TOMATO_SLOW,
+                    time: constants::TOMATO_SLOW_DURATION,
+                });
+            }
+            if status_manager::stun_time(&enemy.status_vec) > 0 {
+                continue;
+            }
+
+            //SIEGE
+            if let Some(wall) = enemy.siege_target {
+                let wall_tile = &mut level.level_vec[wall.0][wall.1];
+                if wall_tile.tile_type != level_manager::TileKind::Wall {
+                    enemy.siege_target = None;
+                }
+                else {
+                    if game.frame_time % enemy.attack_speed as u32 == 0 {
+                        wall_tile.health = wall_tile.health.saturating_sub(enemy.attack_damage as u16);
+                        if wall_tile.health == 0 {
+                            wall_tile.tile_type = level_manager::TileKind::Floor;
+                            wall_tile.prev_type = level_manager::TileKind::Wall;
+                            wall_tile.texture_path = constants::TEXTURE_TILE_FLOOR.to_string();
+                            broken_walls.push(wall);
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            if Self::is_holding_position(enemy) {
+                continue;
+            }
+
+            if let Some(enemy_path) = enemy.final_path.take().as_mut().filter(|path| !path.is_empty()) {
+                let speed = Self::step_speed(enemy, game, level);
 
                 let target_pixel_index = (
                     enemy_path[0].0 as u32 * constants::TILE_SIZE,
                     enemy_path[0].1 as u32 * constants::TILE_SIZE
                 );
-                let mut new_pixel_index: (u32, u32) = enemy.pixel_index;
-                let distance_to_target = (
-                    (target_pixel_index.0 as i32 - new_pixel_index.0 as i32).abs(),
-                    (target_pixel_index.1 as i32 - new_pixel_index.1 as i32).abs()
-                );
+                let dx = target_pixel_index.0 as f64 - enemy.pixel_index.0 as f64;
+                let dy = target_pixel_index.1 as f64 - enemy.pixel_index.1 as f64;
+                let distance_to_target = (dx * dx + dy * dy).sqrt();
 
-                if distance_to_target.0 <= speed as i32 
-                && distance_to_target.1 <= speed as i32 {
+                if distance_to_target > 0.0 {
+                    enemy.direction = Self::direction_from_delta(dx, dy);
+                }
+                if distance_to_target <= speed as f64 {
                     enemy.pixel_index = target_pixel_index;
-                    enemy.grid_index = (
-                        (enemy.pixel_index.0 / constants::TILE_SIZE) as usize,
-                        (enemy.pixel_index.1 / constants::TILE_SIZE) as usize
-                    );
                     enemy_path.remove(0);
                 }
                 else {
-                    if new_pixel_index.0 > target_pixel_index.0 {
-                        new_pixel_index.0 -= speed as u32;
+                    //straight line towards the waypoint so diagonals aren't faster
+                    let step = speed as f64 / distance_to_target;
+                    enemy.pixel_index = (
+                        (enemy.pixel_index.0 as f64 + dx * step).round() as u32,
+                        (enemy.pixel_index.1 as f64 + dy * step).round() as u32
+                    );
+                }
+                //smoothed paths leave the grid, grid_index is the closest tile
+                enemy.grid_index = (
+                    ((enemy.pixel_index.0 + constants::TILE_SIZE / 2) / constants::TILE_SIZE) as usize,
+                    ((enemy.pixel_index.1 + constants::TILE_SIZE / 2) / constants::TILE_SIZE) as usize
+                );
+                if !enemy_path.is_empty() {
+                    enemy.final_path = Some(enemy_path.to_vec());
+                }
+            } 
+
+            if enemy.final_path.is_some() || !is_targets {
+                continue;
+            }
+            if stats.is_flying {
+                //flying enemies ignore walls so they can't share the flow fields
+                //searches are queued, hover in place until ours is done
+                if let Some(request_id) = enemy.path_request {
+                    if let Some(path) = pathfinding_manager.take_path(request_id) {
+                        enemy.path_request = None;
+                        if path.is_empty() {
+                            enemy.current_target = None;
+                        }
+                        else {
+                            enemy.final_path = Some(path);
+                        }
                     }
-                    else if new_pixel_index.0 < target_pixel_index.0 {
-                        new_pixel_index.0 += speed as u32;
+                }
+                else if enemy.current_target.is_none() {
+                    let target = Self::choose_target(enemy, game, towers);
+                    enemy.path_request = Some(pathfinding_manager.request_path(enemy, target));
+                    enemy.current_target = Some(target);
+                }
+                continue;
+            }
+            let target = match enemy.current_target {
+                Some(target) => target,
+                None => {
+                    let target = Self::choose_target(enemy, game, towers);
+                    enemy.current_target = Some(target);
+                    target
+                }
+            };
+            //ground enemies follow the flow field a few tiles ahead and smooth that stretch
+            if let Some(next) = pathfinding_manager.next_step(target, enemy.grid_index, &level.level_vec) {
+                let mut lookahead = vec![enemy.grid_index, next];
+                while lookahead.len() <= constants::PATH_LOOKAHEAD {
+                    match pathfinding_manager.next_step(target, lookahead[lookahead.len() - 1], &level.level_vec) {
+                        Some(next) => lookahead.push(next),
+                        None => break,
+                    }
+                }
+                let smoothed = pathfinding_manager::PathfindingManager::smooth_path(
+                    &lookahead,
+                    &level.level_vec,
+                    false
+                );
+                enemy.final_path = Some(smoothed[1..].to_vec());
+            }
+            //no open route, head for the cheapest walls and break through
+            else if enemy.grid_index != target {
+                if let Some(next) = pathfinding_manager.next_siege_step(target, enemy.grid_index, &level.level_vec) {
+                    //can't squeeze past a wall corner either, go round by the open side or break the weaker one
+                    let sides: Vec<(usize, usize)> = [(next.0, enemy.grid_index.1), (enemy.grid_index.0, next.1)].into_iter()
+                        .filter(|side| *side != next && *side != enemy.grid_index)
+                        .collect();
+                    let side_wall = sides.iter().copied()
+                        .filter(|side| !utilities::tile_pathable(&level.level_vec[side.0][side.1]))
+                        .min_by_key(|side| level.level_vec[side.0][side.1].health);
+                    let open_side = sides.iter().copied()
+                        .find(|side| utilities::tile_pathable(&level.level_vec[side.0][side.1]));
+                    if let (Some(_), Some(open_side)) = (side_wall, open_side) {
+                        enemy.final_path = Some(vec![open_side]);
                     }
-                    if new_pixel_index.1 > target_pixel_index.1 {
-                        new_pixel_index.1 -= speed as u32;
+                    else if let Some(side_wall) = side_wall {
+                        enemy.siege_target = Some(side_wall);
                     }
-                    else if new_pixel_index.1 < target_pixel_index.1 {
-                        new_pixel_index.1 += speed as u32;
+                    else if utilities::tile_pathable(&level.level_vec[next.0][next.1]) {
+                        enemy.final_path = Some(vec![next]);
                     }
-                    enemy.pixel_index = new_pixel_index;
-                    if enemy.pixel_index.0 % 32 == 0 
-                    && enemy.pixel_index.1 % 32 == 0 {
-                        enemy.grid_index = (
-                            (enemy.pixel_index.0 / 32) as usize,
-                            (enemy.pixel_index.1 / 32) as usize
-                        );
+                    else {
+                        enemy.siege_target = Some(next);
                     }
                 }
-                enemy.final_path = Some(enemy_path.to_vec());
-            } 
-            else if !game.is_pathfinding && is_targets && enemy.current_target.is_none() {
-                let target = game.target_vec[game.frame_time as usize % game.target_vec.len()];
-                enemy.final_path = None;
-                pathfinding_manager.astar(enemy, target, &level.level_vec);
-                enemy.current_target = Some(target);
-                game.is_pathfinding = true;
             }
         }
+
+        for wall in broken_walls {
+            pathfinding_manager.mark_dirty(wall);
+            game.level_updated = true;
+            //enemies near the breach look for a new route
+            for enemy in &mut self.enemy_vec {
+                if enemy.grid_index.0.abs_diff(wall.0) <= constants::WALL_REPATH_RADIUS
+                && enemy.grid_index.1.abs_diff(wall.1) <= constants::WALL_REPATH_RADIUS {
+                    enemy.final_path = None;
+                    enemy.siege_target = None;
+                }
+            }
+        }
+    }
+
+    //ranged enemies hold position once their target is in range
+    fn is_holding_position(enemy: &Enemy) -> bool {
+        if !enemy.enemy_type.stats().is_ranged {
+            return false
+        }
+        let Some(target) = enemy.current_target else {
+            return false
+        };
+        let target_pixel_index = (
+            target.0 as i32 * constants::TILE_SIZE as i32,
+            target.1 as i32 * constants::TILE_SIZE as i32
+        );
+        tower_manager::TowerManager::is_within_area(
+            target_pixel_index,
+            (enemy.pixel_index.0 as i32, enemy.pixel_index.1 as i32),
+            enemy.attack_radius as i32
+        )
+    }
+
+    //pixels moved along final_path in one simulation step
+    fn step_speed(
+        enemy: &Enemy,
+        game: &game_manager::GameManager,
+        level: &level_manager::LevelManager,
+    ) -> u16 {
+        //slower on costly terrain, flying enemies don't touch the ground
+        let terrain_scale = if enemy.enemy_type.stats().is_flying {
+            1.0
+        }
+        else {
+            let current_tile = &level.level_vec[enemy.grid_index.0][enemy.grid_index.1];
+            constants::TILE_COST_GRASS as f64 / utilities::tile_cost(current_tile) as f64
+        };
+        let slow_scale = status_manager::speed_percent(&enemy.status_vec) as f64 / 100.0;
+        ((enemy.movement_speed as f64 * game.delta_time
+            .max(constants::MIN_GAME_RATE)) * terrain_scale * slow_scale).max(1.0) as u16
+    }
+
+    //pixel_index now and after each of the next steps, the same moves move_enemies makes along final_path
+    //terrain and slows are taken from now, a new path past the end of final_path isn't known yet
+    pub fn predict_pixel_indices(
+        enemy: &Enemy,
+        game: &game_manager::GameManager,
+        level: &level_manager::LevelManager,
+        steps: usize,
+    ) -> Vec<(u32, u32)> {
+        let mut pixel_index = enemy.pixel_index;
+        let mut predicted = vec![pixel_index];
+        let is_moving = enemy.siege_target.is_none() && !Self::is_holding_position(enemy);
+        let path: &[(usize, usize)] = match &enemy.final_path {
+            Some(final_path) if is_moving => final_path,
+            _ => &[],
+        };
+        let speed = Self::step_speed(enemy, game, level) as f64;
+        let stun_time = status_manager::stun_time(&enemy.status_vec) as usize;
+        let mut waypoint_index = 0;
+
+        for step in 0..steps {
+            if step < stun_time {
+                predicted.push(pixel_index);
+                continue;
+            }
+            if let Some(waypoint) = path.get(waypoint_index) {
+                let target_pixel_index = (
+                    waypoint.0 as u32 * constants::TILE_SIZE,
+                    waypoint.1 as u32 * constants::TILE_SIZE
+                );
+                let dx = target_pixel_index.0 as f64 - pixel_index.0 as f64;
+                let dy = target_pixel_index.1 as f64 - pixel_index.1 as f64;
+                let distance_to_target = (dx * dx + dy * dy).sqrt();
+                if distance_to_target <= speed {
+                    pixel_index = target_pixel_index;
+                    waypoint_index += 1;
+                }
+                else {
+                    let step = speed / distance_to_target;
+                    pixel_index = (
+                        (pixel_index.0 as f64 + dx * step).round() as u32,
+                        (pixel_index.1 as f64 + dy * step).round() as u32
+                    );
+                }
+            }
+            predicted.push(pixel_index);
+        }
+        predicted
+    }
+
+    //y grows downwards, mostly straight moves keep the straight sprite
+    fn direction_from_delta(dx: f64, dy: f64) -> player_manager::Direction {
+        let is_horizontal = dx.abs() > dy.abs() * 2.0;
+        let is_vertical = dy.abs() > dx.abs() * 2.0;
+        match (is_horizontal, is_vertical, dx < 0.0, dy < 0.0) {
+            (true, _, true, _) => player_manager::Direction::Left,
+            (true, _, false, _) => player_manager::Direction::Right,
+            (_, true, _, true) => player_manager::Direction::Up,
+            (_, true, _, false) => player_manager::Direction::Down,
+            (_, _, true, true) => player_manager::Direction::UpLeft,
+            (_, _, false, true) => player_manager::Direction::UpRight,
+            (_, _, true, false) => player_manager::Direction::DownLeft,
+            (_, _, false, false) => player_manager::Direction::DownRight,
+        }
+    }
+
+    fn choose_target(
+        enemy: &Enemy,
+        game: &game_manager::GameManager,
+        towers: &tower_manager::TowerManager,
+    ) -> (usize, usize) {
+        match Self::nearest_tower(enemy, towers) {
+            Some(tower_target) if enemy.enemy_type.stats().targets_towers => tower_target,
+            _ => game.target_vec[game.frame_time as usize % game.target_vec.len()],
+        }
+    }
+
+    fn nearest_tower(
+        enemy: &Enemy,
+        towers: &tower_manager::TowerManager,
+    ) -> Option<(usize, usize)> {
+        towers.tower_vec.iter()
+            .filter(|tower| tower.health != 0)
+            .map(|tower| tower.bottom_index)
+            .min_by_key(|bottom_index| {
+                let dx = bottom_index.0 as i64 - enemy.grid_index.0 as i64;
+                let dy = bottom_index.1 as i64 - enemy.grid_index.1 as i64;
+                dx * dx + dy * dy
+            })
     }
 }
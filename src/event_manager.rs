@@ -4,7 +4,6 @@ use sdl2::mouse::MouseButton;
 
 use crate::game_manager;
 use crate::button_manager;
-use crate::gui_manager;
 use crate::player_manager;
 use crate::tower_manager;
 
@@ -17,13 +16,26 @@ pub struct EventManager {
     pub delta_time: f64,
     pub screen_size: (i32, i32),
     pub mouse_point: sdl2::rect::Point,
-    pub level_updated: bool,
     pub menu_settings: bool,
+    pub menu_level_select: bool,
+    //set by the level select menu, main loads it before the next frame
+    pub level_selected: Option<String>,
+    //level file to open in the editor, may not exist yet for new levels
+    pub level_editing: Option<String>,
+    pub editor_closing: bool,
+    //a level file was written, the level select list is out of date
+    pub levels_changed: bool,
+    //keys pressed in editor mode, read by the editor each frame
+    pub editor_keys: Vec<(sdl2::keyboard::Keycode, sdl2::keyboard::Mod)>,
     pub menu_quit: bool,
     pub game_quit: bool,
     pub game_paused: bool,
     pub game_saving: bool,
     pub game_loading: bool,
+    pub replay_saving: bool,
+    pub replay_loading: bool,
+    //toggles fast forward on the playing replay, main flips it and clears this
+    pub replay_fast_forward: bool,
     pub up: bool,
     pub down: bool,
     pub left: bool,
@@ -32,7 +44,7 @@ pub struct EventManager {
 }
 
 impl EventManager {
-    pub fn new(sdl_context: &sdl2::Sdl, game: &mut game_manager::GameManager) -> EventManager {
+    pub fn new(sdl_context: &sdl2::Sdl, canvas: &sdl2::render::Canvas<sdl2::video::Window>) -> EventManager {
         let event = EventManager {  
             event_pump: sdl_context.event_pump().unwrap(),
             timer_subsystem: sdl_context.timer().unwrap(),
@@ -41,17 +53,25 @@ impl EventManager {
             performance_frequency: 0,
             delta_time: 0.0,
             screen_size: (
-                game.canvas.window().display_mode().unwrap().w,
-                game.canvas.window().display_mode().unwrap().h
+                canvas.window().display_mode().unwrap().w,
+                canvas.window().display_mode().unwrap().h
             ),
             mouse_point: sdl2::rect::Point::new(0, 0),
-            level_updated: false,
             menu_settings: false,
+            menu_level_select: false,
+            level_selected: None,
+            level_editing: None,
+            editor_closing: false,
+            levels_changed: false,
+            editor_keys: Vec::new(),
             menu_quit: false,
             game_quit: false,
             game_paused: true,
             game_saving: false,
             game_loading: false,
+            replay_saving: false,
+            replay_loading: false,
+            replay_fast_forward: false,
             up: false,
             down: false,
             left: false,
@@ -72,7 +92,6 @@ impl EventManager {
         towers: &mut tower_manager::TowerManager,
         seed_buttons: &mut button_manager::ButtonManager, 
         build_buttons: &mut button_manager::ButtonManager,
-        gui_manager: &mut gui_manager::GUIManager,
     ) {
 
         for event in self.event_pump.poll_iter() {
@@ -81,8 +100,8 @@ impl EventManager {
                     self.game_quit = true;
                     break
                 }
-                sdl2::event::Event::KeyDown { keycode: Some(keycode), .. } => {
-                    self.do_key_down(game, towers, seed_buttons, build_buttons, gui_manager, keycode);
+                sdl2::event::Event::KeyDown { keycode: Some(keycode), keymod, .. } => {
+                    self.do_key_down(game, towers, seed_buttons, build_buttons, keycode, keymod);
                     break
                 }, 
                 sdl2::event::Event::KeyUp {keycode: Some(keycode), .. } => {
@@ -106,10 +125,7 @@ impl EventManager {
                     win_event: sdl2::event::WindowEvent::Resized(width, height),
                     ..
                 } => {
-                    self.screen_size = (
-                        game.canvas.window().display_mode().unwrap().w,
-                        game.canvas.window().display_mode().unwrap().h
-                    );
+                    self.screen_size = (width, height);
                 }
                 _ => {}
             }
@@ -121,9 +137,25 @@ impl EventManager {
         towers: &mut tower_manager::TowerManager,
         seed_buttons: &mut button_manager::ButtonManager, 
         build_buttons: &mut button_manager::ButtonManager,
-        gui_manager: &mut gui_manager::GUIManager,
         keycode: sdl2::keyboard::Keycode,
+        keymod: sdl2::keyboard::Mod,
     ) {
+        if game.editor_mode {
+            let is_ctrl = keymod.intersects(sdl2::keyboard::Mod::LCTRLMOD | sdl2::keyboard::Mod::RCTRLMOD);
+            //the editor still moves with wasd and can mute, everything else is an editor key
+            let is_shared_key = matches!(
+                keycode,
+                sdl2::keyboard::Keycode::W
+                | sdl2::keyboard::Keycode::A
+                | sdl2::keyboard::Keycode::S
+                | sdl2::keyboard::Keycode::D
+                | sdl2::keyboard::Keycode::M
+            );
+            if is_ctrl || !is_shared_key {
+                self.editor_keys.push((keycode, keymod));
+                return
+            }
+        }
         match keycode {
             sdl2::keyboard::Keycode::L => {
                 self.menu_quit = !self.menu_quit;
@@ -146,6 +178,17 @@ impl EventManager {
                     self.game_loading = true;
                 }
             },
+            sdl2::keyboard::Keycode::K => {
+                if !self.replay_saving && !self.replay_loading {
+                    self.replay_saving = true;
+                }
+            },
+            sdl2::keyboard::Keycode::J => {
+                if self.game_paused && !self.replay_saving && !self.replay_loading {
+                    self.replay_loading = true;
+                }
+            },
+            sdl2::keyboard::Keycode::F => self.replay_fast_forward = true,
             sdl2::keyboard::Keycode::Escape => { 
                 self.menu_quit = !self.menu_quit;
                 if !self.menu_quit {
@@ -304,7 +347,7 @@ impl EventManager {
                     build_buttons.update_buttons(4, game);
                 }
             },
-            _ => gui_manager.create_message("invalid input".to_string(), 128),
+            _ => game.create_message("invalid input".to_string(), 128),
         }
     }
 
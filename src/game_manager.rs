@@ -1,4 +1,4 @@
-use crate::{level_manager, button_manager, player_manager, event_manager, texture_manager, constants, tower_manager, enemy_manager, gui_manager, projectile_manager, building_manager, pathfinding_manager, upgrade_manager, minimap_manager};
+use crate::{level_manager, button_manager, player_manager, event_manager, texture_manager, constants, tower_manager, enemy_manager, gui_manager, projectile_manager, building_manager, pathfinding_manager, upgrade_manager, minimap_manager, wave_manager, replay_manager};
 
 pub enum Movement {
     Up,
@@ -10,11 +10,11 @@ pub enum Movement {
 
 pub struct GameManager {
     pub placed: bool,
-    pub is_pathfinding: bool,
     pub build_mode: bool,
     pub seed_mode: bool,
     pub upgrade_mode: bool,
     pub preview_mode: bool,
+    pub editor_mode: bool,
     pub hovering_button: bool,
     pub current_seed: usize,
     pub current_build: usize,
@@ -23,18 +23,57 @@ pub struct GameManager {
     pub gold_amount: u32,
     pub cam_x: i32,
     pub cam_y: i32,
+    //counts simulation steps, timers and attack rates are measured in steps
     pub frame_time: u32,
     pub fps: u32,
     pub elapsed_seconds: f64,
-    pub canvas: sdl2::render::Canvas<sdl2::video::Window>,
+    //seconds the simulation advances on the next step
+    pub delta_time: f64,
+    //frame time not yet used up by simulation steps
+    pub step_accumulator: f64,
+    //0.0 to 1.0, how far rendering is between the last step and the next one
+    pub interpolation: f64,
+    pub level_updated: bool,
     pub mouse_point: sdl2::rect::Point,
     pub mouse_button: sdl2::mouse::MouseButton,
     pub target_vec: Vec<(usize, usize)>,
     pub base_location: Option<(usize, usize)>,
+    pub message_vec: Vec<gui_manager::Message>,
 }
 
 impl GameManager {
-    pub fn new(sdl_context: &sdl2::Sdl) -> GameManager {
+    pub fn new() -> GameManager {
+        let game = GameManager {  
+            placed: false,
+            seed_mode: false,
+            build_mode: false,
+            preview_mode: false,
+            editor_mode: false,
+            upgrade_mode: false,
+            hovering_button: false,
+            current_seed: usize::MAX,
+            current_build: usize::MAX,
+            carrot_amount: 0,
+            tomato_amount: 0,
+            gold_amount: constants::STARTING_GOLD,
+            cam_x: 0,
+            cam_y: 0,
+            frame_time: 1,
+            fps: 1,
+            elapsed_seconds: 0.1,
+            delta_time: constants::SIMULATION_STEP,
+            step_accumulator: 0.0,
+            interpolation: 0.0,
+            level_updated: false,
+            mouse_point: sdl2::rect::Point::new(0, 0),
+            mouse_button: sdl2::mouse::MouseButton::Unknown,
+            target_vec: Vec::new(),
+            base_location: None,
+            message_vec: Vec::new(),
+        };
+        game
+    }
+    pub fn create_canvas(sdl_context: &sdl2::Sdl) -> sdl2::render::Canvas<sdl2::video::Window> {
         let video_subsystem = sdl_context.video().unwrap();
         // let gl_attr = video_subsystem.gl_attr();
         //
@@ -64,45 +103,80 @@ impl GameManager {
             .expect("Failed to initialize canvas");
         canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
 
-        let game = GameManager {  
-            placed: false,
-            is_pathfinding: false,
-            seed_mode: false,
-            build_mode: false,
-            preview_mode: false,
-            upgrade_mode: false,
-            hovering_button: false,
-            current_seed: usize::MAX,
-            current_build: usize::MAX,
-            carrot_amount: 0,
-            tomato_amount: 0,
-            gold_amount: 9999,
-            cam_x: 0,
-            cam_y: 0,
-            frame_time: 1,
-            fps: 1,
-            elapsed_seconds: 0.1,
-            canvas,
-            mouse_point: sdl2::rect::Point::new(0, 0),
-            mouse_button: sdl2::mouse::MouseButton::Unknown,
-            target_vec: Vec::new(),
-            base_location: None,
+        canvas
+    }
+
+    pub fn prepare_background(canvas: &mut sdl2::render::Canvas<sdl2::video::Window>) {
+        canvas.set_draw_color(constants::COLOR_BACKGROUND);
+        canvas.clear(); 
+    }
+
+    pub fn create_message (&mut self, message: String, max_time: u16) {
+        let message = gui_manager::Message {
+            index: (0, 0),
+            rect: sdl2::rect::Rect::new(
+                0,
+                0,
+                constants::TILE_SIZE,
+                constants::TILE_SIZE
+            ),
+            texture_path: constants::TEXTURE_HUD_COIN.to_string(),
+            message_text: message,
+            time: 0,
+            max_time,
         };
-        game
+        self.message_vec.push(message);
     }
-    pub fn prepare_background(&mut self) {
-        self.canvas.set_draw_color(constants::COLOR_BACKGROUND);
-        self.canvas.clear(); 
+    pub fn create_unique_message (
+        &mut self,
+        unique_message: String,
+        max_time: u16
+    ) {
+        if !self.message_vec.iter().any(
+            |message| 
+            message.message_text == unique_message
+        ) {
+            let message = gui_manager::Message {
+                index: (0, 0),
+                rect: sdl2::rect::Rect::new(
+                    0,
+                    0,
+                    constants::TILE_SIZE,
+                    constants::TILE_SIZE
+                ),
+                texture_path: constants::TEXTURE_HUD_COIN.to_string(),
+                message_text: unique_message,
+                time: 0,
+                max_time,
+            };
+            self.message_vec.push(message);
+        }
     }
 
-    fn update_camera(&mut self, player: &mut player_manager::PlayerManager) {
-        self.cam_x = player.x;
-        self.cam_y = player.y;
+    //follows the player but stops at the level edges, small levels are centered
+    pub fn update_camera(
+        &mut self,
+        player: &mut player_manager::PlayerManager,
+        level: &level_manager::LevelManager,
+        events: &event_manager::EventManager,
+    ) {
+        let (level_width, level_height) = level.pixel_size();
+        self.cam_x = Self::clamp_camera(player.x, level_width, events.screen_size.0);
+        self.cam_y = Self::clamp_camera(player.y, level_height, events.screen_size.1);
     }
 
+    fn clamp_camera(position: i32, level_size: i32, screen_size: i32) -> i32 {
+        if level_size <= screen_size {
+            (level_size - screen_size) / 2
+        }
+        else {
+            position.clamp(0, level_size - screen_size)
+        }
+    }
+
+    //input pass, everything the player does this frame before the simulation steps
     pub fn update_game(
         &mut self, 
-        tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
         events: &mut event_manager::EventManager,
         player: &mut player_manager::PlayerManager, 
         level: &mut level_manager::LevelManager, 
@@ -116,39 +190,128 @@ impl GameManager {
         seed_buttons: &mut button_manager::ButtonManager, 
         build_buttons: &mut button_manager::ButtonManager,
         pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        wave_manager: &mut wave_manager::WaveManager,
+        replay_manager: &mut replay_manager::ReplayManager,
     ) {
         player.update_player(events, self, level);
-        self.update_camera(player);
-        buildings.update_buildings(
-            self,
-            events,
+        self.update_camera(player, level, events);
+        //a playing replay is the only input, the player can still look around
+        if !replay_manager.is_playing {
+            buildings.update_buildings(
+                self,
+                events,
+                level,
+                player,
+                towers,
+                upgrade_manager,
+                gui_manager,
+                minimap_manager,
+                seed_buttons,
+                build_buttons,
+                pathfinding_manager,
+                wave_manager,
+                replay_manager
+            );
+            projectile_manager::ProjectileManager::fire_player_projectile(self, events, player, replay_manager);
+            upgrade_manager.update_upgrade_menus(self, events, towers, replay_manager);
+        }
+
+        self.advance_simulation(
+            events.delta_time,
             level,
-            player,
             towers,
+            buildings,
             enemies,
-            upgrade_manager,
-            gui_manager,
-            minimap_manager,
-            seed_buttons,
-            build_buttons,
-            projectiles
+            projectiles,
+            pathfinding_manager,
+            wave_manager,
+            replay_manager
         );
+    }
+
+    //steps the simulation at a fixed rate, leftover frame time carries over to the next frame
+    pub fn advance_simulation(
+        &mut self,
+        frame_delta: f64,
+        level: &mut level_manager::LevelManager, 
+        towers: &mut tower_manager::TowerManager, 
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager, 
+        projectiles: &mut projectile_manager::ProjectileManager,
+        pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        wave_manager: &mut wave_manager::WaveManager,
+        replay_manager: &mut replay_manager::ReplayManager,
+    ) -> u32 {
+        let speed = if replay_manager.is_playing && replay_manager.fast_forward {
+            constants::REPLAY_FAST_FORWARD_SPEED
+        }
+        else {
+            1
+        };
+        let max_accumulated = constants::SIMULATION_STEP * (constants::MAX_SIMULATION_STEPS * speed) as f64;
+        self.step_accumulator = (self.step_accumulator + frame_delta.max(0.0) * speed as f64).min(max_accumulated);
+
+        let mut steps = 0;
+        while self.step_accumulator >= constants::SIMULATION_STEP {
+            self.delta_time = constants::SIMULATION_STEP;
+            self.update_simulation(
+                level,
+                towers,
+                buildings,
+                enemies,
+                projectiles,
+                pathfinding_manager,
+                wave_manager,
+                replay_manager
+            );
+            self.step_accumulator -= constants::SIMULATION_STEP;
+            steps += 1;
+        }
+        self.interpolation = self.step_accumulator / constants::SIMULATION_STEP;
+        steps
+    }
+
+    //one step of the game world, needs no window so it can run headless
+    pub fn update_simulation(
+        &mut self,
+        level: &mut level_manager::LevelManager, 
+        towers: &mut tower_manager::TowerManager, 
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager, 
+        projectiles: &mut projectile_manager::ProjectileManager,
+        pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        wave_manager: &mut wave_manager::WaveManager,
+        replay_manager: &mut replay_manager::ReplayManager,
+    ) {
+        for action in replay_manager.take_actions(self.frame_time) {
+            self.apply_action(
+                action,
+                level,
+                towers,
+                buildings,
+                enemies,
+                projectiles,
+                pathfinding_manager,
+                wave_manager
+            );
+        }
+        building_manager::BuildingManager::grow_farms(level);
         level_manager::LevelManager::check_attacks(
             self,
-            events,
-            player,
+            level,
             enemies,
             towers,
             buildings,
             projectiles,
-            gui_manager
+            pathfinding_manager
         );
-        enemies.move_enemies(events, self, level, pathfinding_manager);
-
-        minimap_manager.update_minimap(events, level, tex_man);
-
-        projectiles.check_projectile_hit(self, events, player, enemies);
-        upgrade_manager.update_upgrade_menus(self, events, towers);
+        wave_manager.update_waves(self, level, buildings, enemies);
+        enemies.move_enemies(self, level, towers, pathfinding_manager);
+        projectiles.move_projectiles(self, enemies);
+        projectiles.check_projectile_hit(enemies, towers, buildings);
+        enemies.update_status_effects();
+        enemies.update_bosses(self, level);
+        buildings.regen_buildings();
 
         //not to worry seems to do good with rust compiler
         self.delete_all_dead(
@@ -156,13 +319,62 @@ impl GameManager {
             enemies,
             towers,
             buildings,
-            projectiles,
-            gui_manager
+            projectiles
         );
+        self.frame_time += 1;
+    }
+
+    //the only way player input reaches the simulation, live and replayed input both land here
+    pub fn apply_action(
+        &mut self,
+        action: replay_manager::ReplayAction,
+        level: &mut level_manager::LevelManager, 
+        towers: &mut tower_manager::TowerManager, 
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager, 
+        projectiles: &mut projectile_manager::ProjectileManager,
+        pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+        wave_manager: &wave_manager::WaveManager,
+    ) {
+        match action {
+            replay_manager::ReplayAction::Build { build, index } => buildings.place_build(
+                self,
+                level,
+                towers,
+                enemies,
+                pathfinding_manager,
+                wave_manager,
+                build,
+                index
+            ),
+            replay_manager::ReplayAction::Seed { seed, index } => building_manager::BuildingManager::use_seed(
+                self,
+                level,
+                pathfinding_manager,
+                seed,
+                index
+            ),
+            replay_manager::ReplayAction::Shoot { start, target } => projectiles.spawn_player_projectile(
+                start,
+                start,
+                target
+            ),
+            replay_manager::ReplayAction::Upgrade { index, path } => towers.upgrade_tower(
+                self,
+                level,
+                index,
+                path
+            ),
+            replay_manager::ReplayAction::Target { index, targeting_mode } => towers.set_targeting_mode(
+                index,
+                targeting_mode
+            ),
+        }
     }
 
     pub fn render_game(
         &mut self, 
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>, 
         events: &mut event_manager::EventManager,
         player: &mut player_manager::PlayerManager, 
@@ -176,58 +388,65 @@ impl GameManager {
         minimap_manager: &mut minimap_manager::MinimapManager,
         seed_buttons: &mut button_manager::ButtonManager, 
         build_buttons: &mut button_manager::ButtonManager,
+        wave_manager: &mut wave_manager::WaveManager,
     ) {
+        minimap_manager.update_minimap(self, level, tex_man);
 
-
-        level.render_level(self, tex_man, player, events).unwrap();
+        level.render_level(self, canvas, tex_man, player, events).unwrap();
         enemy_manager::EnemyManager::render_enemies(
             enemies,
             self,
+            canvas,
             tex_man,
             gui_manager
         ).unwrap(); 
         projectile_manager::ProjectileManager::render_projectiles(
             projectiles,
             self,
-            tex_man,
-            events
+            canvas,
+            tex_man
         ).unwrap();
         tower_manager::TowerManager::render_towers(
             towers,
             self,
+            canvas,
             tex_man,
             gui_manager
         ).unwrap();
         buildings.render_buildings(
             self,
+            canvas,
             tex_man,
             gui_manager
         );
-        player.render_player(events, self, tex_man).unwrap();
-        gui_manager.render_preview(self, tex_man);
+        player.render_player(events, self, canvas, tex_man).unwrap();
+        gui_manager.render_preview(self, canvas, tex_man);
 
         minimap_manager.render_minimap(
-            self,
+            canvas,
             level,
             tex_man,
             player
         );
 
-        upgrade_manager.render_upgrade_menus(self);
+        upgrade_manager.render_upgrade_menus(self, canvas).unwrap();
         seed_buttons.render_seed_buttons(
             player,
             tex_man,
             events,
-            self
+            self,
+            canvas
         ).unwrap();
         build_buttons.render_build_buttons(
             player,
             tex_man,
             events,
-            self
+            self,
+            canvas
         ).unwrap();
-        gui_manager.render_inventory_hud(events, self, tex_man);
-        gui_manager.render_messages(self, events, tex_man);
+        gui_manager.render_inventory_hud(events, self, canvas, tex_man, wave_manager);
+        gui_manager.render_boss_health_bars(canvas, events, enemies).unwrap();
+        gui_manager.render_messages(self, canvas, events, tex_man);
     }
     pub fn delete_all_dead (
         &mut self,
@@ -236,7 +455,6 @@ impl GameManager {
         towers: &mut tower_manager::TowerManager,
         buildings: &mut building_manager::BuildingManager,
         projectiles: &mut projectile_manager::ProjectileManager,
-        gui_manager: &mut gui_manager::GUIManager,
     ) {
         for enemy_index in (0..enemies.enemy_vec.len()).rev() {
             let enemy = &mut enemies.enemy_vec[enemy_index];
@@ -319,7 +537,7 @@ impl GameManager {
                     //ALLOW PLAYER TIME TO REBUILD
                     self.gold_amount = 0;
                     buildings.base_created = false;
-                    gui_manager.create_message("base destroyed, time to rebuild".to_string(), 256);
+                    self.create_message("base destroyed, time to rebuild".to_string(), 256);
                 }
                 buildings.building_vec.remove(building_index);
             }
@@ -3,8 +3,11 @@ use crate::constants;
 use crate::event_manager;
 use crate::game_manager;
 use crate::enemy_manager;
+use crate::status_manager;
 use crate::texture_manager;
 use crate::tower_manager;
+use crate::utilities;
+use crate::wave_manager;
 
 pub struct PreviewGUI {
     pub index: (usize, usize),
@@ -16,6 +19,8 @@ pub struct PreviewGUI {
     pub texture_path_bottom_right: String,
     pub texture_path_top_left: String,
     pub texture_path_top_right: String,
+    //placement would seal the path to the base, drawn red
+    pub is_blocked: bool,
 }
 
 pub struct Message {
@@ -41,7 +46,6 @@ pub struct GUI {
 pub struct GUIManager <'a> {
     pub healthbar_vec: Vec<GUI>,
     pub inventory_vec: Vec<HUD>,
-    pub message_vec: Vec<Message>,
     pub preview: PreviewGUI,
     pub font: &'a sdl2::ttf::Font<'a, 'a>,
     pub texture_creator: sdl2::render::TextureCreator<sdl2::video::WindowContext>,
@@ -49,7 +53,7 @@ pub struct GUIManager <'a> {
 }
 
 impl<'a> GUIManager<'a> {
-    pub fn new (game: &mut game_manager::GameManager, font: &'a sdl2::ttf::Font<'a, 'a>,
+    pub fn new (canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, font: &'a sdl2::ttf::Font<'a, 'a>,
 ) -> Self {
         let preview = PreviewGUI {
             index: (0, 0),
@@ -81,60 +85,18 @@ impl<'a> GUIManager<'a> {
             texture_path_bottom_right: "".to_string(),
             texture_path_top_left: "".to_string(),
             texture_path_top_right: "".to_string(),
+            is_blocked: false,
         };
         let gui = GUIManager {
             healthbar_vec: Vec::new(),
             inventory_vec: Vec::new(),
-            message_vec: Vec::new(),
             preview,
             font,
-            texture_creator: game.canvas.texture_creator(),
+            texture_creator: canvas.texture_creator(),
             font_path: std::path::Path::new(&constants::FONT_PATH),
         };
         gui
     }
-    pub fn create_message (&mut self, message: String, max_time: u16) {
-        let message = Message {
-            index: (0, 0),
-            rect: sdl2::rect::Rect::new(
-                0,
-                0,
-                constants::TILE_SIZE,
-                constants::TILE_SIZE
-            ),
-            texture_path: constants::TEXTURE_HUD_COIN.to_string(),
-            message_text: message,
-            time: 0,
-            max_time,
-        };
-        self.message_vec.push(message);
-    }
-    pub fn create_unique_message (
-        &mut self,
-        unique_message: String,
-        max_time: u16
-    ) {
-        if !self.message_vec.iter().any(
-            |message| 
-            message.message_text == unique_message
-        ) {
-            let message = Message {
-                index: (0, 0),
-                rect: sdl2::rect::Rect::new(
-                    0,
-                    0,
-                    constants::TILE_SIZE,
-                    constants::TILE_SIZE
-                ),
-                texture_path: constants::TEXTURE_HUD_COIN.to_string(),
-                message_text: unique_message,
-                time: 0,
-                max_time,
-            };
-            self.message_vec.push(message);
-        }
-    }
-
     pub fn create_inventory_hud (&mut self, game: &mut game_manager::GameManager) {
         let coins = HUD {
             index: (0, 0),
@@ -197,14 +159,27 @@ impl<'a> GUIManager<'a> {
 
         };
         self.inventory_vec.push(delta_time);
+        let wave = HUD {
+            index: (0, 0),
+            rect: sdl2::rect::Rect::new(
+                0,
+                0,
+                constants::TILE_SIZE,
+                constants::TILE_SIZE
+            ),
+            texture_path: constants::TEXTURE_DEFAULT.to_string(),
+
+        };
+        self.inventory_vec.push(wave);
 
     }
     pub fn render_preview (
         &mut self,
         game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>
     ) -> Result<(), String> {
-        if game.preview_mode && (game.build_mode || game.seed_mode) {
+        if game.preview_mode && (game.build_mode || game.seed_mode || game.editor_mode) {
             if self.preview.texture_path_bottom_left.len() > 1 {
                 self.preview.bottom_left_rect.set_x(
                     self.preview.index.0 as i32 * constants::TILE_SIZE as i32 - game.cam_x
@@ -215,7 +190,7 @@ impl<'a> GUIManager<'a> {
 
                 let bottom_left_texture = tex_man.load(&self.preview.texture_path_bottom_left)?;
 
-                game.canvas.copy_ex(
+                canvas.copy_ex(
                     &bottom_left_texture, // Texture object
                     None,      // source rect
                     self.preview.bottom_left_rect,     // destination rect
@@ -237,7 +212,7 @@ impl<'a> GUIManager<'a> {
 
                 let top_left_texture = tex_man.load(&self.preview.texture_path_top_left)?;
 
-                game.canvas.copy_ex(
+                canvas.copy_ex(
                     &top_left_texture, // Texture object
                     None,      // source rect
                     self.preview.top_left_rect,     // destination rect
@@ -260,7 +235,7 @@ impl<'a> GUIManager<'a> {
 
                 let bottom_right_texture = tex_man.load(&self.preview.texture_path_bottom_right)?;
 
-                game.canvas.copy_ex(
+                canvas.copy_ex(
                     &bottom_right_texture, // Texture object
                     None,      // source rect
                     self.preview.bottom_right_rect,     // destination rect
@@ -286,7 +261,7 @@ impl<'a> GUIManager<'a> {
 
                 let top_right_texture = tex_man.load(&self.preview.texture_path_top_right)?;
 
-                game.canvas.copy_ex(
+                canvas.copy_ex(
                     &top_right_texture, // Texture object
                     None,      // source rect
                     self.preview.top_right_rect,     // destination rect
@@ -296,23 +271,37 @@ impl<'a> GUIManager<'a> {
                     false,     // flip vertical
                 )?;
             }
+            if self.preview.is_blocked && game.build_mode {
+                canvas.set_draw_color(constants::COLOR_PREVIEW_BLOCKED);
+                let preview_parts = [
+                    (&self.preview.texture_path_bottom_left, self.preview.bottom_left_rect),
+                    (&self.preview.texture_path_top_left, self.preview.top_left_rect),
+                    (&self.preview.texture_path_bottom_right, self.preview.bottom_right_rect),
+                    (&self.preview.texture_path_top_right, self.preview.top_right_rect),
+                ];
+                for (texture_path, rect) in preview_parts {
+                    if texture_path.len() > 1 {
+                        canvas.fill_rect(rect)?;
+                    }
+                }
+            }
 
         }
         Ok(())
     }
     pub fn render_health_bar_enemy (&mut self, 
-        game: &mut game_manager::GameManager, 
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         enemy: &enemy_manager::Enemy) {
         //TODO: match to enemy type
         let max_health = sdl2::rect::Rect::new(
             enemy.rect.x() 
             + (enemy.rect.width() 
-            - constants::ENEMY_GOBLIN_HEALTH_BAR_WIDTH) as i32 
+            - constants::ENEMY_HEALTH_BAR_WIDTH) as i32 
             / 2, 
             enemy.rect.y() 
-            - constants::ENEMY_GOBLIN_HEALTH_BAR_HEIGHT as i32, 
-            constants::ENEMY_GOBLIN_HEALTH_BAR_WIDTH, 
-            constants::ENEMY_GOBLIN_HEALTH_BAR_HEIGHT);
+            - constants::ENEMY_HEALTH_BAR_HEIGHT as i32, 
+            constants::ENEMY_HEALTH_BAR_WIDTH, 
+            constants::ENEMY_HEALTH_BAR_HEIGHT);
         let health_percentage = enemy.health as f64 / enemy.max_health as f64;
 
         let current_health = self::GUI {
@@ -325,12 +314,36 @@ impl<'a> GUIManager<'a> {
         };
         /*  self.gui_vec.push(temp_gui); */
         //TODO: ADD ERROR HANDLING?
-        game.canvas.set_draw_color(constants::COLOR_RED);
-        game.canvas.fill_rect(max_health);
-        game.canvas.set_draw_color(constants::COLOR_GREEN);
-        game.canvas.fill_rect(current_health.rect);
+        canvas.set_draw_color(constants::COLOR_RED);
+        canvas.fill_rect(max_health);
+        canvas.set_draw_color(constants::COLOR_GREEN);
+        canvas.fill_rect(current_health.rect);
     }
-    pub fn render_health_bar_tower (&mut self, game: &mut game_manager::GameManager, tower: &tower_manager::Tower) {
+    //one square per kind in StatusKind order, stacks share a square
+    pub fn render_status_icons_enemy (&mut self,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        enemy: &enemy_manager::Enemy) {
+        let kinds: Vec<status_manager::StatusKind> = status_manager::StatusKind::ALL.into_iter()
+            .filter(|kind| status_manager::has_status(&enemy.status_vec, *kind))
+            .collect();
+        let icon_step = constants::STATUS_ICON_SIZE as i32 + 1;
+        let row_width = icon_step * kinds.len() as i32 - 1;
+        let row_x = enemy.rect.x() + (enemy.rect.width() as i32 - row_width) / 2;
+        let row_y = enemy.rect.y()
+            - constants::ENEMY_HEALTH_BAR_HEIGHT as i32
+            - constants::STATUS_ICON_SIZE as i32
+            - 1;
+        for (icon_index, kind) in kinds.iter().enumerate() {
+            let icon_rect = sdl2::rect::Rect::new(
+                row_x + icon_step * icon_index as i32,
+                row_y,
+                constants::STATUS_ICON_SIZE,
+                constants::STATUS_ICON_SIZE);
+            canvas.set_draw_color(kind.icon_color());
+            canvas.fill_rect(icon_rect);
+        }
+    }
+    pub fn render_health_bar_tower (&mut self,canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, tower: &tower_manager::Tower) {
         //TODO: match to tower type
         //TODO: store rects & update
         let max_health = sdl2::rect::Rect::new(
@@ -355,14 +368,14 @@ impl<'a> GUIManager<'a> {
         };
         /*  self.gui_vec.push(temp_gui); */
         //TODO: ADD ERROR HANDLING?
-        game.canvas.set_draw_color(constants::COLOR_RED);
-        game.canvas.fill_rect(max_health);
-        game.canvas.set_draw_color(constants::COLOR_GREEN);
-        game.canvas.fill_rect(current_health.rect);
+        canvas.set_draw_color(constants::COLOR_RED);
+        canvas.fill_rect(max_health);
+        canvas.set_draw_color(constants::COLOR_GREEN);
+        canvas.fill_rect(current_health.rect);
     }
     pub fn render_health_bar_buildings (
         &mut self,
-        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         building: &building_manager::Building
     ) {
         let max_health = sdl2::rect::Rect::new(
@@ -382,16 +395,18 @@ impl<'a> GUIManager<'a> {
                 (max_health.width() as f64 * health_percentage) as u32,
                 max_health.height()),
         };
-        game.canvas.set_draw_color(constants::COLOR_RED);
-        game.canvas.fill_rect(max_health);
-        game.canvas.set_draw_color(constants::COLOR_GREEN);
-        game.canvas.fill_rect(current_health.rect);
+        canvas.set_draw_color(constants::COLOR_RED);
+        canvas.fill_rect(max_health);
+        canvas.set_draw_color(constants::COLOR_GREEN);
+        canvas.fill_rect(current_health.rect);
     }
     pub fn render_inventory_hud (
         &mut self,
         events: &mut event_manager::EventManager,
         game: &mut game_manager::GameManager,
-        tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
+        wave_manager: &wave_manager::WaveManager,
     ) -> Result<(), String> {
         for gui_index in 0..self.inventory_vec.len() {
             let gui = &mut self.inventory_vec[gui_index];
@@ -428,6 +443,19 @@ impl<'a> GUIManager<'a> {
                         .blended(constants::COLOR_WHITE)
                         .map_err(|e| e.to_string())?;
                 }
+                5 => {
+                    let wave_text = match wave_manager.wave_state {
+                        wave_manager::WaveState::Build => format!(
+                            "WAVE: {} NEXT: {}",
+                            wave_manager.current_wave,
+                            wave_manager.wave_timer.max(0.0).ceil() as u32
+                        ),
+                        _ => format!("WAVE: {}", wave_manager.current_wave),
+                    };
+                    text_surface = self.font.render(&wave_text)
+                        .blended(constants::COLOR_WHITE)
+                        .map_err(|e| e.to_string())?;
+                }
                 _ => {
                     text_surface = self.font.render(&"ERR".to_string())
                         .blended(constants::COLOR_WHITE)
@@ -446,7 +474,7 @@ impl<'a> GUIManager<'a> {
                             * gui_index as i32,
                             text_surface.width(), text_surface.height()
                         );   
-                        game.canvas.copy(&texture, None, Some(dest)).unwrap(); 
+                        canvas.copy(&texture, None, Some(dest)).unwrap(); 
                     }
                     else {
                         eprintln!("Failed to create GUI texture");
@@ -454,7 +482,7 @@ impl<'a> GUIManager<'a> {
 
 
                     let texture = tex_man.load(&gui.texture_path)?;
-                    game.canvas.copy_ex(
+                    canvas.copy_ex(
                         &texture, 
                         None,      
                         gui.rect,     
@@ -464,7 +492,7 @@ impl<'a> GUIManager<'a> {
                         false,     
                     )?;
                 },
-                3 => {
+                3 | 5 => {
                     if let Ok(texture) = self.texture_creator.create_texture_from_surface(&text_surface) {
                         let dest = sdl2::rect::Rect::new(
                             events.screen_size.0 
@@ -475,7 +503,7 @@ impl<'a> GUIManager<'a> {
                             text_surface.width(),
                             text_surface.height()
                         );   
-                        game.canvas.copy(&texture, None, Some(dest)).unwrap(); 
+                        canvas.copy(&texture, None, Some(dest)).unwrap(); 
                     }
                     else {
                         eprintln!("Failed to create GUI texture");
@@ -486,13 +514,63 @@ impl<'a> GUIManager<'a> {
         }
         Ok(())
     }
+    //bosses get one large bar each along the top of the screen
+    pub fn render_boss_health_bars (
+        &mut self,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        events: &mut event_manager::EventManager,
+        enemies: &enemy_manager::EnemyManager,
+    ) -> Result<(), String> {
+        let bosses = enemies.enemy_vec.iter().filter(|enemy| enemy.boss_parts.is_some());
+
+        for (boss_index, boss) in bosses.enumerate() {
+            let max_health = sdl2::rect::Rect::new(
+                (events.screen_size.0 - constants::BOSS_HEALTH_BAR_WIDTH as i32) / 2,
+                constants::TILE_SIZE as i32 
+                + boss_index as i32 
+                * constants::BOSS_HEALTH_BAR_HEIGHT as i32 * 3,
+                constants::BOSS_HEALTH_BAR_WIDTH,
+                constants::BOSS_HEALTH_BAR_HEIGHT
+            );
+            let health_percentage = boss.health as f64 / boss.max_health as f64;
+            let current_health = sdl2::rect::Rect::new(
+                max_health.x(),
+                max_health.y(),
+                (max_health.width() as f64 * health_percentage) as u32,
+                max_health.height()
+            );
+            canvas.set_draw_color(constants::COLOR_RED);
+            canvas.fill_rect(max_health)?;
+            canvas.set_draw_color(constants::COLOR_GREEN);
+            canvas.fill_rect(current_health)?;
+            utilities::draw_rect_outline(canvas, max_health);
+
+            let text_surface = self.font.render(&boss.enemy_type.stats().name.to_uppercase())
+                .blended(constants::COLOR_WHITE)
+                .map_err(|e| e.to_string())?;
+            if let Ok(texture) = self.texture_creator.create_texture_from_surface(&text_surface) {
+                let dest = sdl2::rect::Rect::new(
+                    max_health.x(),
+                    max_health.bottom(),
+                    text_surface.width(),
+                    text_surface.height()
+                );
+                canvas.copy(&texture, None, Some(dest))?;
+            }
+            else {
+                eprintln!("Failed to create boss bar texture");
+            }
+        }
+        Ok(())
+    }
     pub fn render_messages (&mut self,
         game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         events: &mut event_manager::EventManager,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>
     ) -> Result<(), String> {
-        for message_index in (0..self.message_vec.len()).rev() {
-            let message = &mut self.message_vec[message_index];
+        for message_index in (0..game.message_vec.len()).rev() {
+            let message = &mut game.message_vec[message_index];
             message.rect.set_x(events.screen_size.0 / 2);
             message.rect.set_y(
                 2 * constants::TILE_SIZE as i32 
@@ -511,7 +589,7 @@ impl<'a> GUIManager<'a> {
                     text_surface.width(),
                     text_surface.height()
                 );   
-                game.canvas.copy(&texture, None, Some(dest)).unwrap(); 
+                canvas.copy(&texture, None, Some(dest)).unwrap(); 
             }
             else {
                 eprintln!("Failed to create Message texture");
@@ -520,7 +598,7 @@ impl<'a> GUIManager<'a> {
                 message.time += 1;
             }
             else {
-                self.message_vec.remove(message_index);
+                game.message_vec.remove(message_index);
             }
         }
         Ok(())
@@ -2,9 +2,9 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::env;
 
-use crate::{constants, projectile_manager, gui_manager, game_manager, building_manager, player_manager, event_manager, texture_manager::TextureManager, tower_manager, enemy_manager};
+use crate::{constants, projectile_manager, game_manager, building_manager, player_manager, event_manager, texture_manager::TextureManager, tower_manager, enemy_manager, status_manager, pathfinding_manager};
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum TileData {
     Base,
     ArcherTowerBottom,
@@ -17,25 +17,238 @@ pub enum TileData {
     None,
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TileKind {
+    Grass,
+    Wall,
+    Floor,
+    FieldEmpty,
+    FieldGrowing,
+    FieldHarvestable,
+    ArcherTop,
+    ArcherBottom,
+    FireballTop,
+    FireballBottom,
+    Goblin,
+    Base,
+    Spawn,
+    Mud,
+    Water,
+}
+
+//two kinds sharing a character would load as whichever comes first
+const _: () = assert!(TileKind::codes_are_unique(), "tile kinds must not share a level file character");
+
+impl TileKind {
+    pub const ALL: [TileKind; 15] = [
+        TileKind::Grass,
+        TileKind::Wall,
+        TileKind::Floor,
+        TileKind::FieldEmpty,
+        TileKind::FieldGrowing,
+        TileKind::FieldHarvestable,
+        TileKind::ArcherTop,
+        TileKind::ArcherBottom,
+        TileKind::FireballTop,
+        TileKind::FireballBottom,
+        TileKind::Goblin,
+        TileKind::Base,
+        TileKind::Spawn,
+        TileKind::Mud,
+        TileKind::Water,
+    ];
+
+    //level file and save file character, the only place tile codes are defined
+    pub const fn to_char(self) -> char {
+        match self {
+            TileKind::Grass => '0',
+            TileKind::Wall => '2',
+            TileKind::Floor => '3',
+            TileKind::FieldEmpty => 'F',
+            TileKind::FieldGrowing => 'G',
+            TileKind::FieldHarvestable => 'H',
+            TileKind::ArcherTop => 'A',
+            TileKind::ArcherBottom => 'a',
+            TileKind::FireballTop => 'R',
+            TileKind::FireballBottom => 'f',
+            TileKind::Goblin => 'E',
+            TileKind::Base => 'B',
+            TileKind::Spawn => 'S',
+            TileKind::Mud => 'M',
+            TileKind::Water => 'W',
+        }
+    }
+
+    pub fn from_char(ch: char) -> Option<TileKind> {
+        TileKind::ALL.into_iter().find(|kind| kind.to_char() == ch)
+    }
+
+    //names used by the legend section of level files
+    pub fn name(self) -> &'static str {
+        match self {
+            TileKind::Grass => "grass",
+            TileKind::Wall => "wall",
+            TileKind::Floor => "floor",
+            TileKind::FieldEmpty => "field",
+            TileKind::FieldGrowing => "field_growing",
+            TileKind::FieldHarvestable => "field_harvestable",
+            TileKind::ArcherTop => "archer_top",
+            TileKind::ArcherBottom => "archer_bottom",
+            TileKind::FireballTop => "fireball_top",
+            TileKind::FireballBottom => "fireball_bottom",
+            TileKind::Goblin => "goblin",
+            TileKind::Base => "base",
+            TileKind::Spawn => "spawn",
+            TileKind::Mud => "mud",
+            TileKind::Water => "water",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TileKind> {
+        TileKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    const fn codes_are_unique() -> bool {
+        let mut i = 0;
+        while i < TileKind::ALL.len() {
+            let mut j = i + 1;
+            while j < TileKind::ALL.len() {
+                if TileKind::ALL[i].to_char() == TileKind::ALL[j].to_char() {
+                    return false
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    pub fn texture_path(self) -> &'static str {
+        match self {
+            TileKind::Grass => constants::TEXTURE_TILE_GRASS,
+            TileKind::Wall => constants::TEXTURE_TILE_WALL,
+            TileKind::Floor => constants::TEXTURE_TILE_FLOOR,
+            TileKind::FieldEmpty => constants::TEXTURE_FIELD_EMPTY,
+            TileKind::Spawn => constants::TEXTURE_TILE_SPAWN,
+            TileKind::Mud => constants::TEXTURE_TILE_MUD,
+            TileKind::Water => constants::TEXTURE_TILE_WATER,
+            _ => constants::TEXTURE_DEFAULT,
+        }
+    }
+}
+
+//level file layout, plain tile grids without sections still load as before:
+//  [header]    name, size <cols> <rows>, gold, music, spawn <col> <row>, base <col> <row>
+//  [legend]    <char> <tile kind name>, on top of the default TileKind codes
+//  [tiles]     one line per column of level_vec, like the old format
+//  [entities]  tower <archer|fireball>, building base, crop <carrot|tomato>, enemy <name>, each with <col> <row>
+//  # starts a comment outside of [tiles]
+pub struct LevelHeader {
+    pub name: String,
+    pub size: Option<(usize, usize)>,
+    pub starting_gold: Option<u32>,
+    pub music_path: Option<String>,
+    pub spawn_points: Vec<(usize, usize)>,
+    pub base_location: Option<(usize, usize)>,
+}
+
+pub enum LevelEntity {
+    Tower {
+        tile_data: TileData,
+        index: (usize, usize),
+    },
+    Building {
+        building_type: building_manager::BuildingType,
+        index: (usize, usize),
+    },
+    Crop {
+        tile_data: TileData,
+        index: (usize, usize),
+    },
+    Enemy {
+        enemy_type: enemy_manager::EnemyType,
+        index: (usize, usize),
+    },
+}
+
+#[derive(Debug)]
+pub struct LevelParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for LevelParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
 pub struct LevelManager {
     pub level_vec: Vec<Vec<LevelTile>>,
+    //file the level was read from, empty until read_file
+    pub path: String,
+    pub header: LevelHeader,
+    //placed once by place_entities after the level is read
+    pub entities: Vec<LevelEntity>,
 }
 
 pub struct LevelTile {
-    pub tile_type: char,
-    pub prev_type: char,
-    pub original_type: char,
+    pub tile_type: TileKind,
+    pub prev_type: TileKind,
+    pub original_type: TileKind,
     pub texture_path: String,
     pub rect: sdl2::rect::Rect,
     pub state: u16,
     pub tile_data: TileData,
     pub is_occupied: bool,
+    //only walls have health, they break back into floor
+    pub health: u16,
+}
+
+impl LevelHeader {
+    pub fn new(name: &str) -> LevelHeader {
+        let header = LevelHeader {
+            name: name.to_string(),
+            size: None,
+            starting_gold: None,
+            music_path: None,
+            spawn_points: Vec::new(),
+            base_location: None,
+        };
+        header
+    }
+}
+
+impl LevelTile {
+    pub fn new(tile_type: TileKind, rect: sdl2::rect::Rect) -> LevelTile {
+        let health = if tile_type == TileKind::Wall {
+            constants::WALL_HEALTH
+        }
+        else {
+            0
+        };
+        let tile = LevelTile {
+            tile_type,
+            prev_type: tile_type,
+            original_type: tile_type,
+            texture_path: tile_type.texture_path().to_string(),
+            rect,
+            state: 0,
+            tile_data: TileData::None,
+            is_occupied: false,
+            health,
+        };
+        tile
+    }
 }
 
 impl LevelManager {
     pub fn new() -> LevelManager {
         let level = LevelManager {
             level_vec: Vec::new(),
+            path: String::new(),
+            header: LevelHeader::new(""),
+            entities: Vec::new(),
         };
         level
     }
@@ -51,30 +264,20 @@ impl LevelManager {
     //     Ok(LevelManager { level_vec })
     // }
 
-    pub fn create_level(&mut self) {
-        for _ in 0..constants::MAX_HEIGHT {
-            let mut row = Vec::new();
-            for _ in 0..constants::MAX_WIDTH {
-                let rect = sdl2::rect::Rect::new(
-                    0,
-                    0,
-                    constants::TILE_SIZE,
-                    constants::TILE_SIZE
-                );
+    //level size in tiles, comes from whichever file was loaded last
+    pub fn width(&self) -> usize {
+        self.level_vec.len()
+    }
 
-                row.push(LevelTile { 
-                    tile_type: constants::TILE_TYPE_GRASS,
-                    prev_type: constants::TILE_TYPE_GRASS,
-                    original_type: constants::TILE_TYPE_GRASS,
-                    texture_path: constants::TEXTURE_TILE_GRASS.to_string(),
-                    rect,
-                    state: 0,
-                    tile_data: TileData::None,
-                    is_occupied: false,
-                });
-            }
-            self.level_vec.push(row);
-        }
+    pub fn height(&self) -> usize {
+        self.level_vec.first().map_or(0, |col| col.len())
+    }
+
+    pub fn pixel_size(&self) -> (i32, i32) {
+        (
+            self.width() as i32 * constants::TILE_SIZE as i32,
+            self.height() as i32 * constants::TILE_SIZE as i32
+        )
     }
 
     pub fn read_file(
@@ -83,96 +286,364 @@ impl LevelManager {
     -> Result<(), std::io::Error> {
         println!("Reading from dir: {:?}", env::current_dir()?);
         let file = File::open(filename)?;
-        let reader = BufReader::new(file);
-        let mut temp_vec: Vec<Vec<LevelTile>> = Vec::new();
+        let mut lines = Vec::new();
+        for line in BufReader::new(file).lines() {
+            lines.push(line?);
+        }
+        let level_name = std::path::Path::new(filename)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        *self = Self::parse_level(&lines.join("\n"), &level_name)
+            .map_err(|error| std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("{} {}", filename, error)
+            ))?;
+        self.path = filename.to_string();
+        Ok(())
+    }
+
+    pub fn parse_level(
+        contents: &str,
+        level_name: &str,
+    ) -> Result<LevelManager, LevelParseError> {
+        let mut header = LevelHeader::new(level_name);
+        let mut entities: Vec<LevelEntity> = Vec::new();
+        //line numbers for spawn points and entities, positions are checked once the tiles are read
+        let mut spawn_lines: Vec<usize> = Vec::new();
+        let mut entity_lines: Vec<usize> = Vec::new();
+        let mut legend: std::collections::HashMap<char, TileKind> = std::collections::HashMap::new();
+        let mut tile_lines: Vec<(usize, &str)> = Vec::new();
+
+        let is_sectioned = contents.trim_start().starts_with('[');
+        //old levels are nothing but tile lines
+        let mut section = if is_sectioned { "" } else { "tiles" };
+
+        for (line_index, line) in contents.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = line.trim_end_matches('\r');
+            let error = |message: String| LevelParseError {
+                line: line_number,
+                message,
+            };
+
+            if is_sectioned && line.starts_with('[') {
+                section = match line.trim() {
+                    "[header]" => "header",
+                    "[legend]" => "legend",
+                    "[tiles]" => "tiles",
+                    "[entities]" => "entities",
+                    other => return Err(error(format!("unknown section '{}'", other))),
+                };
+                continue;
+            }
+            if section == "tiles" {
+                if !line.is_empty() {
+                    tile_lines.push((line_number, line));
+                }
+                continue;
+            }
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() {
+                continue;
+            }
+
+            match section {
+                "header" => match words[0] {
+                    "name" => header.name = words[1..].join(" "),
+                    "size" => header.size = Some(Self::parse_index(&words, "size <cols> <rows>").map_err(error)?),
+                    "gold" => {
+                        let gold = words.get(1).and_then(|word| word.parse::<u32>().ok());
+                        header.starting_gold = Some(gold.ok_or_else(|| error("expected: gold <amount>".to_string()))?);
+                    }
+                    "music" => match words.get(1) {
+                        Some(music_path) if words.len() == 2 => header.music_path = Some(music_path.to_string()),
+                        _ => return Err(error("expected: music <path>".to_string())),
+                    },
+                    "spawn" => {
+                        header.spawn_points.push(Self::parse_index(&words, "spawn <col> <row>").map_err(error)?);
+                        spawn_lines.push(line_number);
+                    }
+                    "base" => {
+                        let index = Self::parse_index(&words, "base <col> <row>").map_err(error)?;
+                        header.base_location = Some(index);
+                        entities.push(LevelEntity::Building {
+                            building_type: building_manager::BuildingType::Base,
+                            index,
+                        });
+                        entity_lines.push(line_number);
+                    }
+                    other => return Err(error(format!("unknown header entry '{}'", other))),
+                },
+                "legend" => {
+                    let mut chars = words[0].chars();
+                    let (Some(ch), None, 2) = (chars.next(), chars.next(), words.len()) else {
+                        return Err(error("expected: <char> <tile kind>".to_string()))
+                    };
+                    let Some(tile_kind) = TileKind::from_name(words[1]) else {
+                        return Err(error(format!("unknown tile kind '{}'", words[1])))
+                    };
+                    if let Some(previous) = legend.insert(ch, tile_kind) {
+                        return Err(error(format!(
+                            "'{}' is already {}, a character can only mean one tile kind",
+                            ch,
+                            previous.name()
+                        )));
+                    }
+                }
+                "entities" => {
+                    let expected = "expected: <entity> <type> <col> <row>";
+                    if words.len() != 4 {
+                        return Err(error(expected.to_string()));
+                    }
+                    let index = Self::parse_index(&words[1..], expected).map_err(error)?;
+                    let entity = match (words[0], words[1]) {
+                        ("tower", "archer") => LevelEntity::Tower {
+                            tile_data: TileData::ArcherTowerBottom,
+                            index,
+                        },
+                        ("tower", "fireball") => LevelEntity::Tower {
+                            tile_data: TileData::FireballTowerBottom,
+                            index,
+                        },
+                        ("building", "base") => LevelEntity::Building {
+                            building_type: building_manager::BuildingType::Base,
+                            index,
+                        },
+                        ("crop", "carrot") => LevelEntity::Crop {
+                            tile_data: TileData::Carrots,
+                            index,
+                        },
+                        ("crop", "tomato") => LevelEntity::Crop {
+                            tile_data: TileData::Tomatoes,
+                            index,
+                        },
+                        ("enemy", name) => match enemy_manager::EnemyType::from_name(name) {
+                            Some(enemy_type) => LevelEntity::Enemy {
+                                enemy_type,
+                                index,
+                            },
+                            None => return Err(error(format!("unknown enemy type '{}'", name))),
+                        },
+                        (entity, kind) => return Err(error(format!("unknown entity '{} {}'", entity, kind))),
+                    };
+                    entities.push(entity);
+                    entity_lines.push(line_number);
+                }
+                _ => return Err(error("expected a section like [header] first".to_string())),
+            }
+        }
+
         let rect = sdl2::rect::Rect::new(
             0,
             0,
             constants::TILE_SIZE,
             constants::TILE_SIZE
         );
-
-        for line in reader.lines() {
-            let line = line?;
+        let mut level_vec: Vec<Vec<LevelTile>> = Vec::new();
+        for (line_number, line) in tile_lines {
             let mut row_vec: Vec<LevelTile> = Vec::new();
             for ch in line.chars() {
-                match ch {
-                    constants::TILE_TYPE_GRASS => {
-                        let tile = LevelTile {
-                            tile_type: ch,
-                            prev_type: ch,
-                            original_type: ch,
-                            texture_path: constants::TEXTURE_TILE_GRASS.to_string(),
-                            rect,
-                            state: 0,
-                            tile_data: TileData::None,
-                            is_occupied: false,
-                        };
-                        row_vec.push(tile);
-                    }
-                    constants::TILE_TYPE_WALL => {
-                        let tile = LevelTile {
-                            tile_type: ch,
-                            prev_type: ch,
-                            original_type: ch,
-                            texture_path: constants::TEXTURE_TILE_WALL.to_string(),
-                            rect,
-                            state: 0,
-                            tile_data: TileData::None,
-                            is_occupied: false,
-                        };
-                        row_vec.push(tile);
-                    }
-                    constants::TILE_TYPE_FLOOR => {
-                        let tile = LevelTile {
-                            tile_type: ch,
-                            prev_type: ch,
-                            original_type: ch,
-                            texture_path: constants::TEXTURE_TILE_FLOOR.to_string(),
-                            rect,
-                            state: 0,
-                            tile_data: TileData::None,
-                            is_occupied: false,
-                        };
-                        row_vec.push(tile);
-                    }
-                    constants::TILE_TYPE_FIELD_EMPTY => {
-                        let tile = LevelTile {
-                            tile_type: ch,
-                            prev_type: ch,
-                            original_type: ch,
-                            texture_path: constants::TEXTURE_FIELD_EMPTY.to_string(),
-                            rect,
-                            state: 0,
-                            tile_data: TileData::None,
-                            is_occupied: false,
-                        };
-                        row_vec.push(tile);
-                    }
-                    _ => {
-                        let tile = LevelTile {
-                            tile_type: ch,
-                            prev_type: ch,
-                            original_type: ch,
-                            texture_path: constants::TEXTURE_DEFAULT.to_string(),
-                            rect,
-                            state: 0,
-                            tile_data: TileData::None,
-                            is_occupied: false,
-                        };
-                        row_vec.push(tile);
-                    }
-                }
+                let Some(tile_type) = legend.get(&ch).copied().or_else(|| TileKind::from_char(ch)) else {
+                    return Err(LevelParseError {
+                        line: line_number,
+                        message: format!("unknown tile {:?}", ch),
+                    })
+                };
+                row_vec.push(LevelTile::new(tile_type, rect));
+            }
+            level_vec.push(row_vec);
+        }
+
+        if level_vec.is_empty() {
+            return Err(LevelParseError {
+                line: 0,
+                message: "level has no tiles".to_string(),
+            });
+        }
+        let rows = level_vec[0].len();
+        if let Some((col, _)) = level_vec.iter().enumerate().find(|(_, col)| col.len() != rows) {
+            return Err(LevelParseError {
+                line: 0,
+                message: format!("tile line {} has {} tiles, the first one has {}", col + 1, level_vec[col].len(), rows),
+            });
+        }
+        if let Some((cols, rows)) = header.size {
+            if level_vec.len() != cols || level_vec.iter().any(|col| col.len() != rows) {
+                return Err(LevelParseError {
+                    line: 0,
+                    message: format!("tiles don't match size {} {}", cols, rows),
+                });
+            }
+        }
+
+        let is_on_map = |index: (usize, usize)| level_vec.get(index.0).is_some_and(|col| index.1 < col.len());
+        let outside = |index: (usize, usize)| format!("position {} {} is outside the level", index.0, index.1);
+        for (spawn_point, line_number) in header.spawn_points.iter().zip(&spawn_lines) {
+            if !is_on_map(*spawn_point) {
+                return Err(LevelParseError {
+                    line: *line_number,
+                    message: outside(*spawn_point),
+                });
             }
-            temp_vec.push(row_vec);
         }
-        self.level_vec = temp_vec;
+        for (entity, line_number) in entities.iter().zip(&entity_lines) {
+            let message = match *entity {
+                LevelEntity::Tower { index, .. }
+                | LevelEntity::Building { index, .. }
+                | LevelEntity::Crop { index, .. }
+                | LevelEntity::Enemy { index, .. } if !is_on_map(index) => outside(index),
+                //the top of a tower goes on the tile above it
+                LevelEntity::Tower { index, .. } if index.1 == 0 => {
+                    format!("tower at {} {} needs a row above it", index.0, index.1)
+                }
+                LevelEntity::Building { index, .. } if !is_on_map((index.0 + 1, index.1 + 1)) => {
+                    format!("building at {} {} covers 2 by 2 tiles and doesn't fit", index.0, index.1)
+                }
+                _ => continue,
+            };
+            return Err(LevelParseError {
+                line: *line_number,
+                message,
+            });
+        }
+        for spawn_point in &header.spawn_points {
+            level_vec[spawn_point.0][spawn_point.1] = LevelTile::new(TileKind::Spawn, rect);
+        }
+
+        let level = LevelManager {
+            level_vec,
+            path: String::new(),
+            header,
+            entities,
+        };
+        Ok(level)
+    }
+
+    //grass level for the editor, named after the file it will be written to
+    pub fn blank(level_name: &str, size: (usize, usize)) -> LevelManager {
+        let rect = sdl2::rect::Rect::new(
+            0,
+            0,
+            constants::TILE_SIZE,
+            constants::TILE_SIZE
+        );
+        let level = LevelManager {
+            level_vec: (0..size.0)
+                .map(|_| (0..size.1).map(|_| LevelTile::new(TileKind::Grass, rect)).collect())
+                .collect(),
+            path: String::new(),
+            header: LevelHeader::new(level_name),
+            entities: Vec::new(),
+        };
+        level
+    }
+
+    //comments and legend characters are not kept, spawns are written as tiles
+    pub fn write_file(&self, filename: &str) -> Result<(), std::io::Error> {
+        std::fs::write(filename, self.to_level_string())?;
         Ok(())
     }
 
+    pub fn to_level_string(&self) -> String {
+        let mut contents = String::new();
+        contents.push_str("[header]\n");
+        contents.push_str(&format!("name {}\n", self.header.name));
+        contents.push_str(&format!("size {} {}\n", self.width(), self.height()));
+        if let Some(starting_gold) = self.header.starting_gold {
+            contents.push_str(&format!("gold {}\n", starting_gold));
+        }
+        if let Some(music_path) = &self.header.music_path {
+            contents.push_str(&format!("music {}\n", music_path));
+        }
+        if let Some(base_location) = self.header.base_location {
+            contents.push_str(&format!("base {} {}\n", base_location.0, base_location.1));
+        }
+
+        contents.push_str("[tiles]\n");
+        for col in &self.level_vec {
+            contents.extend(col.iter().map(|tile| tile.tile_type.to_char()));
+            contents.push('\n');
+        }
+
+        let entity_lines: Vec<String> = self.entities.iter().filter_map(|entity| match *entity {
+            LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index } => Some(format!("tower archer {} {}", index.0, index.1)),
+            LevelEntity::Tower { index, .. } => Some(format!("tower fireball {} {}", index.0, index.1)),
+            //the header base line already adds its building
+            LevelEntity::Building { building_type: building_manager::BuildingType::Base, index } => {
+                (self.header.base_location != Some(index)).then(|| format!("building base {} {}", index.0, index.1))
+            }
+            LevelEntity::Building { .. } => None,
+            LevelEntity::Crop { tile_data: TileData::Carrots, index } => Some(format!("crop carrot {} {}", index.0, index.1)),
+            LevelEntity::Crop { index, .. } => Some(format!("crop tomato {} {}", index.0, index.1)),
+            LevelEntity::Enemy { enemy_type, index } => Some(format!("enemy {} {} {}", enemy_type.stats().name, index.0, index.1)),
+        }).collect();
+        if !entity_lines.is_empty() {
+            contents.push_str("[entities]\n");
+            for line in entity_lines {
+                contents.push_str(&line);
+                contents.push('\n');
+            }
+        }
+        contents
+    }
+
+    fn parse_index(words: &[&str], expected: &str) -> Result<(usize, usize), String> {
+        match (words.len(), words.get(1).map(|word| word.parse()), words.get(2).map(|word| word.parse())) {
+            (3, Some(Ok(col)), Some(Ok(row))) => Ok((col, row)),
+            _ => Err(format!("expected: {}", expected)),
+        }
+    }
+
+    //towers, buildings, crops and enemies that the level file starts with
+    pub fn place_entities(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        towers: &mut tower_manager::TowerManager,
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager,
+    ) {
+        if let Some(starting_gold) = self.header.starting_gold {
+            game.gold_amount = starting_gold;
+        }
+        for entity in &self.entities {
+            match *entity {
+                LevelEntity::Tower { tile_data, index } => {
+                    let temp_tile = &mut self.level_vec[index.0][index.1];
+                    temp_tile.tile_type = match tile_data {
+                        TileData::ArcherTowerBottom => TileKind::ArcherBottom,
+                        _ => TileKind::FireballBottom,
+                    };
+                    temp_tile.tile_data = tile_data;
+                    towers.place_tower(game, temp_tile, index);
+                }
+                LevelEntity::Building { building_type, index } => {
+                    let temp_tile = &mut self.level_vec[index.0][index.1];
+                    buildings.create_building(game, building_type, temp_tile, index.0, index.1);
+                }
+                LevelEntity::Crop { tile_data, index } => {
+                    let temp_tile = &mut self.level_vec[index.0][index.1];
+                    temp_tile.tile_type = TileKind::FieldEmpty;
+                    temp_tile.texture_path = constants::TEXTURE_FIELD_SEEDS.to_string();
+                    temp_tile.tile_data = tile_data;
+                    temp_tile.state = 0;
+                }
+                LevelEntity::Enemy { enemy_type, index } => {
+                    enemies.place_enemy(game, &self.level_vec[index.0][index.1], enemy_type, index);
+                }
+            }
+        }
+    }
+
     pub fn render_level(
         &mut self,
-        game: &mut game_manager::GameManager, 
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut TextureManager<sdl2::video::WindowContext>,
         player: &mut player_manager::PlayerManager,
         events: &mut event_manager::EventManager,
@@ -199,7 +670,7 @@ impl LevelManager {
 
                 if temp_tile.rect.has_intersection(screen_rect) {
                     let texture = tex_man.load(&temp_tile.texture_path)?;
-                    game.canvas.copy_ex(
+                    canvas.copy_ex(
                         &texture,
                         None,
                         temp_tile.rect,
@@ -215,53 +686,72 @@ impl LevelManager {
     }
     pub fn check_attacks (
         game: &mut game_manager::GameManager,
-        events: &mut event_manager::EventManager,
-        player: &mut player_manager::PlayerManager,
+        level: &LevelManager,
         enemies: &mut enemy_manager::EnemyManager, 
         towers: &mut tower_manager::TowerManager,
         buildings: &mut building_manager::BuildingManager,
         projectiles: &mut projectile_manager::ProjectileManager,
-        gui_manager: &mut gui_manager::GUIManager,
+        pathfinding_manager: &pathfinding_manager::PathfindingManager,
     ) {
+        enemies.update_enemy_grid();
+        //furthest any enemy can hit from, grid queries for enemy attacks use it
+        let enemy_reach = enemies.enemy_vec.iter()
+            .map(|enemy| enemy.attack_radius as i32)
+            .max()
+            .unwrap_or(0);
         for tower in &mut towers.tower_vec {
             let tower_pos_pixel = (
                 constants::TILE_SIZE as i32 * tower.top_index.0 as i32,
                 constants::TILE_SIZE as i32 * tower.top_index.1 as i32
             );
-            for enemy in &mut enemies.enemy_vec {
-                /* let enemy_pos_pixel = (constants::TILE_SIZE as i32 * enemy.grid_index.0 as i32, constants::TILE_SIZE as i32 * enemy.grid_index.1 as i32); */
+            //TOWER ATTACK
+            if let Some(target) = tower_manager::TowerManager::choose_target(
+                tower,
+                tower_pos_pixel,
+                enemies,
+                pathfinding_manager
+            ) {
+                //need to add delta time here
+                if game.frame_time % tower.attack_speed as u32 == 0 {
+                    let enemy = &enemies.enemy_vec[target];
+                    //homing projectiles chase the enemy itself, the rest aim where it is going to be
+                    let aim_pos_pixel = if tower.projectile_homing {
+                        (enemy.pixel_index.0 as i32, enemy.pixel_index.1 as i32)
+                    }
+                    else {
+                        projectile_manager::ProjectileManager::intercept_point(
+                            tower_pos_pixel,
+                            tower.projectile_speed,
+                            enemy,
+                            game,
+                            level
+                        )
+                    };
+                    projectiles.spawn_tower_projectile(
+                        tower,
+                        tower_pos_pixel,
+                        tower_pos_pixel,
+                        aim_pos_pixel,
+                        Some(enemy.id)
+                    );
+                    tower.is_attacking = true;
+                }
+            }
+            for enemy_index in enemies.enemy_grid.query(tower_pos_pixel, enemy_reach) {
+                let enemy = &enemies.enemy_vec[enemy_index];
                 let enemy_pos_pixel = (
                     enemy.pixel_index.0 as i32,
                     enemy.pixel_index.1 as i32
                 );
-                //need to add delta time here
-                let tower_can_attack: bool = tower_manager::TowerManager::is_within_area(
-                    tower_pos_pixel,
-                    enemy_pos_pixel,
-                    tower.attack_radius
-                ) && game.frame_time % tower.attack_speed as u32 == 0;
                 let enemy_can_attack: bool = tower_manager::TowerManager::is_within_area(
                     tower_pos_pixel,
                     enemy_pos_pixel,
                     enemy.attack_radius as i32
                 ) && game.frame_time % enemy.attack_speed as u32 == 0;
+                let is_stunned = status_manager::stun_time(&enemy.status_vec) > 0;
 
-                //TOWER ATTACK
-                if enemy.health != 0 {
-                    if tower_can_attack && !tower.is_attacking {
-                        if tower_pos_pixel != enemy_pos_pixel {
-                            projectiles.spawn_tower_projectile(
-                                tower,
-                                tower_pos_pixel,
-                                tower_pos_pixel,
-                                enemy_pos_pixel
-                            );
-                            tower.is_attacking = true;
-                        }
-                    }
-                }
                 //ENEMY ATTACK
-                if tower.health != 0 && enemy_can_attack {
+                if tower.health != 0 && enemy_can_attack && !is_stunned && !enemy.enemy_type.stats().is_ranged {
                     if tower.health > enemy.attack_damage as u16 {
                         tower.health -= enemy.attack_damage as u16;
                     }
@@ -275,7 +765,10 @@ impl LevelManager {
             tower.is_attacking = false;
         }
         for building in &mut buildings.building_vec {
-            for enemy in &mut enemies.enemy_vec {
+            //buildings are hit from the enemy's tile, up to half a tile from its pixel_index
+            let building_reach = enemy_reach + constants::TILE_SIZE as i32 / 2;
+            for enemy_index in enemies.enemy_grid.query(building.pixel_index, building_reach) {
+                let enemy = &enemies.enemy_vec[enemy_index];
                 let enemy_pos_pixel = (
                     constants::TILE_SIZE as i32 * enemy.grid_index.0 as i32,
                     constants::TILE_SIZE as i32 * enemy.grid_index.1 as i32
@@ -285,7 +778,8 @@ impl LevelManager {
                     enemy_pos_pixel,
                     enemy.attack_radius as i32
                 );
-                if building.health != 0 && enemy_can_attack {
+                let is_stunned = status_manager::stun_time(&enemy.status_vec) > 0;
+                if building.health != 0 && enemy_can_attack && !is_stunned && !enemy.enemy_type.stats().is_ranged {
                     if building.health > enemy.attack_damage as u16 {
                         building.health -= enemy.attack_damage as u16;
                         building.last_damaged = 0;
@@ -294,9 +788,43 @@ impl LevelManager {
                         building.health = 0;
                     }
                     /*                     enemy.found_target = true; */
-                    gui_manager.create_unique_message("base is under attack!".to_string(), 128);
+                    game.create_unique_message("base is under attack!".to_string(), 128);
                 }
             }
         }
+        //RANGED ENEMY ATTACK
+        for enemy in &enemies.enemy_vec {
+            if !enemy.enemy_type.stats().is_ranged
+            || status_manager::stun_time(&enemy.status_vec) > 0
+            || game.frame_time % enemy.attack_speed as u32 != 0 {
+                continue;
+            }
+            let enemy_pos_pixel = (
+                enemy.pixel_index.0 as i32,
+                enemy.pixel_index.1 as i32
+            );
+            let tower_targets = towers.tower_vec.iter()
+                .filter(|tower| tower.health != 0)
+                .map(|tower| (
+                    constants::TILE_SIZE as i32 * tower.bottom_index.0 as i32,
+                    constants::TILE_SIZE as i32 * tower.bottom_index.1 as i32
+                ));
+            let building_targets = buildings.building_vec.iter()
+                .filter(|building| building.health != 0)
+                .map(|building| building.pixel_index);
+            let target = tower_targets.chain(building_targets)
+                .filter(|target_pos_pixel| tower_manager::TowerManager::is_within_area(
+                    *target_pos_pixel,
+                    enemy_pos_pixel,
+                    enemy.attack_radius as i32
+                ))
+                .min_by_key(|target_pos_pixel| {
+                    (target_pos_pixel.0 - enemy_pos_pixel.0).abs()
+                    + (target_pos_pixel.1 - enemy_pos_pixel.1).abs()
+                });
+            if let Some(target_pos_pixel) = target {
+                projectiles.spawn_enemy_projectile(enemy, enemy_pos_pixel, target_pos_pixel);
+            }
+        }
     }
 }
@@ -0,0 +1,23 @@
+pub mod constants;
+pub mod game_manager;
+pub mod texture_manager;
+pub mod event_manager;
+pub mod player_manager;
+pub mod level_manager;
+pub mod tower_manager;
+pub mod building_manager;
+pub mod enemy_manager;
+pub mod projectile_manager;
+pub mod gui_manager;
+pub mod minimap_manager;
+pub mod button_manager;
+pub mod menu_manager;
+pub mod pathfinding_manager;
+pub mod upgrade_manager;
+pub mod save_manager;
+pub mod wave_manager;
+pub mod editor_manager;
+pub mod replay_manager;
+pub mod spatial_manager;
+pub mod status_manager;
+pub mod utilities;
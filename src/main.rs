@@ -1,72 +1,259 @@
-pub mod constants;
-pub mod game_manager;
-pub mod texture_manager;
-pub mod event_manager;
-pub mod player_manager;
-pub mod level_manager;
-pub mod tower_manager;
-pub mod building_manager;
-pub mod enemy_manager;
-pub mod projectile_manager;
-pub mod gui_manager;
-pub mod minimap_manager;
-pub mod button_manager;
-pub mod menu_manager;
-pub mod pathfinding_manager;
-pub mod upgrade_manager;
-pub mod utilities;
+use farm_defense::{
+    constants,
+    game_manager,
+    texture_manager,
+    event_manager,
+    player_manager,
+    level_manager,
+    tower_manager,
+    building_manager,
+    enemy_manager,
+    projectile_manager,
+    gui_manager,
+    minimap_manager,
+    button_manager,
+    menu_manager,
+    pathfinding_manager,
+    upgrade_manager,
+    save_manager,
+    wave_manager,
+    editor_manager,
+    replay_manager,
+};
 
 fn save_game (
     game: &mut game_manager::GameManager, 
-    tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>, 
-    events: &mut event_manager::EventManager, 
     player: &mut player_manager::PlayerManager,
     level: &mut level_manager::LevelManager,
     towers: &mut tower_manager::TowerManager,
     buildings: &mut building_manager::BuildingManager,
     enemies: &mut enemy_manager::EnemyManager,
     projectiles: &mut projectile_manager::ProjectileManager,
-    seed_buttons: &mut button_manager::ButtonManager,
-    build_buttons: &mut button_manager::ButtonManager,
-    health_bars: &mut gui_manager::GUIManager,
+    upgrade_manager: &mut upgrade_manager::UpgradeManager,
+    save_manager: &mut save_manager::SaveManager,
+    wave_manager: &mut wave_manager::WaveManager,
 ) {
-    // let save_path = "saves/save.bin";
-    // match level.save_to_file(&save_path) {
-    //     Ok(()) => println!("Data saved successfully."),
-    //     Err(error) => eprintln!("Failed to save data: {}", error),
-    // }
+    match save_manager.save_game(game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, wave_manager) {
+        Ok(()) => {
+            println!("Data saved successfully.");
+            game.create_message("game saved".to_string(), 128);
+        }
+        Err(error) => {
+            eprintln!("Failed to save data: {}", error);
+            game.create_message("failed to save game".to_string(), 256);
+        }
+    }
 }
 
 fn load_game (
     game: &mut game_manager::GameManager, 
-    tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>, 
-    events: &mut event_manager::EventManager, 
     player: &mut player_manager::PlayerManager,
     level: &mut level_manager::LevelManager,
     towers: &mut tower_manager::TowerManager,
     buildings: &mut building_manager::BuildingManager,
     enemies: &mut enemy_manager::EnemyManager,
     projectiles: &mut projectile_manager::ProjectileManager,
-    seed_buttons: &mut button_manager::ButtonManager,
-    build_buttons: &mut button_manager::ButtonManager,
-    health_bars: &mut gui_manager::GUIManager,
+    upgrade_manager: &mut upgrade_manager::UpgradeManager,
+    save_manager: &mut save_manager::SaveManager,
+    wave_manager: &mut wave_manager::WaveManager,
+    pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+    replay_manager: &mut replay_manager::ReplayManager,
+) {
+    match save_manager.load_game(game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, wave_manager) {
+        Ok(()) => {
+            println!("Data loaded successfully.");
+            game.level_updated = true;
+            pathfinding_manager.clear_flow_fields();
+            pathfinding_manager.clear_path_requests();
+            game.message_vec.clear();
+            //replays start from a level file, not from a save
+            replay_manager.stop_recording();
+            game.create_message("game loaded".to_string(), 128);
+        }
+        Err(error) => {
+            eprintln!("Failed to load data: {}", error);
+            game.create_message("failed to load game".to_string(), 256);
+        }
+    }
+}
+
+//starts the selected level from scratch, the current run is thrown away
+//returns false if the level file could not be read
+fn load_level (
+    level_path: &str,
+    game: &mut game_manager::GameManager, 
+    player: &mut player_manager::PlayerManager,
+    level: &mut level_manager::LevelManager,
+    towers: &mut tower_manager::TowerManager,
+    buildings: &mut building_manager::BuildingManager,
+    enemies: &mut enemy_manager::EnemyManager,
+    projectiles: &mut projectile_manager::ProjectileManager,
+    upgrade_manager: &mut upgrade_manager::UpgradeManager,
+    wave_manager: &mut wave_manager::WaveManager,
+    pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+    replay_manager: &mut replay_manager::ReplayManager,
+    music: &mut sdl2::mixer::Music<'static>,
+) -> bool {
+    if let Err(error) = level.read_file(level_path) {
+        eprintln!("Failed to load level: {}", error);
+        game.create_message("failed to load level".to_string(), 256);
+        return false
+    }
+    reset_level_state(game, player, towers, buildings, enemies, projectiles, upgrade_manager, pathfinding_manager);
+
+    level.place_entities(game, towers, buildings, enemies);
+    wave_manager.find_spawn_points(level);
+    wave_manager.load_wave_script(level_path, game);
+    game.level_updated = true;
+    replay_manager.start_recording(level_path);
+
+    match play_level_music(level) {
+        Ok(level_music) => *music = level_music,
+        Err(error) => eprintln!("Failed to play level music: {}", error),
+    }
+    game.create_message(format!("{} loaded", level.header.name), 128);
+    true
+}
+
+fn save_replay (
+    game: &mut game_manager::GameManager, 
+    replay_manager: &mut replay_manager::ReplayManager,
+) {
+    if replay_manager.is_playing {
+        game.create_message("the replay is still playing".to_string(), 128);
+        return
+    }
+    if !replay_manager.is_recording {
+        game.create_message("loaded games can't be saved as replays".to_string(), 256);
+        return
+    }
+    match replay_manager.write_replay(constants::REPLAY_PATH) {
+        Ok(()) => {
+            game.create_message("replay saved".to_string(), 128);
+        }
+        Err(error) => {
+            eprintln!("Failed to save replay: {}", error);
+            game.create_message("failed to save replay".to_string(), 256);
+        }
+    }
+}
+
+//restarts the replay's level and feeds it the recorded inputs instead of the player's
+fn play_replay (
+    replay_path: &str,
+    game: &mut game_manager::GameManager, 
+    player: &mut player_manager::PlayerManager,
+    level: &mut level_manager::LevelManager,
+    towers: &mut tower_manager::TowerManager,
+    buildings: &mut building_manager::BuildingManager,
+    enemies: &mut enemy_manager::EnemyManager,
+    projectiles: &mut projectile_manager::ProjectileManager,
+    upgrade_manager: &mut upgrade_manager::UpgradeManager,
+    wave_manager: &mut wave_manager::WaveManager,
+    pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+    replay_manager: &mut replay_manager::ReplayManager,
+    music: &mut sdl2::mixer::Music<'static>,
 ) {
-    // let load_path: &str = "saves/save.bin";
-    // let load_file = include_bytes!(load_path);
-
-    // match level_manager::LevelManager::load_from_file(&load_path) {
-    //     Ok(loaded_level) => {
-    //         *level = loaded_level;
-    //         println!("Data loaded successfully.");
-    //     }
-    //     Err(error) => eprintln!("Failed to load data: {}", error),
-    // }
+    let replay = match replay_manager::ReplayManager::read_replay(replay_path) {
+        Ok(replay) => replay,
+        Err(error) => {
+            eprintln!("Failed to load replay {}: {}", replay_path, error);
+            game.create_message("failed to load replay".to_string(), 256);
+            return
+        }
+    };
+    if load_level(&replay.level_path, game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, wave_manager, pathfinding_manager, replay_manager, music) {
+        replay_manager.start_playback(replay.input_vec);
+        game.create_message("replay playing, f to fast forward".to_string(), 256);
+    }
 }
 
+//everything that belongs to the previous level, shared by playing and editing
+fn reset_level_state (
+    game: &mut game_manager::GameManager, 
+    player: &mut player_manager::PlayerManager,
+    towers: &mut tower_manager::TowerManager,
+    buildings: &mut building_manager::BuildingManager,
+    enemies: &mut enemy_manager::EnemyManager,
+    projectiles: &mut projectile_manager::ProjectileManager,
+    upgrade_manager: &mut upgrade_manager::UpgradeManager,
+    pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+) {
+    game.gold_amount = constants::STARTING_GOLD;
+    game.carrot_amount = 0;
+    game.tomato_amount = 0;
+    //attack timers count from frame_time, replays need every level to start on the same step
+    game.frame_time = 1;
+    game.step_accumulator = 0.0;
+    game.base_location = None;
+    game.target_vec.clear();
+    game.placed = false;
+    game.preview_mode = false;
+
+    player.x = 0;
+    player.y = 0;
+    player.is_attacking = false;
 
+    towers.tower_vec.clear();
+    buildings.building_vec.clear();
+    buildings.base_created = false;
+    enemies.enemy_vec.clear();
+    enemies.next_enemy_id = 0;
+    projectiles.projectile_vec.clear();
+    projectiles.explosion_vec.clear();
+    upgrade_manager.upgrade_menu_vec.clear();
+    pathfinding_manager.clear_flow_fields();
+    pathfinding_manager.clear_path_requests();
+    game.message_vec.clear();
+}
+
+//opens a level file in the editor, a path that doesn't exist yet starts a blank level
+fn open_editor (
+    level_path: &str,
+    game: &mut game_manager::GameManager, 
+    events: &mut event_manager::EventManager, 
+    player: &mut player_manager::PlayerManager,
+    level: &mut level_manager::LevelManager,
+    towers: &mut tower_manager::TowerManager,
+    buildings: &mut building_manager::BuildingManager,
+    enemies: &mut enemy_manager::EnemyManager,
+    projectiles: &mut projectile_manager::ProjectileManager,
+    upgrade_manager: &mut upgrade_manager::UpgradeManager,
+    pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+    editor_manager: &mut editor_manager::EditorManager,
+) {
+    if std::path::Path::new(level_path).exists() {
+        if let Err(error) = level.read_file(level_path) {
+            eprintln!("Failed to open level in editor: {}", error);
+            game.create_message("failed to open level".to_string(), 256);
+            events.menu_quit = false;
+            events.game_paused = true;
+            return
+        }
+    }
+    else {
+        let level_name = std::path::Path::new(level_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        *level = level_manager::LevelManager::blank(&level_name, constants::EDITOR_NEW_LEVEL_SIZE);
+        level.path = level_path.to_string();
+    }
+    reset_level_state(game, player, towers, buildings, enemies, projectiles, upgrade_manager, pathfinding_manager);
+    editor_manager.open_editor(game, level);
+    game.level_updated = true;
+}
+
+fn play_level_music(level: &level_manager::LevelManager) -> Result<sdl2::mixer::Music<'static>, String> {
+    let music_path = level.header.music_path.as_deref().unwrap_or(constants::DEFAULT_MUSIC_PATH);
+    let music = sdl2::mixer::Music::from_file(music_path)?;
+    music.play(-1)?;
+    Ok(music)
+}
 
 fn game_loop (
     game: &mut game_manager::GameManager, 
+    canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
     tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>, 
     events: &mut event_manager::EventManager, 
     player: &mut player_manager::PlayerManager,
@@ -82,6 +269,11 @@ fn game_loop (
     gui_manager: &mut gui_manager::GUIManager,
     menu_manager: &mut menu_manager::MenuManager,
     pathfinding_manager: &mut pathfinding_manager::PathfindingManager,
+    save_manager: &mut save_manager::SaveManager,
+    wave_manager: &mut wave_manager::WaveManager,
+    editor_manager: &mut editor_manager::EditorManager,
+    replay_manager: &mut replay_manager::ReplayManager,
+    music: &mut sdl2::mixer::Music<'static>,
 ) -> Result<(), String> {
     let mut frame_count: u32 = 0;
     let mut last_fps_time = std::time::Instant::now();
@@ -93,42 +285,81 @@ fn game_loop (
         events.performance_frequency = events.timer_subsystem.performance_frequency();
         events.delta_time = (events.current_performance_counter - events.last_performance_counter) as f64 / events.performance_frequency as f64;
 
-        game.prepare_background();
-        events.do_event(game, towers, seed_buttons, build_buttons, gui_manager);
+        game_manager::GameManager::prepare_background(canvas);
+        events.do_event(game, towers, seed_buttons, build_buttons);
         if !events.menu_quit {
-            menu_manager.update_menu(events, game, player);
+            menu_manager.update_menu(events, game, canvas, player);
+        }
+        else if game.editor_mode {
+            editor_manager.update_editor(game, events, player, level, gui_manager);
+            editor_manager.render_editor(game, canvas, tex_man, events, player, level, gui_manager)?;
         }
         else if !events.game_paused {
-            game.update_game(tex_man, events, player, level, towers, buildings, enemies, projectiles, upgrade_manager, gui_manager, minimap_manager,seed_buttons, build_buttons, pathfinding_manager);
-            game.render_game(tex_man, events, player, level, towers, buildings, enemies, projectiles, upgrade_manager, gui_manager, minimap_manager, seed_buttons, build_buttons);
-
+            game.update_game(events, player, level, towers, buildings, enemies, projectiles, upgrade_manager, gui_manager, minimap_manager,seed_buttons, build_buttons, pathfinding_manager, wave_manager, replay_manager);
+            game.render_game(canvas, tex_man, events, player, level, towers, buildings, enemies, projectiles, upgrade_manager, gui_manager, minimap_manager, seed_buttons, build_buttons, wave_manager);
 
-            game.frame_time += 1;
             frame_count += 1;
-            if game.frame_time % 16 == 0 {
+            if frame_count == 16 {
                 let elapsed_fps_time = last_fps_time.elapsed();
                 game.elapsed_seconds = elapsed_fps_time.as_secs_f64();
                 game.fps = (frame_count as f64 / game.elapsed_seconds) as u32;
-/*                 println!("\nFPS: {}\tELAPSED: {:.4}\tFRAME TIME: {}\tPLAYER POS: X: {}\tY: {}\nCARROTS: {}\tTOMATOES: {}\tDELTA TIME: {}\tPATHING: {}\tBUILD_MODE: {}\tSEED_MODE: {}\tUPGRADE_MODE: {}\n", game.fps, game.elapsed_seconds, game.frame_time, player.x, player.y, game.carrot_amount, game.tomato_amount, events.delta_time, game.is_pathfinding, game.build_mode, game.seed_mode, game.upgrade_mode); */
+/*                 println!("\nFPS: {}\tELAPSED: {:.4}\tFRAME TIME: {}\tPLAYER POS: X: {}\tY: {}\nCARROTS: {}\tTOMATOES: {}\tDELTA TIME: {}\tBUILD_MODE: {}\tSEED_MODE: {}\tUPGRADE_MODE: {}\n", game.fps, game.elapsed_seconds, game.frame_time, player.x, player.y, game.carrot_amount, game.tomato_amount, events.delta_time, game.build_mode, game.seed_mode, game.upgrade_mode); */
                 frame_count = 0;
                 last_fps_time = std::time::Instant::now();
             }
-
-            if game.elapsed_seconds < 2.0 {
-                game.is_pathfinding = false;
+        }
+        if let Some(level_path) = events.level_selected.take() {
+            if std::path::Path::new(&level_path) != std::path::Path::new(&level.path) {
+                load_level(&level_path, game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, wave_manager, pathfinding_manager, replay_manager, music);
+            }
+        }
+        if let Some(level_path) = events.level_editing.take() {
+            open_editor(&level_path, game, events, player, level, towers, buildings, enemies, projectiles, upgrade_manager, pathfinding_manager, editor_manager);
+        }
+        if events.editor_closing {
+            //the edited tiles have no towers or buildings placed, read the file again to play it
+            let level_path = if std::path::Path::new(&level.path).exists() {
+                level.path.clone()
+            }
+            else {
+                constants::DEFAULT_LEVEL_PATH.to_string()
+            };
+            load_level(&level_path, game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, wave_manager, pathfinding_manager, replay_manager, music);
+            events.editor_closing = false;
+            events.menu_quit = false;
+            events.game_paused = true;
+        }
+        if events.levels_changed {
+            if let Err(error) = menu_manager.scan_levels() {
+                eprintln!("Failed to scan levels: {}", error);
             }
+            events.levels_changed = false;
         }
         if events.game_saving {
-            println!("SAVING");
-            save_game(game, tex_man, events, player, level, towers, buildings, enemies, projectiles, seed_buttons, build_buttons, gui_manager);
+            save_game(game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, save_manager, wave_manager);
             events.game_saving = false;
         }
         else if events.game_loading {
-            println!("LOADING");
-            load_game(game, tex_man, events, player, level, towers, buildings, enemies, projectiles, seed_buttons, build_buttons, gui_manager);
+            load_game(game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, save_manager, wave_manager, pathfinding_manager, replay_manager);
             events.game_loading = false;
         }
-        game.canvas.present();
+        if events.replay_saving {
+            save_replay(game, replay_manager);
+            events.replay_saving = false;
+        }
+        else if events.replay_loading {
+            play_replay(constants::REPLAY_PATH, game, player, level, towers, buildings, enemies, projectiles, upgrade_manager, wave_manager, pathfinding_manager, replay_manager, music);
+            events.replay_loading = false;
+        }
+        if events.replay_fast_forward {
+            if replay_manager.is_playing {
+                replay_manager.fast_forward = !replay_manager.fast_forward;
+                let state = if replay_manager.fast_forward { "on" } else { "off" };
+                game.create_message(format!("fast forward {}", state), 128);
+            }
+            events.replay_fast_forward = false;
+        }
+        canvas.present();
     }
     Ok(())
 }
@@ -144,41 +375,52 @@ fn main() -> Result<(), String> {
     let medium_font = ttf_context.load_font(font_path, 48)?;
     let large_font = ttf_context.load_font(font_path, 64)?;
 
-    let mut game = game_manager::GameManager::new(&sdl_context);
-    let texture_creator = game.canvas.texture_creator();
+    let mut canvas = game_manager::GameManager::create_canvas(&sdl_context);
+    let mut game = game_manager::GameManager::new();
+    let texture_creator = canvas.texture_creator();
     let mut tex_man = texture_manager::TextureManager::new(&texture_creator);
-    let mut events = event_manager::EventManager::new(&sdl_context, &mut game);
+    let mut events = event_manager::EventManager::new(&sdl_context, &canvas);
     let mut player = player_manager::PlayerManager::new(&mut game, &mut events);
     let mut level = level_manager::LevelManager::new();
     let mut towers = tower_manager::TowerManager::new();
     let mut buildings = building_manager::BuildingManager::new();
     let mut enemies = enemy_manager::EnemyManager::new();
     let mut projectiles = projectile_manager::ProjectileManager::new();
-    let mut upgrade_manager = upgrade_manager::UpgradeManager::new(&mut game, &small_font);
+    let mut upgrade_manager = upgrade_manager::UpgradeManager::new(&mut canvas, &small_font);
     let mut minimap_manager = minimap_manager::MinimapManager::new(&mut game);
     let mut seed_buttons = button_manager::ButtonManager::new(constants::SEED_BUTTON_AMT, button_manager::ButtonType::Seed, &player);
     let mut build_buttons = button_manager::ButtonManager::new(constants::BUILD_BUTTON_AMT, button_manager::ButtonType::Build, &player);
-    let mut gui_manager = gui_manager::GUIManager::new(&mut game, &small_font);
+    let mut gui_manager = gui_manager::GUIManager::new(&mut canvas, &small_font);
     gui_manager.create_inventory_hud(&mut game);
 
-    let mut menu_manager = menu_manager::MenuManager::new(&mut game, &small_font, &medium_font, &large_font);
-    menu_manager.create_menu(&mut game, &mut events);
+    let mut menu_manager = menu_manager::MenuManager::new(&mut canvas, &small_font, &medium_font, &large_font);
+    menu_manager.create_menu(&mut canvas, &mut events);
 
     let mut pathfinding_manager = pathfinding_manager::PathfindingManager::new();
+    let mut save_manager = save_manager::SaveManager::new();
+    let mut wave_manager = wave_manager::WaveManager::new();
+    let mut editor_manager = editor_manager::EditorManager::new();
+    let mut replay_manager = replay_manager::ReplayManager::new();
 
     // TODO: music manager here
     sdl2::mixer::open_audio(44100, sdl2::mixer::DEFAULT_FORMAT, 2, 2048)?;
     sdl2::mixer::allocate_channels(2);
 
-    let audio_chunk = sdl2::mixer::Music::from_file("assets/music/song5.mp3")?;
+    level.read_file(constants::DEFAULT_LEVEL_PATH).unwrap();
+    level.place_entities(&mut game, &mut towers, &mut buildings, &mut enemies);
+    wave_manager.find_spawn_points(&level);
+    wave_manager.load_wave_script(constants::DEFAULT_LEVEL_PATH, &mut game);
+    replay_manager.start_recording(constants::DEFAULT_LEVEL_PATH);
 
-    sdl2::mixer::Music::play(&audio_chunk, -1);
+    let mut audio_chunk = play_level_music(&level)?;
     sdl2::mixer::Music::set_volume(50);
 
-    level.create_level(); 
-    level.read_file("farm.txt").unwrap();
+    //testers send replay files, `farm_defense <replay file>` plays one straight away
+    if let Some(replay_path) = std::env::args().nth(1) {
+        play_replay(&replay_path, &mut game, &mut player, &mut level, &mut towers, &mut buildings, &mut enemies, &mut projectiles, &mut upgrade_manager, &mut wave_manager, &mut pathfinding_manager, &mut replay_manager, &mut audio_chunk);
+    }
 
-    game_loop(&mut game, &mut tex_man, &mut events, &mut player, &mut level, &mut towers, &mut buildings, &mut enemies, &mut projectiles, &mut upgrade_manager, &mut seed_buttons, &mut build_buttons, &mut minimap_manager, &mut gui_manager, &mut menu_manager, &mut pathfinding_manager);
+    game_loop(&mut game, &mut canvas, &mut tex_man, &mut events, &mut player, &mut level, &mut towers, &mut buildings, &mut enemies, &mut projectiles, &mut upgrade_manager, &mut seed_buttons, &mut build_buttons, &mut minimap_manager, &mut gui_manager, &mut menu_manager, &mut pathfinding_manager, &mut save_manager, &mut wave_manager, &mut editor_manager, &mut replay_manager, &mut audio_chunk);
 
     Ok(())
 }
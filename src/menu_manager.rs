@@ -1,4 +1,4 @@
-use crate::{event_manager, game_manager, player_manager, constants, utilities};
+use crate::{event_manager, game_manager, player_manager, constants, utilities, level_manager};
 
 pub struct MenuButton <'a> {
     texture_surface: sdl2::surface::Surface<'a>,
@@ -10,10 +10,20 @@ pub struct MenuButton <'a> {
     last_clicked: i32,
 }
 
+//one level file found in the levels directory
+pub struct LevelEntry <'a> {
+    path: String,
+    button: MenuButton<'a>,
+    thumbnail: sdl2::surface::Surface<'a>,
+}
+
 pub struct MenuManager <'a> {
     pub quit: bool,
     button_vec: Vec<MenuButton<'a>>,
     settings_vec: Vec<MenuButton<'a>>,
+    level_vec: Vec<LevelEntry<'a>>,
+    level_buttons_vec: Vec<MenuButton<'a>>,
+    selected_level: usize,
     resolution_vec: Vec<(u32, u32)>,
     button_amount: usize,
     pub current_resolution: usize,
@@ -26,7 +36,7 @@ pub struct MenuManager <'a> {
 
 impl<'a> MenuManager<'a> {
     pub fn new (
-        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         small_font: &'a sdl2::ttf::Font<'a,
         'a>, medium_font: &'a sdl2::ttf::Font<'a, 'a>,
         large_font: &'a sdl2::ttf::Font<'a,
@@ -36,6 +46,9 @@ impl<'a> MenuManager<'a> {
             quit: false,
             button_vec: Vec::new(),
             settings_vec: Vec::new(),
+            level_vec: Vec::new(),
+            level_buttons_vec: Vec::new(),
+            selected_level: 0,
             resolution_vec: vec![
                 (640, 480),
                 (1280, 720),
@@ -45,7 +58,7 @@ impl<'a> MenuManager<'a> {
             ],
             current_resolution: 0,
             button_amount: 3,
-            texture_creator: game.canvas.texture_creator(),
+            texture_creator: canvas.texture_creator(),
             small_font,
             medium_font,
             large_font,
@@ -53,7 +66,7 @@ impl<'a> MenuManager<'a> {
         };
         menu
     }
-    pub fn create_menu (&mut self, game: &mut game_manager::GameManager, events: &mut event_manager::EventManager) -> Result<(), String> {
+    pub fn create_menu (&mut self,canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, events: &mut event_manager::EventManager) -> Result<(), String> {
         let texture_surface = self.current_font.render(&"farm defense".to_string())
             .blended(constants::COLOR_WHITE)
             .map_err(|e| e.to_string())?;
@@ -164,7 +177,7 @@ impl<'a> MenuManager<'a> {
         };
         let texture_surface;
         let button_text;
-        match game.canvas.window().fullscreen_state() {
+        match canvas.window().fullscreen_state() {
             sdl2::video::FullscreenType::True => {
                 texture_surface = self.current_font.render(&"fullscreen".to_string())
                     .blended(constants::COLOR_WHITE)
@@ -208,8 +221,111 @@ impl<'a> MenuManager<'a> {
         self.settings_vec.push(resolution_minus);
         self.settings_vec.push(resolution_plus);
 
+        for button_text in ["play", "edit", "new", "back"] {
+            let texture_surface = self.current_font.render(button_text)
+                .blended(constants::COLOR_WHITE)
+                .map_err(|e| e.to_string())?;
+            self.level_buttons_vec.push(self::MenuButton {
+                texture_surface,
+                rect: sdl2::rect::Rect::new(0, 0, 0, 0),
+                button_text: button_text.to_string(),
+                clicked: false,
+                hovering_button: false,
+                outline_visible: false,
+                last_clicked: 0,
+            });
+        }
+        self.scan_levels()?;
+
+        Ok(())
+    }
+
+    //every parsable level file in LEVELS_DIRECTORY, sorted by file name
+    pub fn scan_levels(&mut self) -> Result<(), String> {
+        let mut level_paths: Vec<std::path::PathBuf> = match std::fs::read_dir(constants::LEVELS_DIRECTORY) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|extension| extension == constants::LEVEL_FILE_EXTENSION))
+                .collect(),
+            Err(err) => {
+                eprintln!("Failed to read levels directory {}:\t{}", constants::LEVELS_DIRECTORY, err);
+                Vec::new()
+            }
+        };
+        level_paths.sort();
+
+        self.level_vec.clear();
+        for level_path in level_paths {
+            let level_name = level_path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let level = match std::fs::read_to_string(&level_path) {
+                Ok(contents) => match level_manager::LevelManager::parse_level(&contents, &level_name) {
+                    Ok(level) => level,
+                    Err(err) => {
+                        eprintln!("Skipping level {:?}:\t{}", level_path, err);
+                        continue;
+                    }
+                },
+                Err(err) => {
+                    eprintln!("Skipping level {:?}:\t{}", level_path, err);
+                    continue;
+                }
+            };
+
+            let texture_surface = self.current_font.render(&level.header.name)
+                .blended(constants::COLOR_WHITE)
+                .map_err(|e| e.to_string())?;
+            let level_path = level_path.to_string_lossy().to_string();
+            if std::path::Path::new(&level_path) == std::path::Path::new(constants::DEFAULT_LEVEL_PATH) {
+                self.selected_level = self.level_vec.len();
+            }
+            self.level_vec.push(LevelEntry {
+                path: level_path,
+                button: self::MenuButton {
+                    texture_surface,
+                    rect: sdl2::rect::Rect::new(0, 0, 0, 0),
+                    button_text: level.header.name.clone(),
+                    clicked: false,
+                    hovering_button: false,
+                    outline_visible: false,
+                    last_clicked: 0,
+                },
+                thumbnail: Self::create_thumbnail(&level)?,
+            });
+        }
         Ok(())
     }
+
+    //one pixel per tile, scaled up when drawn
+    fn create_thumbnail(level: &level_manager::LevelManager) -> Result<sdl2::surface::Surface<'a>, String> {
+        let mut thumbnail = sdl2::surface::Surface::new(
+            level.width() as u32,
+            level.height() as u32,
+            sdl2::pixels::PixelFormatEnum::RGB888
+        )?;
+        for col_index in 0..level.level_vec.len() {
+            for row_index in 0..level.level_vec[col_index].len() {
+                let color = match level.level_vec[col_index][row_index].tile_type {
+                    level_manager::TileKind::Wall => sdl2::pixels::Color::RGB(90, 90, 90),
+                    level_manager::TileKind::Floor => sdl2::pixels::Color::RGB(160, 140, 110),
+                    level_manager::TileKind::FieldEmpty
+                    | level_manager::TileKind::FieldGrowing
+                    | level_manager::TileKind::FieldHarvestable => sdl2::pixels::Color::RGB(120, 80, 40),
+                    level_manager::TileKind::Mud => sdl2::pixels::Color::RGB(100, 70, 40),
+                    level_manager::TileKind::Water => sdl2::pixels::Color::RGB(40, 90, 200),
+                    level_manager::TileKind::Spawn => sdl2::pixels::Color::RED,
+                    _ => sdl2::pixels::Color::RGB(60, 150, 60),
+                };
+                thumbnail.fill_rect(
+                    sdl2::rect::Rect::new(col_index as i32, row_index as i32, 1, 1),
+                    color
+                )?;
+            }
+        }
+        Ok(thumbnail)
+    }
     pub fn render_menu(&mut self) -> Result<(), String> {
     
         Ok(())
@@ -219,11 +335,15 @@ impl<'a> MenuManager<'a> {
         &mut self,
         events: &mut event_manager::EventManager,
         game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         player: &mut player_manager::PlayerManager
     ) -> Result<(), String> {
+        if events.menu_level_select {
+            return self.update_level_select(events, game, canvas)
+        }
         if !events.menu_settings {
             for menu_button_index in 0..self.button_vec.len() {
-                self.update_menu_buttons(menu_button_index, game);
+                self.update_menu_buttons(menu_button_index, game, canvas);
 
                 let menu_button = &mut self.button_vec[menu_button_index];
 
@@ -243,11 +363,15 @@ impl<'a> MenuManager<'a> {
                     }
                 };
 
-                if let Err(err) = game.canvas.copy(&texture, None, menu_button.rect) {
+                if let Err(err) = canvas.copy(&texture, None, menu_button.rect) {
                     eprintln!("Failed to copy texture to canvas:\t{}", err);
                 }
                 if menu_button.clicked && menu_button.last_clicked > 32 {
-                    if menu_button_index == constants::CURRENT_BUTTON_MENU_PLAY {
+                    if menu_button_index == constants::CURRENT_BUTTON_MENU_PLAY && !self.level_vec.is_empty() {
+                        events.menu_level_select = true;
+                        menu_button.outline_visible = false;
+                    }
+                    else if menu_button_index == constants::CURRENT_BUTTON_MENU_PLAY {
                         events.menu_quit = true;
                         events.game_paused = false;
                     }
@@ -271,7 +395,7 @@ impl<'a> MenuManager<'a> {
             .rect.height() as i32;
         if events.menu_settings {
             for settings_button_index in 0..self.settings_vec.len() {
-                self.update_settings_buttons(settings_button_index, game);
+                self.update_settings_buttons(settings_button_index, game, canvas);
 
                 let resolution_rect_data: (i32, i32, u32, u32) = (
                     self.settings_vec[1].rect.x(),
@@ -332,11 +456,11 @@ impl<'a> MenuManager<'a> {
 
                 }
 
-                if let Err(err) = game.canvas.copy(&texture, None, settings_button.rect) {
+                if let Err(err) = canvas.copy(&texture, None, settings_button.rect) {
                     eprintln!("Failed to copy texture to canvas:\t{}", err);
                 }
                 if settings_button_index == constants::CURRENT_BUTTON_SETTINGS_RESOLUTION_STRING { 
-                    let screen_size_result = game.canvas.window().display_mode();
+                    let screen_size_result = canvas.window().display_mode();
                     let screen_size = match screen_size_result {
                         Ok(screen_size) => screen_size,
                         Err(err) => {
@@ -381,7 +505,7 @@ impl<'a> MenuManager<'a> {
                             }
                             println!("Current res: {}", self.current_resolution);
                             
-                            game.canvas.window_mut().set_size(
+                            canvas.window_mut().set_size(
                                 self.resolution_vec[self.current_resolution].0,
                                 self.resolution_vec[self.current_resolution].1
                             );
@@ -395,16 +519,16 @@ impl<'a> MenuManager<'a> {
                                 _ => self.current_font = self.large_font,
                             }
                             println!("Current res: {}", self.current_resolution);
-                            game.canvas.window_mut().set_size(
+                            canvas.window_mut().set_size(
                                 self.resolution_vec[self.current_resolution].0,
                                 self.resolution_vec[self.current_resolution].1
                             );
                         }
                         constants::CURRENT_BUTTON_SETTINGS_SCREEN_MODE => {
                             let texture_surface: Option<sdl2::surface::Surface> 
-                            = match game.canvas.window().fullscreen_state() {
+                            = match canvas.window().fullscreen_state() {
                                 sdl2::video::FullscreenType::True => {
-                                    if let Err(err) = game.canvas.window_mut()
+                                    if let Err(err) = canvas.window_mut()
                                         .set_fullscreen(sdl2::video::FullscreenType::Off) {
                                         eprintln!("Failed to set fullscreen mode: {}", err);
                                         None
@@ -418,7 +542,7 @@ impl<'a> MenuManager<'a> {
                                     }
                                 },
                                 sdl2::video::FullscreenType::Off => {
-                                    if let Err(err) = game.canvas.window_mut()
+                                    if let Err(err) = canvas.window_mut()
                                         .set_fullscreen(sdl2::video::FullscreenType::Desktop) {
                                         eprintln!("Failed to set fullscreen mode: {}", err);
                                         None
@@ -431,7 +555,7 @@ impl<'a> MenuManager<'a> {
                                     }
                                 },
                                 sdl2::video::FullscreenType::Desktop => {
-                                    if let Err(err) = game.canvas.window_mut()
+                                    if let Err(err) = canvas.window_mut()
                                         .set_fullscreen(sdl2::video::FullscreenType::True) {
                                         eprintln!("Failed to set fullscreen mode: {}", err);
                                         None
@@ -464,7 +588,131 @@ impl<'a> MenuManager<'a> {
         Ok(())
     }
 
-    pub fn update_menu_buttons (&mut self, menu_button_index: usize, game: &mut game_manager::GameManager) {
+    fn update_level_select (
+        &mut self,
+        events: &mut event_manager::EventManager,
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
+    ) -> Result<(), String> {
+        let thumbnail_size = constants::LEVEL_THUMBNAIL_SIZE;
+        let mut row_y = 0;
+        game.hovering_button = false;
+
+        for level_index in 0..self.level_vec.len() {
+            let level_entry = &mut self.level_vec[level_index];
+            let button = &mut level_entry.button;
+            Self::update_button_state(button, game);
+            let row_height = button.texture_surface.height().max(thumbnail_size);
+            let row_width = thumbnail_size + constants::TILE_SIZE + button.texture_surface.width();
+
+            //thumbnail keeps the level's aspect ratio inside a thumbnail_size square
+            let longest_side = level_entry.thumbnail.width().max(level_entry.thumbnail.height()).max(1);
+            let thumbnail_rect = sdl2::rect::Rect::new(
+                events.screen_size.0 / 2 - row_width as i32 / 2,
+                row_y,
+                (level_entry.thumbnail.width() * thumbnail_size / longest_side).max(1),
+                (level_entry.thumbnail.height() * thumbnail_size / longest_side).max(1)
+            );
+            button.rect.set_x(thumbnail_rect.x() + (thumbnail_size + constants::TILE_SIZE) as i32);
+            button.rect.set_y(row_y + (row_height - button.texture_surface.height()) as i32 / 2);
+            button.rect.set_width(button.texture_surface.width());
+            button.rect.set_height(button.texture_surface.height());
+            row_y += row_height as i32;
+
+            for (surface, rect) in [(&level_entry.thumbnail, thumbnail_rect), (&button.texture_surface, button.rect)] {
+                match self.texture_creator.create_texture_from_surface(surface) {
+                    Ok(texture) => {
+                        if let Err(err) = canvas.copy(&texture, None, rect) {
+                            eprintln!("Failed to copy texture to canvas:\t{}", err);
+                        }
+                    }
+                    Err(err) => eprintln!("Failed to create texture from surface:\t{}", err),
+                }
+            }
+
+            if button.clicked && button.last_clicked > 32 {
+                self.selected_level = level_index;
+                button.last_clicked = 0;
+            }
+            button.last_clicked += 1;
+            if level_index == self.selected_level {
+                utilities::draw_rect_outline(canvas, thumbnail_rect);
+                utilities::draw_rect_outline(canvas, button.rect);
+            }
+        }
+
+        for button_index in 0..self.level_buttons_vec.len() {
+            let button = &mut self.level_buttons_vec[button_index];
+            Self::update_button_state(button, game);
+            button.rect.set_x(events.screen_size.0 / 2 - button.texture_surface.width() as i32 / 2);
+            button.rect.set_y(row_y);
+            button.rect.set_width(button.texture_surface.width());
+            button.rect.set_height(button.texture_surface.height());
+            row_y += button.texture_surface.height() as i32;
+
+            match self.texture_creator.create_texture_from_surface(&button.texture_surface) {
+                Ok(texture) => {
+                    if let Err(err) = canvas.copy(&texture, None, button.rect) {
+                        eprintln!("Failed to copy texture to canvas:\t{}", err);
+                    }
+                }
+                Err(err) => eprintln!("Failed to create texture from surface:\t{}", err),
+            }
+
+            if button.clicked && button.last_clicked > 32 {
+                match button_index {
+                    constants::CURRENT_BUTTON_LEVELS_PLAY => {
+                        events.level_selected = Some(self.level_vec[self.selected_level].path.clone());
+                        events.menu_level_select = false;
+                        events.menu_quit = true;
+                        events.game_paused = false;
+                    }
+                    constants::CURRENT_BUTTON_LEVELS_EDIT => {
+                        events.level_editing = Some(self.level_vec[self.selected_level].path.clone());
+                        events.menu_level_select = false;
+                        events.menu_quit = true;
+                        events.game_paused = false;
+                    }
+                    constants::CURRENT_BUTTON_LEVELS_NEW => {
+                        events.level_editing = Some(Self::new_level_path());
+                        events.menu_level_select = false;
+                        events.menu_quit = true;
+                        events.game_paused = false;
+                    }
+                    constants::CURRENT_BUTTON_LEVELS_BACK => {
+                        events.menu_level_select = false;
+                    }
+                    _ => {},
+                }
+                button.outline_visible = false;
+                button.last_clicked = 0;
+            }
+            button.last_clicked += 1;
+        }
+        Ok(())
+    }
+
+    //first levels/level<n>.txt that isn't taken
+    fn new_level_path() -> String {
+        let mut level_number = 0;
+        loop {
+            let level_path = format!("{}/level{}.{}", constants::LEVELS_DIRECTORY, level_number, constants::LEVEL_FILE_EXTENSION);
+            if !std::path::Path::new(&level_path).exists() {
+                return level_path
+            }
+            level_number += 1;
+        }
+    }
+
+    fn update_button_state(button: &mut MenuButton, game: &mut game_manager::GameManager) {
+        button.hovering_button = sdl2::rect::Rect::contains_point(&button.rect, game.mouse_point);
+        button.clicked = button.hovering_button && game.mouse_button == sdl2::mouse::MouseButton::Left;
+        if button.hovering_button {
+            game.hovering_button = true;
+        }
+    }
+
+    pub fn update_menu_buttons (&mut self, menu_button_index: usize, game: &mut game_manager::GameManager, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>) {
         let button = &mut self.button_vec[menu_button_index];
         if sdl2::rect::Rect::contains_point(&button.rect, game.mouse_point) {
             button.hovering_button = true;
@@ -488,7 +736,7 @@ impl<'a> MenuManager<'a> {
             button.outline_visible = true;
         }
         if button.outline_visible {
-            utilities::draw_rect_outline(game, button.rect);
+            utilities::draw_rect_outline(canvas, button.rect);
             for other_button_index in 0..self.button_vec.len() {
                 let other_button = &mut self.button_vec[other_button_index];
                 if other_button_index != menu_button_index {
@@ -508,7 +756,7 @@ impl<'a> MenuManager<'a> {
             game.hovering_button = false;
         }
     }
-    pub fn update_settings_buttons (&mut self, settings_button_index: usize, game: &mut game_manager::GameManager) {
+    pub fn update_settings_buttons (&mut self, settings_button_index: usize, game: &mut game_manager::GameManager, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>) {
         let button = &mut self.settings_vec[settings_button_index];
         if sdl2::rect::Rect::contains_point(&button.rect, game.mouse_point) {
             button.hovering_button = true;
@@ -532,7 +780,7 @@ impl<'a> MenuManager<'a> {
             button.outline_visible = true;
         }
         if button.outline_visible {
-            utilities::draw_rect_outline(game, button.rect);
+            utilities::draw_rect_outline(canvas, button.rect);
             for other_button_index in 0..self.settings_vec.len() {
                 let other_button = &mut self.settings_vec[other_button_index];
                 if other_button_index != settings_button_index {
@@ -1,5 +1,5 @@
 //do bitmap load
-use crate::{constants, projectile_manager, gui_manager, game_manager, building_manager, player_manager, event_manager, texture_manager::TextureManager, tower_manager, enemy_manager, level_manager};
+use crate::{constants, projectile_manager, gui_manager, game_manager, building_manager, player_manager, texture_manager::TextureManager, tower_manager, enemy_manager, level_manager};
 
 
 pub struct MinimapManager <'a> {
@@ -18,11 +18,11 @@ impl<'a> MinimapManager <'a> {
         minimap
     }
     pub fn update_minimap(&mut self, 
-        events: &mut event_manager::EventManager, 
+        game: &mut game_manager::GameManager, 
         level: &mut level_manager::LevelManager,
         tex_man: &mut TextureManager<sdl2::video::WindowContext>
     ) -> Result<(), String> {
-        if events.level_updated {
+        if game.level_updated {
             println!("UPDATING MINIMAP");
             for col_index in 0..level.level_vec.len() {
                 for row_index in 0..level.level_vec[col_index].len() {
@@ -31,35 +31,43 @@ impl<'a> MinimapManager <'a> {
 
                 }
             }
-            events.level_updated = false;
+            game.level_updated = false;
         }
         Ok(())
     }
     pub fn render_minimap(&mut self,
-        game: &mut game_manager::GameManager, 
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         level: &mut level_manager::LevelManager,
         tex_man: &mut TextureManager<sdl2::video::WindowContext>,
         player: &mut player_manager::PlayerManager,
     ) -> Result<(), String> {
+        //longest level side fits in MINIMAP_SIZE pixels, smaller levels get bigger tiles
+        let longest_side = level.width().max(level.height()).max(1);
+        let minimap_offset = |index: usize| (index * constants::MINIMAP_SIZE as usize / longest_side) as i32;
+        let player_index = (
+            ((player.rect.x() + player.x) / constants::TILE_SIZE as i32) as usize,
+            ((player.rect.y() + player.y) / constants::TILE_SIZE as i32) as usize
+        );
+
         for col_index in 0..level.level_vec.len() {
             for row_index in 0..level.level_vec[col_index].len() {
                 let texture_path = &level.level_vec[col_index][row_index].texture_path;
                 let mut rect = level.level_vec[col_index][row_index].rect;
 
-                rect.set_x(col_index as i32);
-                rect.set_y(row_index as i32);
-                rect.set_width(1);
-                rect.set_height(1);
+                rect.set_x(minimap_offset(col_index));
+                rect.set_y(minimap_offset(row_index));
+                rect.set_width((minimap_offset(col_index + 1) - rect.x()).max(1) as u32);
+                rect.set_height((minimap_offset(row_index + 1) - rect.y()).max(1) as u32);
 
-                if level.level_vec[col_index][row_index].rect.has_intersection(player.rect) {
-                    game.canvas.set_draw_color(sdl2::pixels::Color::RED);
-                    game.canvas.fill_rect(rect);
+                if (col_index, row_index) == player_index {
+                    canvas.set_draw_color(sdl2::pixels::Color::RED);
+                    canvas.fill_rect(rect);
                 } 
                 else {
                     let texture = tex_man.load(&texture_path)?;
 
 
-                    game.canvas.copy_ex(
+                    canvas.copy_ex(
                         &texture,
                         None,
                         rect,
@@ -82,7 +90,7 @@ impl<'a> MinimapManager <'a> {
         // /*         println!("Loading minimap texture: {}", self.texture_path); */
         //         let texture = tex_man.load(&self.texture_path)?;
         //
-        //         game.canvas.copy_ex(
+        //         canvas.copy_ex(
         //             &texture,
         //             None,
         //             rect,
@@ -24,160 +24,724 @@ impl PartialOrd for PathState {
 }
 
 
+pub struct PlacementCheck {
+    pub tile: (usize, usize),
+    pub base: (usize, usize),
+    pub keeps_path: bool,
+}
+
+//dijkstra map towards one target, distance is indexed the same as level_vec
+pub struct FlowField {
+    pub target: (usize, usize),
+    pub distance: Vec<Vec<u32>>,
+    //siege fields go through walls, priced by how much wall is left to break
+    pub is_siege: bool,
+}
+
+//a* search that can stop when the frame's node budget is used up and resume later
+pub struct PathSearch {
+    pub request_id: u32,
+    pub start: (usize, usize),
+    pub target: (usize, usize),
+    pub is_flying: bool,
+    pub frontier: std::collections::BinaryHeap<PathState>,
+    pub costs: std::collections::HashMap<(usize, usize), usize>,
+    pub came_from: std::collections::HashMap<(usize, usize), (usize, usize)>,
+}
+
 pub struct PathfindingManager {
-    pub frontier: Option<std::collections::BinaryHeap<PathState>>,
-    pub unsearched: Vec<i8>,
+    pub path_queue: std::collections::VecDeque<PathSearch>,
+    pub finished_paths: std::collections::HashMap<u32, Vec<(usize, usize)>>,
+    pub next_request_id: u32,
+    pub flow_fields: Vec<FlowField>,
+    pub siege_fields: Vec<FlowField>,
+    //tiles whose pathability changed since the fields were last updated
+    pub dirty_tiles: Vec<(usize, usize)>,
+    //last placement checked, the preview asks every frame
+    pub placement_check: Option<PlacementCheck>,
 }
 
 impl PathfindingManager {
     pub fn new () -> PathfindingManager {
         let pathfinding_manager = PathfindingManager {
-            frontier: None,
-            unsearched: Vec::new(),
+            path_queue: std::collections::VecDeque::new(),
+            finished_paths: std::collections::HashMap::new(),
+            next_request_id: 0,
+            flow_fields: Vec::new(),
+            siege_fields: Vec::new(),
+            dirty_tiles: Vec::new(),
+            placement_check: None,
         };
         pathfinding_manager
     }
-    pub fn create_frontier(
+
+    pub fn clear_flow_fields(&mut self) {
+        self.flow_fields.clear();
+        self.siege_fields.clear();
+        self.dirty_tiles.clear();
+        self.placement_check = None;
+    }
+
+    pub fn mark_dirty(&mut self, tile: (usize, usize)) {
+        self.dirty_tiles.push(tile);
+        self.placement_check = None;
+        //half finished searches that got to the tile saw it the old way, start those over
+        //a tile that just opened was never reached itself, so reaching a neighbour counts
+        for path_search in &mut self.path_queue {
+            let is_explored = (tile.0.saturating_sub(1)..=tile.0 + 1)
+                .flat_map(|col| (tile.1.saturating_sub(1)..=tile.1 + 1).map(move |row| (col, row)))
+                .any(|index| path_search.costs.contains_key(&index));
+            if is_explored {
+                *path_search = Self::create_frontier(
+                    path_search.request_id,
+                    path_search.start,
+                    path_search.target,
+                    path_search.is_flying
+                );
+            }
+        }
+    }
+
+    //false if blocking tile would cut a spawn that can reach the base off from it
+    pub fn placement_keeps_path(
         &mut self,
+        tile: (usize, usize),
+        base_location: Option<(usize, usize)>,
+        spawn_points: &[(usize, usize)],
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> bool {
+        let base = match base_location {
+            Some(base) => base,
+            None => return true,
+        };
+        if let Some(placement_check) = &self.placement_check {
+            if placement_check.tile == tile && placement_check.base == base {
+                return placement_check.keeps_path
+            }
+        }
+
+        let keeps_path = if tile == base {
+            false
+        }
+        else {
+            let reachable = Self::reachable_from(base, None, level_vec);
+            let reachable_after = Self::reachable_from(base, Some(tile), level_vec);
+            spawn_points.iter().all(|spawn_point| {
+                !reachable[spawn_point.0][spawn_point.1]
+                || reachable_after[spawn_point.0][spawn_point.1]
+            })
+        };
+        self.placement_check = Some(PlacementCheck {
+            tile,
+            base,
+            keeps_path,
+        });
+        keeps_path
+    }
+
+    fn reachable_from(
         start: (usize, usize),
+        blocked: Option<(usize, usize)>,
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> Vec<Vec<bool>> {
+        let mut reachable: Vec<Vec<bool>> = level_vec.iter().map(|col| vec![false; col.len()]).collect();
+        let mut queue = std::collections::VecDeque::from([start]);
+        reachable[start.0][start.1] = true;
+
+        while let Some(current) = queue.pop_front() {
+            for next in Self::get_neighbors(current, level_vec, false) {
+                //the blocked tile also closes the diagonal steps around it
+                let squeezes_past_blocked = blocked == Some((next.0, current.1))
+                    || blocked == Some((current.0, next.1));
+                if Some(next) != blocked && !squeezes_past_blocked && !reachable[next.0][next.1] {
+                    reachable[next.0][next.1] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        reachable
+    }
+
+    //drops fields for targets that are gone and applies pending tile changes
+    pub fn update_flow_fields(
+        &mut self,
+        target_vec: &[(usize, usize)],
         level_vec: &[Vec<level_manager::LevelTile>]
     ) {
-        let initial_state = PathState {
-            position: start,
-            priority: Self::heuristic(start, (10, 10)),
-        };
+        self.flow_fields.retain(|flow_field| target_vec.contains(&flow_field.target));
+        self.siege_fields.retain(|flow_field| target_vec.contains(&flow_field.target));
 
-        let frontier: std::collections::BinaryHeap<PathState> = [initial_state].into();
-        self.frontier = Some(frontier);
+        let dirty_tiles = std::mem::take(&mut self.dirty_tiles);
+        //sieges are rare, rebuild those fields from scratch when asked again
+        if !dirty_tiles.is_empty() {
+            self.siege_fields.clear();
+        }
+        for flow_field in &mut self.flow_fields {
+            for &dirty_tile in &dirty_tiles {
+                //a cost change is handled as removing the tile and adding it back
+                Self::close_tile(flow_field, dirty_tile, level_vec);
+                //the target stays the target even when a tower or the base covers it
+                if dirty_tile == flow_field.target
+                || utilities::tile_pathable(&level_vec[dirty_tile.0][dirty_tile.1]) {
+                    Self::open_tile(flow_field, dirty_tile, level_vec);
+                }
+            }
+        }
     }
-    pub fn astar(
+
+    //next tile towards target, fields are built the first time a target is asked for
+    pub fn next_step(
+        &mut self,
+        target: (usize, usize),
+        position: (usize, usize),
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> Option<(usize, usize)> {
+        let field_index = match self.flow_fields.iter().position(|flow_field| flow_field.target == target) {
+            Some(field_index) => field_index,
+            None => {
+                self.flow_fields.push(Self::create_flow_field(target, level_vec, false));
+                self.flow_fields.len() - 1
+            }
+        };
+        Self::lowest_neighbor(&self.flow_fields[field_index], position, level_vec)
+    }
+
+    //next tile towards target when walls are in the way, may be a wall to break
+    pub fn next_siege_step(
         &mut self,
-        enemy: &mut enemy_manager::Enemy,
         target: (usize, usize),
+        position: (usize, usize),
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> Option<(usize, usize)> {
+        let field_index = match self.siege_fields.iter().position(|flow_field| flow_field.target == target) {
+            Some(field_index) => field_index,
+            None => {
+                self.siege_fields.push(Self::create_flow_field(target, level_vec, true));
+                self.siege_fields.len() - 1
+            }
+        };
+        Self::lowest_neighbor(&self.siege_fields[field_index], position, level_vec)
+    }
+
+    //how far the enemy still has to go to its target, towers targeting first/last compare this
+    //flyers and enemies whose field isn't built yet go by a straight line
+    pub fn remaining_distance(&self, enemy: &enemy_manager::Enemy) -> u32 {
+        let Some(target) = enemy.current_target else {
+            return u32::MAX
+        };
+        let position = enemy.grid_index;
+        let field_distance = |flow_fields: &[FlowField]| flow_fields.iter()
+            .find(|flow_field| flow_field.target == target)
+            .and_then(|flow_field| flow_field.distance.get(position.0)?.get(position.1).copied())
+            .filter(|distance| *distance != u32::MAX);
+        if !enemy.enemy_type.stats().is_flying {
+            if let Some(distance) = field_distance(&self.flow_fields).or_else(|| field_distance(&self.siege_fields)) {
+                return distance
+            }
+        }
+        let dx = position.0.abs_diff(target.0) as u32;
+        let dy = position.1.abs_diff(target.1) as u32;
+        (dx.min(dy) * constants::PATH_STEP_DIAGONAL + dx.abs_diff(dy) * constants::PATH_STEP_STRAIGHT)
+            * constants::TILE_COST_GRASS
+    }
+
+    fn lowest_neighbor(
+        flow_field: &FlowField,
+        position: (usize, usize),
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> Option<(usize, usize)> {
+        let current_distance = *flow_field.distance.get(position.0)?.get(position.1)?;
+
+        Self::get_neighbors(position, level_vec, flow_field.is_siege).into_iter()
+            .filter(|next| flow_field.distance[next.0][next.1] < current_distance)
+            .min_by_key(|next| flow_field.distance[next.0][next.1]
+                .saturating_add(Self::step_cost(position, *next, level_vec)))
+    }
+
+    pub fn create_flow_field(
+        target: (usize, usize),
+        level_vec: &[Vec<level_manager::LevelTile>],
+        is_siege: bool,
+    ) -> FlowField {
+        let mut flow_field = FlowField {
+            target,
+            distance: level_vec.iter().map(|col| vec![u32::MAX; col.len()]).collect(),
+            is_siege,
+        };
+        flow_field.distance[target.0][target.1] = 0;
+        Self::propagate(&mut flow_field, vec![target], level_vec);
+        flow_field
+    }
+
+    //cost of walking off the from tile, matches how move_enemies scales speed
+    fn step_cost(
+        from: (usize, usize),
+        to: (usize, usize),
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> u32 {
+        let tile = &level_vec[from.0][from.1];
+        let tile_cost = if utilities::tile_pathable(tile) {
+            utilities::tile_cost(tile)
+        }
+        else {
+            //only siege fields step off walls
+            constants::TILE_COST_GRASS
+            + constants::TILE_COST_WALL_BREACH * tile.health as u32 / constants::WALL_HEALTH as u32
+        };
+        tile_cost * Self::step_length(from, to)
+    }
+
+    fn step_length(from: (usize, usize), to: (usize, usize)) -> u32 {
+        if from.0 != to.0 && from.1 != to.1 {
+            constants::PATH_STEP_DIAGONAL
+        }
+        else {
+            constants::PATH_STEP_STRAIGHT
+        }
+    }
+
+    //dijkstra outwards from already settled tiles, only lowers distances
+    fn propagate(
+        flow_field: &mut FlowField,
+        start_tiles: Vec<(usize, usize)>,
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) {
+        let mut frontier: std::collections::BinaryHeap<PathState> = start_tiles.into_iter()
+            .map(|position| PathState {
+                position,
+                priority: flow_field.distance[position.0][position.1] as usize,
+            })
+            .collect();
+
+        while let Some(current_state) = frontier.pop() {
+            let current = current_state.position;
+            if current_state.priority > flow_field.distance[current.0][current.1] as usize {
+                continue;
+            }
+            //enemies walk towards the target, so look at who can step into current
+            for next in Self::get_neighbors(current, level_vec, flow_field.is_siege) {
+                let new_distance = flow_field.distance[current.0][current.1]
+                    .saturating_add(Self::step_cost(next, current, level_vec));
+                if new_distance < flow_field.distance[next.0][next.1] {
+                    flow_field.distance[next.0][next.1] = new_distance;
+                    frontier.push(PathState {
+                        position: next,
+                        priority: new_distance as usize,
+                    });
+                }
+            }
+        }
+    }
+
+    //a tile became walkable, distances can only shrink around it
+    fn open_tile(
+        flow_field: &mut FlowField,
+        tile: (usize, usize),
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) {
+        if tile == flow_field.target {
+            flow_field.distance[tile.0][tile.1] = 0;
+        }
+        else {
+            let best_distance = Self::get_neighbors(tile, level_vec, false).into_iter()
+                .map(|next| flow_field.distance[next.0][next.1]
+                    .saturating_add(Self::step_cost(tile, next, level_vec)))
+                .min()
+                .unwrap_or(u32::MAX);
+            if best_distance < flow_field.distance[tile.0][tile.1] {
+                flow_field.distance[tile.0][tile.1] = best_distance;
+            }
+        }
+        //opening a tile can also open diagonal steps between its neighbors
+        let mut start_tiles: Vec<(usize, usize)> = Self::get_neighbors(tile, level_vec, true).into_iter()
+            .filter(|next| flow_field.distance[next.0][next.1] != u32::MAX)
+            .collect();
+        if flow_field.distance[tile.0][tile.1] != u32::MAX {
+            start_tiles.push(tile);
+        }
+        Self::propagate(flow_field, start_tiles, level_vec);
+    }
+
+    //a tile became blocked or slower, only tiles whose route went through it are recomputed
+    fn close_tile(
+        flow_field: &mut FlowField,
+        tile: (usize, usize),
         level_vec: &[Vec<level_manager::LevelTile>]
     ) {
-        println!("EXECUTING A*"); 
+        let mut affected = Vec::new();
+        let mut is_affected = std::collections::HashSet::new();
+        if flow_field.distance[tile.0][tile.1] != u32::MAX {
+            affected.push(tile);
+            is_affected.insert(tile);
+        }
+        //closing a tile also removes diagonal steps between its neighbors
+        let sides = Self::get_neighbors(tile, level_vec, true);
+        for &from in &sides {
+            for &to in &sides {
+                let from_distance = flow_field.distance[from.0][from.1];
+                if from.0.abs_diff(to.0) == 1 && from.1.abs_diff(to.1) == 1
+                && from_distance != u32::MAX
+                && from_distance == flow_field.distance[to.0][to.1]
+                    .saturating_add(Self::step_cost(from, to, level_vec))
+                && is_affected.insert(from) {
+                    affected.push(from);
+                }
+            }
+        }
+        if affected.is_empty() {
+            return
+        }
+
+        let mut affected_index = 0;
+        while affected_index < affected.len() {
+            let current = affected[affected_index];
+            affected_index += 1;
+            let current_distance = flow_field.distance[current.0][current.1];
+
+            for next in Self::get_neighbors(current, level_vec, false) {
+                let next_distance = flow_field.distance[next.0][next.1];
+                if next_distance != u32::MAX
+                && next_distance == current_distance.saturating_add(Self::step_cost(next, current, level_vec))
+                && is_affected.insert(next) {
+                    affected.push(next);
+                }
+            }
+        }
+
+        for &position in &affected {
+            flow_field.distance[position.0][position.1] = u32::MAX;
+        }
+
+        //reseed the cleared region from its untouched border
+        let mut start_tiles = Vec::new();
+        for &position in &affected {
+            if position == tile || !utilities::tile_pathable(&level_vec[position.0][position.1]) {
+                continue;
+            }
+            let best_distance = Self::get_neighbors(position, level_vec, false).into_iter()
+                .filter(|next| !is_affected.contains(next))
+                .map(|next| flow_field.distance[next.0][next.1]
+                    .saturating_add(Self::step_cost(position, next, level_vec)))
+                .min()
+                .unwrap_or(u32::MAX);
+            if best_distance != u32::MAX {
+                flow_field.distance[position.0][position.1] = best_distance;
+                start_tiles.push(position);
+            }
+        }
+        Self::propagate(flow_field, start_tiles, level_vec);
+    }
+    pub fn create_frontier(
+        request_id: u32,
+        start: (usize, usize),
+        target: (usize, usize),
+        is_flying: bool,
+    ) -> PathSearch {
         let initial_state = PathState {
-            position: enemy.grid_index,
-            priority: Self::heuristic(enemy.grid_index, target),
+            position: start,
+            priority: Self::heuristic(start, target) * Self::min_tile_cost(is_flying) as usize,
         };
 
-        let mut frontier: std::collections::BinaryHeap<PathState> 
-        = [initial_state].into();
-        let mut priorities: std::collections::HashMap<(usize, usize), usize> 
-        = std::collections::HashMap::new();
-        let mut came_from: std::collections::HashMap<(usize, usize), (usize, usize)> 
-        = std::collections::HashMap::new();
+        let path_search = PathSearch {
+            request_id,
+            start,
+            target,
+            is_flying,
+            frontier: [initial_state].into(),
+            costs: [(start, 0)].into(),
+            came_from: std::collections::HashMap::new(),
+        };
+        path_search
+    }
 
-        priorities.insert(enemy.grid_index, initial_state.priority);
+    //flying enemies ignore terrain, ground enemies can at best cross floor
+    fn min_tile_cost(is_flying: bool) -> u32 {
+        if is_flying {
+            constants::TILE_COST_GRASS
+        }
+        else {
+            constants::TILE_COST_FLOOR
+        }
+    }
 
-        while let Some(current_state) = frontier.pop() {
+    //queues a search, the enemy keeps the id and picks the path up once it's done
+    pub fn request_path(
+        &mut self,
+        enemy: &enemy_manager::Enemy,
+        target: (usize, usize),
+    ) -> u32 {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+        self.path_queue.push_back(Self::create_frontier(
+            request_id,
+            enemy.grid_index,
+            target,
+            enemy.enemy_type.stats().is_flying
+        ));
+        request_id
+    }
+
+    //an empty path means the target can't be reached
+    pub fn take_path(&mut self, request_id: u32) -> Option<Vec<(usize, usize)>> {
+        self.finished_paths.remove(&request_id)
+    }
+
+    //forget searches and results nobody is waiting for anymore
+    pub fn retain_path_requests(&mut self, request_ids: &[u32]) {
+        self.path_queue.retain(|path_search| request_ids.contains(&path_search.request_id));
+        self.finished_paths.retain(|request_id, _| request_ids.contains(request_id));
+    }
+
+    pub fn clear_path_requests(&mut self) {
+        self.path_queue.clear();
+        self.finished_paths.clear();
+    }
+
+    //expands at most PATH_NODE_BUDGET nodes, a search that runs out resumes next frame
+    pub fn process_path_queue(
+        &mut self,
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) {
+        let mut node_budget = constants::PATH_NODE_BUDGET;
+        while node_budget > 0 {
+            let Some(path_search) = self.path_queue.front_mut() else {
+                return
+            };
+            if let Some(path) = Self::continue_search(path_search, &mut node_budget, level_vec) {
+                let request_id = path_search.request_id;
+                let path = Self::smooth_path(&path, level_vec, path_search.is_flying);
+                self.path_queue.pop_front();
+                self.finished_paths.insert(request_id, path);
+            }
+        }
+    }
+
+    //returns the tile by tile path once the search is done, none while there are nodes left to expand
+    fn continue_search(
+        path_search: &mut PathSearch,
+        node_budget: &mut usize,
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> Option<Vec<(usize, usize)>> {
+        let min_tile_cost = Self::min_tile_cost(path_search.is_flying) as usize;
+
+        while *node_budget > 0 {
+            let Some(current_state) = path_search.frontier.pop() else {
+                return Some(Vec::new())
+            };
+            *node_budget -= 1;
             let current = current_state.position;
 
-            if current == target {
+            if current == path_search.target {
                 let mut path = vec![current];
                 let mut current = current;
-                while let Some(&prev) = came_from.get(&current) {
+                while let Some(&prev) = path_search.came_from.get(&current) {
                     path.push(prev);
                     current = prev;
                 }
                 path.reverse();
-                enemy.final_path = Some(path);
-                println!("ASTAR PATH: {:?}", enemy.final_path);
-                return
+                return Some(path)
             }
 
-            let neighbors = Self::get_neighbors(current, level_vec);
+            let neighbors = Self::get_neighbors(current, level_vec, path_search.is_flying);
 
             for next in neighbors {
-                let new_cost = 1;
-                let priority = new_cost + Self::heuristic(next, target);
+                let step_cost = if path_search.is_flying {
+                    constants::TILE_COST_GRASS * Self::step_length(current, next)
+                }
+                else {
+                    Self::step_cost(current, next, level_vec)
+                };
+                let new_cost = path_search.costs[&current] + step_cost as usize;
 
-                if !priorities.contains_key(&next) || priority < priorities[&next] {
-                    priorities.insert(next, priority);
-                    frontier.push(PathState {
+                if !path_search.costs.contains_key(&next) || new_cost < path_search.costs[&next] {
+                    path_search.costs.insert(next, new_cost);
+                    path_search.frontier.push(PathState {
                         position: next,
-                        priority,
+                        priority: new_cost + Self::heuristic(next, path_search.target) * min_tile_cost,
                     });
-                    came_from.insert(next, current);
+                    path_search.came_from.insert(next, current);
                 }
             }
         }
+        None
     }
-    pub fn astar_new(
+
+    //runs a whole search at once, skips the queue
+    pub fn astar(
         &mut self,
         enemy: &mut enemy_manager::Enemy,
         target: (usize, usize),
         level_vec: &[Vec<level_manager::LevelTile>]
     ) {
+        let is_flying = enemy.enemy_type.stats().is_flying;
+        enemy.final_path = Self::find_path(enemy.grid_index, target, is_flying, level_vec)
+            .map(|path| Self::smooth_path(&path, level_vec, is_flying));
+    }
+
+    //the same search on nothing but a grid, every tile from start to target without smoothing
+    pub fn find_path(
+        start: (usize, usize),
+        target: (usize, usize),
+        is_flying: bool,
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> Option<Vec<(usize, usize)>> {
+        let is_on_map = |index: (usize, usize)| level_vec.get(index.0).is_some_and(|col| index.1 < col.len());
+        if !is_on_map(start) || !is_on_map(target) {
+            return None
+        }
+        let mut path_search = Self::create_frontier(0, start, target, is_flying);
+        let mut node_budget = usize::MAX;
+        Self::continue_search(&mut path_search, &mut node_budget, level_vec)
+            .filter(|path| !path.is_empty())
+    }
+
+    //what a path costs to walk, the same units the search minimizes
+    pub fn path_cost(
+        path: &[(usize, usize)],
+        is_flying: bool,
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> u32 {
+        path.windows(2)
+            .map(|step| if is_flying {
+                constants::TILE_COST_GRASS * Self::step_length(step[0], step[1])
+            }
+            else {
+                Self::step_cost(step[0], step[1], level_vec)
+            })
+            .sum()
+    }
+
+    //string pulling, drops every point that can be seen from the one before it
+    pub fn smooth_path(
+        path: &[(usize, usize)],
+        level_vec: &[Vec<level_manager::LevelTile>],
+        ignore_walls: bool,
+    ) -> Vec<(usize, usize)> {
+        if path.len() <= 2 {
+            return path.to_vec()
+        }
+        let mut smoothed = vec![path[0]];
+        let mut anchor = 0;
 
+        while anchor < path.len() - 1 {
+            let mut farthest = anchor + 1;
+            for candidate in (anchor + 2..path.len()).rev() {
+                //never shortcut over terrain slower than what the path already crossed
+                let max_tile_cost = path[anchor..=candidate].iter()
+                    .map(|tile| utilities::tile_cost(&level_vec[tile.0][tile.1]))
+                    .max()
+                    .unwrap_or(constants::TILE_COST_GRASS);
+                if ignore_walls 
+                || Self::line_of_sight(path[anchor], path[candidate], max_tile_cost, level_vec) {
+                    farthest = candidate;
+                    break;
+                }
+            }
+            smoothed.push(path[farthest]);
+            anchor = farthest;
+        }
+        smoothed
     }
-    fn get_neighbors(start: (usize, usize), level_vec: &[Vec<level_manager::LevelTile>]) -> Vec<(usize, usize)> {
+
+    //walks every tile the line between tile centers touches, corners count both sides
+    pub fn line_of_sight(
+        start: (usize, usize),
+        end: (usize, usize),
+        max_tile_cost: u32,
+        level_vec: &[Vec<level_manager::LevelTile>]
+    ) -> bool {
+        let is_clear = |tile: (isize, isize)| {
+            let tile = &level_vec[tile.0 as usize][tile.1 as usize];
+            utilities::tile_pathable(tile) && utilities::tile_cost(tile) <= max_tile_cost
+        };
+        let dx = (end.0 as isize - start.0 as isize).abs();
+        let dy = (end.1 as isize - start.1 as isize).abs();
+        let step_x = if end.0 > start.0 { 1 } else { -1 };
+        let step_y = if end.1 > start.1 { 1 } else { -1 };
+        let (mut x, mut y) = (start.0 as isize, start.1 as isize);
+        let (mut moved_x, mut moved_y) = (0, 0);
+
+        while moved_x < dx || moved_y < dy {
+            let decision = (1 + 2 * moved_x) * dy - (1 + 2 * moved_y) * dx;
+            if decision == 0 {
+                if !is_clear((x + step_x, y)) || !is_clear((x, y + step_y)) {
+                    return false
+                }
+                x += step_x;
+                y += step_y;
+                moved_x += 1;
+                moved_y += 1;
+            }
+            else if decision < 0 {
+                x += step_x;
+                moved_x += 1;
+            }
+            else {
+                y += step_y;
+                moved_y += 1;
+            }
+            if !is_clear((x, y)) {
+                return false
+            }
+        }
+        true
+    }
+
+    fn get_neighbors(
+        start: (usize, usize),
+        level_vec: &[Vec<level_manager::LevelTile>],
+        ignore_walls: bool,
+    ) -> Vec<(usize, usize)> {
         let (x, y) = start;
-        let width = level_vec[0].len();
-        let height = level_vec.len();
+        let width = level_vec.len();
+        let height = level_vec[0].len();
         let mut neighbors = Vec::with_capacity(8);
-        let top_tile = &level_vec[x][y - 1];
-        let bottom_tile = &level_vec[x][y + 1]; 
-        let left_tile = &level_vec[x - 1][y];
-        let right_tile = &level_vec[x + 1][y];
-        let top_left_tile = &level_vec[x - 1][y - 1];
-        let top_right_tile = &level_vec[x - 1][y + 1];
-        let bottom_left_tile = &level_vec[x + 1][y - 1];
-        let bottom_right_tile = &level_vec[x + 1][y + 1];
-
-        // Top
-        if y > 0 
-        && utilities::tile_pathable(top_tile) {
-            neighbors.push((x, y - 1));
-        }
-        // Bottom
-        if y < height - 1
-        && utilities::tile_pathable(bottom_tile) {
-            neighbors.push((x, y + 1));
-        }
-        // Left
-        if x > 0
-        && utilities::tile_pathable(left_tile) {
-            neighbors.push((x - 1, y));
-        }
-        // Right
-        if x < width - 1 
-        && utilities::tile_pathable(right_tile) {
-            neighbors.push((x + 1, y));
-        }
-        // Top-left
-        if x > 0 && y > 0 
-        && utilities::tile_pathable(top_left_tile) {
-            neighbors.push((x - 1, y - 1));
-        }
-        // Top-right
-        if x > 0 && y < height - 1 
-        && utilities::tile_pathable(top_right_tile) {
-            neighbors.push((x - 1, y + 1));
-        }
-        // Bottom-left
-        if x < width - 1 && y > 0 
-        && utilities::tile_pathable(bottom_left_tile) {
-
-            neighbors.push((x + 1, y - 1));
-        }
-        // Bottom-right
-        if x < width - 1 && y < height - 1 
-        && utilities::tile_pathable(bottom_right_tile) {
-            neighbors.push((x + 1, y + 1));
-        }            
+        let offsets: [(isize, isize); 8] = [
+            (0, -1), // Top
+            (0, 1), // Bottom
+            (-1, 0), // Left
+            (1, 0), // Right
+            (-1, -1), // Top-left
+            (-1, 1), // Top-right
+            (1, -1), // Bottom-left
+            (1, 1), // Bottom-right
+        ];
+
+        for (dx, dy) in offsets {
+            let next_x = x as isize + dx;
+            let next_y = y as isize + dy;
+            if next_x < 0 || next_y < 0
+            || next_x >= width as isize || next_y >= height as isize {
+                continue;
+            }
+            let next = (next_x as usize, next_y as usize);
+            //flying enemies and sieges only need to stay on the map
+            if ignore_walls {
+                neighbors.push(next);
+                continue;
+            }
+            //no cutting corners, both tiles beside a diagonal step have to be open
+            let is_diagonal = dx != 0 && dy != 0;
+            if utilities::tile_pathable(&level_vec[next.0][next.1])
+            && (!is_diagonal 
+                || (utilities::tile_pathable(&level_vec[next.0][y]) 
+                    && utilities::tile_pathable(&level_vec[x][next.1]))) {
+                neighbors.push(next);
+            }
+        }
         neighbors
     }
 
 
+    //octile distance in step lengths, multiply by a tile cost to compare with paths
     fn heuristic(start: (usize, usize), target: (usize, usize)) -> usize {
         let (x1, y1) = start;
         let (x2, y2) = target;
 
-        let dx = (x1 as isize - x2 as isize).abs() as usize;
-        let dy = (y1 as isize - y2 as isize).abs() as usize;
+        let dx = x1.abs_diff(x2);
+        let dy = y1.abs_diff(y2);
 
-        dx + dy
+        constants::PATH_STEP_STRAIGHT as usize * (dx.max(dy) - dx.min(dy))
+        + constants::PATH_STEP_DIAGONAL as usize * dx.min(dy)
     }
 }
@@ -30,10 +30,6 @@ pub struct PlayerManager {
     pub rect: sdl2::rect::Rect,
     pub direction: Direction,
     pub menu_selection: u8,
-    pub projectile_texture: String,
-    pub projectile_damage: u8,
-    pub projectile_speed: f64,
-    pub projectile_radius: u8,
 }
 
 impl PlayerManager {
@@ -59,10 +55,6 @@ impl PlayerManager {
             ),
             direction: Direction::Up,
             menu_selection: 0,
-            projectile_texture: constants::TEXTURE_PROJECTILE_ARROW.to_string(),
-            projectile_speed: constants::PROJECTILE_ARROW_SPEED,
-            projectile_radius: constants::PROJECTILE_ARROW_RADIUS,
-            projectile_damage: 20,
         };
         player
     }
@@ -130,7 +122,14 @@ impl PlayerManager {
             }
         }
 
-        if !utilities::check_player_collisions(self, game, events, (new_x, new_y), level) {
+        //player.x/y put the player in the middle of the screen, keep that point on the level
+        let (level_width, level_height) = level.pixel_size();
+        let max_x = level_width - constants::TILE_SIZE as i32 - events.screen_size.0 / 2;
+        let max_y = level_height - constants::TILE_SIZE as i32 - events.screen_size.1 / 2;
+        new_x = new_x.min(max_x).max(-events.screen_size.0 / 2);
+        new_y = new_y.min(max_y).max(-events.screen_size.1 / 2);
+
+        if !utilities::check_player_collisions(game, events, (new_x, new_y), level) {
             self.x = new_x;
             self.y = new_y;
         }
@@ -138,7 +137,8 @@ impl PlayerManager {
     pub fn render_player(
         &mut self, 
         events: &mut event_manager::EventManager,
-        game: &mut game_manager::GameManager, 
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>
     ) -> Result<(), String> {
         if (events.up || events.down || events.left || events.right) && game.frame_time % constants::PLAYER_SPEED as u32 == 0 {
@@ -183,10 +183,13 @@ impl PlayerManager {
         }
 
         let texture = tex_man.load(&self.texture_path)?;
-        game.canvas.copy_ex(
+        //rect stays centered, the camera stops at level edges so the sprite moves off center
+        let mut screen_rect = self.rect;
+        screen_rect.offset(self.x - game.cam_x, self.y - game.cam_y);
+        canvas.copy_ex(
             &texture, // Texture object
             None,      // source rect
-            self.rect,     // destination rect
+            screen_rect,     // destination rect
             0.0,      // angle (degrees)
             None,   // center
             false,    // flip horizontal
@@ -6,6 +6,37 @@ use crate::level_manager;
 use crate::enemy_manager;
 use crate::texture_manager;
 use crate::tower_manager;
+use crate::building_manager;
+use crate::replay_manager;
+use crate::status_manager;
+use crate::utilities;
+
+//what a projectile does to the enemies it hits
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ProjectileEffect {
+    Single,
+    //hurts every enemy within radius pixels of the hit, falloff is the percent of damage left at the edge
+    Splash {
+        radius: i32,
+        falloff: u8,
+    },
+    //the hit sets the enemy burning, damage every BURN_INTERVAL steps for duration steps
+    Burn {
+        damage: u8,
+        duration: u16,
+    },
+    //flies on through enemies until it has hit this many
+    Pierce {
+        hits: u8,
+    },
+}
+
+//splash hits, only drawn, not saved
+pub struct Explosion {
+    pub position: (i32, i32),
+    pub radius: i32,
+    pub time: u8,
+}
 
 pub struct Projectile {
     pub rect: sdl2::rect::Rect,
@@ -13,28 +44,42 @@ pub struct Projectile {
     pub time: u8,
     pub start: (i32, i32),
     pub position: (i32, i32),
+    //position before the last simulation step, rendering blends between the two
+    pub previous_position: (i32, i32),
     pub target: (i32, i32),
     pub hit_target: bool,
-    angle: f64,
-    speed: f64,
+    pub angle: f64,
+    pub speed: f64,
     pub radius: u8,
     pub damage: u8,
+    //fired by enemies, hits towers and buildings instead of enemies
+    pub is_hostile: bool,
+    //follows homing_enemy every step, time counts its whole flight
+    pub is_homing: bool,
+    //id of the enemy a homing projectile was fired at, None once it died and the projectile flies straight on
+    pub homing_enemy: Option<u32>,
+    pub effect: ProjectileEffect,
+    //ids of the enemies a piercing projectile already went through
+    pub pierced: Vec<u32>,
+    //put on every enemy this damages, on top of the effect
+    pub status: Option<status_manager::StatusEffect>,
 }
 
 pub struct ProjectileManager {
     pub projectile_vec: Vec<Projectile>,
+    pub explosion_vec: Vec<Explosion>,
 }
 
 impl ProjectileManager {
     pub fn new () -> Self {
         let projectiles = ProjectileManager {
             projectile_vec: Vec::new(),
+            explosion_vec: Vec::new(),
         };
         projectiles
     }
     pub fn spawn_player_projectile (
         &mut self,
-        player: &mut player_manager::PlayerManager,
         start: (i32, i32),
         position: (i32, i32),
         target: (i32, i32)
@@ -47,15 +92,22 @@ impl ProjectileManager {
                 constants::TILE_SIZE,
                 constants::TILE_SIZE
             ),
-            texture_path: player.projectile_texture.clone(),
+            texture_path: constants::TEXTURE_PROJECTILE_ARROW.to_string(),
             start,
             position,
+            previous_position: position,
             target,
             hit_target: false,
             angle: Self::calculate_angle(start, target),
-            speed: player.projectile_speed,
-            radius: player.projectile_radius,
-            damage: player.projectile_damage,
+            speed: constants::PROJECTILE_ARROW_SPEED,
+            radius: constants::PROJECTILE_ARROW_RADIUS,
+            damage: constants::PLAYER_PROJECTILE_DAMAGE,
+            is_hostile: false,
+            is_homing: false,
+            homing_enemy: None,
+            effect: ProjectileEffect::Single,
+            pierced: Vec::new(),
+            status: None,
         };
 
         self.projectile_vec.push(projectile);
@@ -66,8 +118,28 @@ impl ProjectileManager {
         tower: &mut tower_manager::Tower,
         start: (i32, i32),
         position: (i32, i32),
-        target: (i32, i32)
+        target: (i32, i32),
+        target_enemy: Option<u32>,
     ) {
+        //piercing projectiles fly on past the aim point to the edge of the tower's range
+        let target = match tower.projectile_effect {
+            ProjectileEffect::Pierce { .. } => {
+                let dx = (target.0 - start.0) as f64;
+                let dy = (target.1 - start.1) as f64;
+                let distance = (dx * dx + dy * dy).sqrt();
+                let length = (tower.attack_radius as f64 * std::f64::consts::SQRT_2).max(distance);
+                if distance > 0.0 {
+                    (
+                        start.0 + (dx / distance * length).round() as i32,
+                        start.1 + (dy / distance * length).round() as i32
+                    )
+                }
+                else {
+                    target
+                }
+            }
+            _ => target,
+        };
         let projectile = self::Projectile {
             time: 0,
             rect: sdl2::rect::Rect::new(
@@ -79,12 +151,54 @@ impl ProjectileManager {
             texture_path: tower.projectile_texture.clone(),
             start,
             position,
+            previous_position: position,
             target,
             hit_target: false,
             angle: Self::calculate_angle(start, target),
             speed: tower.projectile_speed,
             radius: tower.projectile_radius,
             damage: tower.projectile_damage,
+            is_hostile: false,
+            is_homing: tower.projectile_homing,
+            homing_enemy: target_enemy.filter(|_| tower.projectile_homing),
+            effect: tower.projectile_effect,
+            pierced: Vec::new(),
+            status: tower.projectile_status,
+        };
+
+        self.projectile_vec.push(projectile);
+    }
+
+    pub fn spawn_enemy_projectile (
+        &mut self,
+        enemy: &enemy_manager::Enemy,
+        start: (i32, i32),
+        target: (i32, i32)
+    ) {
+        let projectile = self::Projectile {
+            time: 0,
+            rect: sdl2::rect::Rect::new(
+                start.0,
+                start.1,
+                constants::TILE_SIZE,
+                constants::TILE_SIZE
+            ),
+            texture_path: constants::TEXTURE_PROJECTILE_ENEMY_ARROW.to_string(),
+            start,
+            position: start,
+            previous_position: start,
+            target,
+            hit_target: false,
+            angle: Self::calculate_angle(start, target),
+            speed: constants::PROJECTILE_ENEMY_ARROW_SPEED,
+            radius: constants::PROJECTILE_ENEMY_ARROW_RADIUS,
+            damage: enemy.attack_damage,
+            is_hostile: true,
+            is_homing: false,
+            homing_enemy: None,
+            effect: ProjectileEffect::Single,
+            pierced: Vec::new(),
+            status: None,
         };
 
         self.projectile_vec.push(projectile);
@@ -92,7 +206,7 @@ impl ProjectileManager {
 
     fn move_projectile (
         projectile: &mut Projectile,
-        events: &mut event_manager::EventManager
+        game: &game_manager::GameManager
     ) {
         let dx = projectile.target.0 - projectile.position.0;
         let dy = projectile.target.1 - projectile.position.1 ;
@@ -103,57 +217,241 @@ impl ProjectileManager {
         projectile.position.0 += (
         direction_x 
         * (projectile.speed 
-        * events.delta_time.max(constants::MIN_GAME_RATE)
+        * game.delta_time.max(constants::MIN_GAME_RATE)
     ) as f32) as i32;
         projectile.position.1 += (
         direction_y 
         * (projectile.speed 
-        * events.delta_time.max(constants::MIN_GAME_RATE)) as f32
+        * game.delta_time.max(constants::MIN_GAME_RATE)) as f32
     ) as i32;
     }
 
-    pub fn check_projectile_hit(
+    pub fn move_projectiles (
         &mut self,
-        game: &mut game_manager::GameManager,
-        events: &mut event_manager::EventManager,
+        game: &game_manager::GameManager,
+        enemies: &enemy_manager::EnemyManager,
+    ) {
+        for projectile in &mut self.projectile_vec {
+            projectile.previous_position = projectile.position;
+            if projectile.is_homing && !projectile.hit_target {
+                Self::follow_target(projectile, enemies);
+            }
+            if !tower_manager::TowerManager::is_within_area(
+                projectile.position,
+                projectile.target,
+                (projectile.speed 
+                * game.delta_time.max(constants::MIN_GAME_RATE)) as i32
+            ) {
+                Self::move_projectile(projectile, game);
+                //burns out instead of chasing a faster enemy forever
+                if projectile.is_homing {
+                    projectile.time += 1;
+                }
+            }
+            else {
+                projectile.time += 1;
+            }
+        }
+        for explosion in &mut self.explosion_vec {
+            explosion.time += 1;
+        }
+        self.explosion_vec.retain(|explosion| explosion.time <= constants::EXPLOSION_DURATION);
+    }
+
+    //straight on to where the enemy last was once it's dead
+    fn follow_target(
+        projectile: &mut Projectile,
+        enemies: &enemy_manager::EnemyManager,
+    ) {
+        let Some(enemy_id) = projectile.homing_enemy else {
+            return
+        };
+        match enemies.enemy_vec.iter().find(|enemy| enemy.id == enemy_id && enemy.health != 0) {
+            Some(enemy) => {
+                projectile.target = (enemy.pixel_index.0 as i32, enemy.pixel_index.1 as i32);
+                projectile.angle = Self::calculate_angle(projectile.position, projectile.target);
+            }
+            None => projectile.homing_enemy = None,
+        }
+    }
+
+    //where a projectile fired from start this step meets the enemy
+    //enemies faster than the projectile may never be met, then it aims for the closest it gets
+    pub fn intercept_point(
+        start: (i32, i32),
+        speed: f64,
+        enemy: &enemy_manager::Enemy,
+        game: &game_manager::GameManager,
+        level: &level_manager::LevelManager,
+    ) -> (i32, i32) {
+        let step_distance = speed * game.delta_time.max(constants::MIN_GAME_RATE);
+        let predicted = enemy_manager::EnemyManager::predict_pixel_indices(
+            enemy,
+            game,
+            level,
+            constants::PROJECTILE_LEAD_STEPS
+        );
+        let mut closest: Option<(f64, (i32, i32))> = None;
+        for (steps, pixel_index) in predicted.into_iter().enumerate() {
+            let enemy_pos_pixel = (pixel_index.0 as i32, pixel_index.1 as i32);
+            let dx = (enemy_pos_pixel.0 - start.0) as f64;
+            let dy = (enemy_pos_pixel.1 - start.1) as f64;
+            let gap = (dx * dx + dy * dy).sqrt() - step_distance * steps as f64;
+            if gap <= 0.0 {
+                return enemy_pos_pixel
+            }
+            if closest.is_none_or(|(closest_gap, _)| gap < closest_gap) {
+                closest = Some((gap, enemy_pos_pixel));
+            }
+        }
+        match closest {
+            Some((_, enemy_pos_pixel)) => enemy_pos_pixel,
+            None => (enemy.pixel_index.0 as i32, enemy.pixel_index.1 as i32),
+        }
+    }
+
+    //full damage at the impact, falloff percent of it at radius pixels away
+    fn apply_splash(
+        enemies: &mut enemy_manager::EnemyManager,
+        impact: (i32, i32),
+        damage: u8,
+        radius: i32,
+        falloff: u8,
+        status: Option<status_manager::StatusEffect>,
+    ) {
+        for enemy_index in enemies.enemy_grid.query(impact, radius) {
+            let enemy = &mut enemies.enemy_vec[enemy_index];
+            let dx = (enemy.pixel_index.0 as i32 - impact.0) as f64;
+            let dy = (enemy.pixel_index.1 as i32 - impact.1) as f64;
+            let distance = (dx * dx + dy * dy).sqrt();
+            if enemy.health == 0 || distance > radius as f64 {
+                continue;
+            }
+            let lost = (100 - falloff.min(100)) as f64 / 100.0 * distance / radius.max(1) as f64;
+            let splash_damage = (damage as f64 * (1.0 - lost)).round() as u16;
+            enemy.health = enemy.health.saturating_sub(splash_damage);
+            if let Some(status) = status {
+                status_manager::apply_status(&mut enemy.status_vec, status);
+            }
+        }
+    }
+
+    //player shots come from input, they join the simulation once spawned
+    pub fn fire_player_projectile (
+        game: &game_manager::GameManager,
+        events: &event_manager::EventManager,
         player: &mut player_manager::PlayerManager,
-        enemies: &mut enemy_manager::EnemyManager
+        replay_manager: &mut replay_manager::ReplayManager,
     ) {
-        for enemy in &mut enemies.enemy_vec {
-            let enemy_pos_pixel = (
-                enemy.pixel_index.0 as i32,
-                enemy.pixel_index.1 as i32
+        if player.is_attacking {
+            let start = (
+                player.rect.x() + player.x,
+                player.rect.y() + player.y
             );
+            replay_manager.queue_action(replay_manager::ReplayAction::Shoot {
+                start,
+                target: (events.mouse_point.x + game.cam_x,
+                    events.mouse_point.y + game.cam_y),
+            });
+            player.is_attacking = false;
+        }
+    }
 
-            if player.is_attacking {
-                let start = (
-                    player.rect.x() + player.x,
-                    player.rect.y() + player.y
-                );
-                self.spawn_player_projectile(
-                    player,
-                    start,
-                    start,
-                    (events.mouse_point.x + game.cam_x,
-                        events.mouse_point.y + game.cam_y)
-                ); 
-                player.is_attacking = false;
-
+    pub fn check_projectile_hit(
+        &mut self,
+        enemies: &mut enemy_manager::EnemyManager,
+        towers: &mut tower_manager::TowerManager,
+        buildings: &mut building_manager::BuildingManager,
+    ) {
+        enemies.update_enemy_grid();
+        for projectile in &mut self.projectile_vec {
+            if projectile.is_hostile || projectile.hit_target {
+                continue;
             }
-            for projectile in &mut self.projectile_vec {
+            //enemies are hit in enemy_vec order, most effects stop at the first living one
+            for enemy_index in enemies.enemy_grid.query(projectile.position, projectile.radius as i32) {
+                let enemy = &mut enemies.enemy_vec[enemy_index];
+                let enemy_pos_pixel = (
+                    enemy.pixel_index.0 as i32,
+                    enemy.pixel_index.1 as i32
+                );
                 let projectile_hit: bool = tower_manager::TowerManager::is_within_area(
                     projectile.position,
                     enemy_pos_pixel,
                     projectile.radius as i32
                 );
+                if !projectile_hit || enemy.health == 0 || projectile.pierced.contains(&enemy.id) {
+                    continue;
+                }
 
-                if projectile_hit && enemy.health != 0 && !projectile.hit_target {
-                    if enemy.health > projectile.damage as u16 {
-                        enemy.health -= projectile.damage as u16;
+                if let Some(status) = projectile.status {
+                    //splash puts it on everyone caught in the blast instead
+                    if !matches!(projectile.effect, ProjectileEffect::Splash { .. }) {
+                        status_manager::apply_status(&mut enemy.status_vec, status);
+                    }
+                }
+                match projectile.effect {
+                    ProjectileEffect::Single => {
+                        enemy.health = enemy.health.saturating_sub(projectile.damage as u16);
+                        projectile.hit_target = true;
+                    }
+                    ProjectileEffect::Splash { radius, falloff } => {
+                        Self::apply_splash(enemies, enemy_pos_pixel, projectile.damage, radius, falloff, projectile.status);
+                        self.explosion_vec.push(Explosion {
+                            position: enemy_pos_pixel,
+                            radius,
+                            time: 0,
+                        });
+                        projectile.hit_target = true;
+                    }
+                    ProjectileEffect::Burn { damage, duration } => {
+                        enemy.health = enemy.health.saturating_sub(projectile.damage as u16);
+                        status_manager::apply_status(&mut enemy.status_vec, status_manager::StatusEffect {
+                            kind: status_manager::StatusKind::Burn,
+                            strength: damage,
+                            time: duration,
+                        });
+                        projectile.hit_target = true;
                     }
-                    else {
-                        enemy.health = 0;
+                    ProjectileEffect::Pierce { hits } => {
+                        enemy.health = enemy.health.saturating_sub(projectile.damage as u16);
+                        projectile.pierced.push(enemy.id);
+                        projectile.hit_target = projectile.pierced.len() >= hits as usize;
                     }
+                }
+                if projectile.hit_target {
+                    break;
+                }
+            }
+        }
+        for projectile in &mut self.projectile_vec {
+            if !projectile.is_hostile || projectile.hit_target {
+                continue;
+            }
+            for tower in &mut towers.tower_vec {
+                let tower_pos_pixel = (
+                    tower.bottom_index.0 as i32 * constants::TILE_SIZE as i32,
+                    tower.bottom_index.1 as i32 * constants::TILE_SIZE as i32
+                );
+                if tower.health != 0 && !projectile.hit_target
+                && tower_manager::TowerManager::is_within_area(
+                    projectile.position,
+                    tower_pos_pixel,
+                    projectile.radius as i32
+                ) {
+                    tower.health = tower.health.saturating_sub(projectile.damage as u16);
+                    projectile.hit_target = true;
+                }
+            }
+            for building in &mut buildings.building_vec {
+                if building.health != 0 && !projectile.hit_target
+                && tower_manager::TowerManager::is_within_area(
+                    projectile.position,
+                    building.pixel_index,
+                    projectile.radius as i32
+                ) {
+                    building.health = building.health.saturating_sub(projectile.damage as u16);
+                    building.last_damaged = 0;
                     projectile.hit_target = true;
                 }
             }
@@ -163,15 +461,20 @@ impl ProjectileManager {
     pub fn render_projectiles (
         &mut self,
         game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>,
-        events: &mut event_manager::EventManager
     ) -> Result<(), String> {
         for projectile in &mut self.projectile_vec {
-            projectile.rect.set_x(projectile.position.0 - game.cam_x);
-            projectile.rect.set_y(projectile.position.1 - game.cam_y);
+            let position = utilities::interpolate(
+                projectile.previous_position,
+                projectile.position,
+                game.interpolation
+            );
+            projectile.rect.set_x(position.0 - game.cam_x);
+            projectile.rect.set_y(position.1 - game.cam_y);
 
             let texture = tex_man.load(&projectile.texture_path)?;
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &texture, // Texture object
                 None,      // source rect
                 projectile.rect,     // destination rect
@@ -180,18 +483,19 @@ impl ProjectileManager {
                 false,    // flip horizontal
                 false,     // flip vertical
             )?;
+        }
+        //splash grows from half its size to the whole radius
+        for explosion in &self.explosion_vec {
+            let grown = 0.5 + 0.5 * explosion.time as f64 / constants::EXPLOSION_DURATION as f64;
+            let size = (explosion.radius as f64 * 2.0 * grown).max(1.0) as u32;
+            let center = (
+                explosion.position.0 + constants::TILE_SIZE as i32 / 2 - game.cam_x,
+                explosion.position.1 + constants::TILE_SIZE as i32 / 2 - game.cam_y
+            );
+            let explosion_rect = sdl2::rect::Rect::from_center(center, size, size);
 
-            if !tower_manager::TowerManager::is_within_area(
-                projectile.position,
-                projectile.target,
-                (projectile.speed 
-                * (events.delta_time).max(constants::MIN_GAME_RATE)) as i32
-            ) {
-                Self::move_projectile(projectile, events);
-            }
-            else {
-                projectile.time += 1;
-            }
+            let texture = tex_man.load(constants::TEXTURE_PROJECTILE_FIREBALL)?;
+            canvas.copy(&texture, None, explosion_rect)?;
         }
         Ok(())
     }
@@ -0,0 +1,316 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::tower_manager;
+
+//replay file format, one entry per line, '#' starts a comment:
+//  level <level file path>
+//  <tick> build <current build> <col> <row>
+//  <tick> seed <current seed> <col> <row>
+//  <tick> shoot <start x> <start y> <target x> <target y>
+//  <tick> upgrade <col> <row> <first|second>
+//  <tick> target <col> <row> <first|last|strongest|weakest|closest>
+//ticks are game frame_time, which starts over when a level is loaded, inputs must be in tick order
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ReplayAction {
+    Build {
+        build: usize,
+        index: (usize, usize),
+    },
+    Seed {
+        seed: usize,
+        index: (usize, usize),
+    },
+    //world pixels, the player moving around is not part of the simulation
+    Shoot {
+        start: (i32, i32),
+        target: (i32, i32),
+    },
+    //the next upgrade on the path, what it does comes from TowerManager::upgrade_path
+    Upgrade {
+        index: (usize, usize),
+        path: tower_manager::UpgradePath,
+    },
+    Target {
+        index: (usize, usize),
+        targeting_mode: tower_manager::TargetingMode,
+    },
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ReplayInput {
+    pub tick: u32,
+    pub action: ReplayAction,
+}
+
+#[derive(Debug)]
+pub struct ReplayParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ReplayParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+pub struct ReplayManager {
+    pub level_path: String,
+    pub input_vec: Vec<ReplayInput>,
+    //queued by this frame's input, applied at the start of the next simulation step
+    pub pending_vec: Vec<ReplayAction>,
+    pub next_input: usize,
+    pub is_recording: bool,
+    pub is_playing: bool,
+    pub fast_forward: bool,
+}
+
+impl ReplayManager {
+    pub fn new() -> ReplayManager {
+        let replay_manager = ReplayManager {
+            level_path: "".to_string(),
+            input_vec: Vec::new(),
+            pending_vec: Vec::new(),
+            next_input: 0,
+            is_recording: false,
+            is_playing: false,
+            fast_forward: false,
+        };
+        replay_manager
+    }
+
+    //the level has just been loaded from level_path, everything after this can be replayed
+    pub fn start_recording(&mut self, level_path: &str) {
+        self.level_path = level_path.to_string();
+        self.input_vec.clear();
+        self.pending_vec.clear();
+        self.next_input = 0;
+        self.is_recording = true;
+        self.is_playing = false;
+        self.fast_forward = false;
+    }
+
+    //the run no longer starts from a level file, a save was loaded or the editor opened
+    pub fn stop_recording(&mut self) {
+        self.input_vec.clear();
+        self.pending_vec.clear();
+        self.is_recording = false;
+        self.is_playing = false;
+        self.fast_forward = false;
+    }
+
+    //the level must already be loaded from self.level_path
+    pub fn start_playback(&mut self, input_vec: Vec<ReplayInput>) {
+        self.input_vec = input_vec;
+        self.pending_vec.clear();
+        self.next_input = 0;
+        self.is_recording = false;
+        self.is_playing = true;
+        self.fast_forward = false;
+    }
+
+    //live input is ignored while a replay plays
+    pub fn queue_action(&mut self, action: ReplayAction) {
+        if !self.is_playing {
+            self.pending_vec.push(action);
+        }
+    }
+
+    //actions for the step about to run, recorded ones during playback otherwise the queued ones
+    pub fn take_actions(&mut self, tick: u32) -> Vec<ReplayAction> {
+        let mut actions = Vec::new();
+        if self.is_playing {
+            while self.next_input < self.input_vec.len()
+            && self.input_vec[self.next_input].tick <= tick {
+                actions.push(self.input_vec[self.next_input].action);
+                self.next_input += 1;
+            }
+            if self.next_input >= self.input_vec.len() {
+                //keeps recording from here so the longer run can be saved again
+                self.is_playing = false;
+                self.is_recording = true;
+                self.fast_forward = false;
+            }
+        }
+        else {
+            actions.append(&mut self.pending_vec);
+            if self.is_recording {
+                for action in &actions {
+                    self.input_vec.push(ReplayInput {
+                        tick,
+                        action: *action,
+                    });
+                }
+            }
+        }
+        actions
+    }
+
+    pub fn write_replay(&self, replay_path: &str) -> Result<(), std::io::Error> {
+        if let Some(directory) = std::path::Path::new(replay_path).parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(replay_path, self.to_replay_string())
+    }
+
+    pub fn to_replay_string(&self) -> String {
+        let mut replay = format!("level {}\n", self.level_path);
+        for input in &self.input_vec {
+            let action = match input.action {
+                ReplayAction::Build { build, index } => format!("build {} {} {}", build, index.0, index.1),
+                ReplayAction::Seed { seed, index } => format!("seed {} {} {}", seed, index.0, index.1),
+                ReplayAction::Shoot { start, target } => format!("shoot {} {} {} {}", start.0, start.1, target.0, target.1),
+                ReplayAction::Upgrade { index, path } => format!(
+                    "upgrade {} {} {}",
+                    index.0,
+                    index.1,
+                    path.name()
+                ),
+                ReplayAction::Target { index, targeting_mode } => format!(
+                    "target {} {} {}",
+                    index.0,
+                    index.1,
+                    targeting_mode.name()
+                ),
+            };
+            replay.push_str(&format!("{} {}\n", input.tick, action));
+        }
+        replay
+    }
+
+    pub fn read_replay(replay_path: &str) -> Result<ReplayManager, ReplayParseError> {
+        let file = File::open(replay_path).map_err(|e| ReplayParseError {
+            line: 0,
+            message: e.to_string(),
+        })?;
+        let mut lines = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| ReplayParseError {
+                line: lines.len() + 1,
+                message: e.to_string(),
+            })?;
+            lines.push(line);
+        }
+        Self::parse_replay(&lines.join("\n"))
+    }
+
+    pub fn parse_replay(replay: &str) -> Result<ReplayManager, ReplayParseError> {
+        let mut replay_manager = ReplayManager::new();
+        let mut level_path: Option<String> = None;
+
+        for (line_index, line) in replay.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() {
+                continue;
+            }
+            let error = |message: String| ReplayParseError {
+                line: line_number,
+                message,
+            };
+
+            if words[0] == "level" {
+                if words.len() != 2 {
+                    return Err(error("expected: level <level file path>".to_string()));
+                }
+                level_path = Some(words[1].to_string());
+                continue;
+            }
+
+            let tick: u32 = Self::parse_number(words[0], "tick").map_err(error)?;
+            if let Some(last_input) = replay_manager.input_vec.last() {
+                if tick < last_input.tick {
+                    return Err(error(format!("tick {} comes before tick {}", tick, last_input.tick)));
+                }
+            }
+            let arguments = &words[1..];
+            let expect_arguments = |amount: usize, usage: &str| {
+                if arguments.len() == amount + 1 {
+                    Ok(())
+                }
+                else {
+                    Err(error(format!("expected: <tick> {}", usage)))
+                }
+            };
+            let action = match arguments.first().copied() {
+                Some("build") => {
+                    expect_arguments(3, "build <current build> <col> <row>")?;
+                    ReplayAction::Build {
+                        build: Self::parse_number(arguments[1], "build").map_err(error)?,
+                        index: (
+                            Self::parse_number(arguments[2], "col").map_err(error)?,
+                            Self::parse_number(arguments[3], "row").map_err(error)?,
+                        ),
+                    }
+                }
+                Some("seed") => {
+                    expect_arguments(3, "seed <current seed> <col> <row>")?;
+                    ReplayAction::Seed {
+                        seed: Self::parse_number(arguments[1], "seed").map_err(error)?,
+                        index: (
+                            Self::parse_number(arguments[2], "col").map_err(error)?,
+                            Self::parse_number(arguments[3], "row").map_err(error)?,
+                        ),
+                    }
+                }
+                Some("shoot") => {
+                    expect_arguments(4, "shoot <start x> <start y> <target x> <target y>")?;
+                    ReplayAction::Shoot {
+                        start: (
+                            Self::parse_number(arguments[1], "start x").map_err(error)?,
+                            Self::parse_number(arguments[2], "start y").map_err(error)?,
+                        ),
+                        target: (
+                            Self::parse_number(arguments[3], "target x").map_err(error)?,
+                            Self::parse_number(arguments[4], "target y").map_err(error)?,
+                        ),
+                    }
+                }
+                Some("upgrade") => {
+                    expect_arguments(3, "upgrade <col> <row> <first|second>")?;
+                    ReplayAction::Upgrade {
+                        index: (
+                            Self::parse_number(arguments[1], "col").map_err(error)?,
+                            Self::parse_number(arguments[2], "row").map_err(error)?,
+                        ),
+                        path: tower_manager::UpgradePath::from_name(arguments[3])
+                            .ok_or_else(|| error(format!("invalid upgrade path '{}'", arguments[3])))?,
+                    }
+                }
+                Some("target") => {
+                    expect_arguments(3, "target <col> <row> <first|last|strongest|weakest|closest>")?;
+                    ReplayAction::Target {
+                        index: (
+                            Self::parse_number(arguments[1], "col").map_err(error)?,
+                            Self::parse_number(arguments[2], "row").map_err(error)?,
+                        ),
+                        targeting_mode: tower_manager::TargetingMode::from_name(arguments[3])
+                            .ok_or_else(|| error(format!("invalid targeting mode '{}'", arguments[3])))?,
+                    }
+                }
+                Some(name) => return Err(error(format!("unknown action '{}'", name))),
+                None => return Err(error("expected an action after the tick".to_string())),
+            };
+            replay_manager.input_vec.push(ReplayInput { tick, action });
+        }
+
+        match level_path {
+            Some(level_path) => replay_manager.level_path = level_path,
+            None => return Err(ReplayParseError {
+                line: 0,
+                message: "missing level line".to_string(),
+            }),
+        }
+        Ok(replay_manager)
+    }
+
+    fn parse_number<T: std::str::FromStr>(word: &str, name: &str) -> Result<T, String> {
+        word.parse::<T>().map_err(|_| format!("invalid {} '{}'", name, word))
+    }
+}
@@ -0,0 +1,1003 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use crate::constants;
+use crate::game_manager;
+use crate::player_manager;
+use crate::level_manager;
+use crate::level_manager::{LevelTile, TileData, TileKind};
+use crate::tower_manager;
+use crate::building_manager;
+use crate::enemy_manager;
+use crate::projectile_manager;
+use crate::status_manager;
+use crate::upgrade_manager;
+use crate::wave_manager;
+
+//save layout (little endian):
+//  magic, version
+//  game: gold, carrots, tomatoes, frame_time, base_location, target_vec
+//  player: x, y, direction
+//  level_vec (tiles with wall health), towers, buildings, enemies (type first), projectiles, upgrade menus
+//  waves: state, wave, build timer, wave elapsed, round robin index, pending spawns
+
+pub struct SaveManager {
+    pub save_path: String,
+}
+
+//upgrade menus need a font, only what rebuilds them is saved
+pub struct UpgradeMenuSave {
+    pub grid_index: (usize, usize),
+    pub menu_active: bool,
+    pub first_path_purchased: u8,
+    pub second_path_purchased: u8,
+}
+
+impl SaveManager {
+    pub fn new() -> SaveManager {
+        let save_manager = SaveManager {
+            save_path: constants::SAVE_PATH.to_string(),
+        };
+        save_manager
+    }
+
+    pub fn save_game(
+        &self,
+        game: &mut game_manager::GameManager,
+        player: &mut player_manager::PlayerManager,
+        level: &mut level_manager::LevelManager,
+        towers: &mut tower_manager::TowerManager,
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager,
+        projectiles: &mut projectile_manager::ProjectileManager,
+        upgrade_manager: &mut upgrade_manager::UpgradeManager,
+        wave_manager: &mut wave_manager::WaveManager,
+    ) -> Result<(), std::io::Error> {
+        let upgrade_menus: Vec<UpgradeMenuSave> = upgrade_manager.upgrade_menu_vec.iter()
+            .map(|upgrade_menu| UpgradeMenuSave {
+                grid_index: upgrade_menu.grid_index,
+                menu_active: upgrade_menu.menu_active,
+                first_path_purchased: upgrade_menu.first_path_purchased,
+                second_path_purchased: upgrade_menu.second_path_purchased,
+            })
+            .collect();
+        self.write_save(
+            game,
+            player,
+            level,
+            towers,
+            buildings,
+            enemies,
+            projectiles,
+            &upgrade_menus,
+            wave_manager
+        )
+    }
+
+    //everything but the upgrade menu buttons, no window needed
+    pub fn write_save(
+        &self,
+        game: &game_manager::GameManager,
+        player: &player_manager::PlayerManager,
+        level: &level_manager::LevelManager,
+        towers: &tower_manager::TowerManager,
+        buildings: &building_manager::BuildingManager,
+        enemies: &enemy_manager::EnemyManager,
+        projectiles: &projectile_manager::ProjectileManager,
+        upgrade_menus: &[UpgradeMenuSave],
+        wave_manager: &wave_manager::WaveManager,
+    ) -> Result<(), std::io::Error> {
+        if let Some(save_dir) = std::path::Path::new(&self.save_path).parent() {
+            std::fs::create_dir_all(save_dir)?;
+        }
+        let file = File::create(&self.save_path)?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(constants::SAVE_MAGIC)?;
+        write_u32(&mut writer, constants::SAVE_VERSION)?;
+
+        write_u32(&mut writer, game.gold_amount)?;
+        write_u32(&mut writer, game.carrot_amount)?;
+        write_u32(&mut writer, game.tomato_amount)?;
+        write_u32(&mut writer, game.frame_time)?;
+        write_option_index(&mut writer, game.base_location)?;
+        write_usize(&mut writer, game.target_vec.len())?;
+        for target in &game.target_vec {
+            write_index(&mut writer, *target)?;
+        }
+
+        write_i32(&mut writer, player.x)?;
+        write_i32(&mut writer, player.y)?;
+        write_u8(&mut writer, direction_to_u8(&player.direction))?;
+
+        write_usize(&mut writer, level.level_vec.len())?;
+        for col in &level.level_vec {
+            write_usize(&mut writer, col.len())?;
+            for tile in col {
+                write_char(&mut writer, tile.tile_type.to_char())?;
+                write_char(&mut writer, tile.prev_type.to_char())?;
+                write_char(&mut writer, tile.original_type.to_char())?;
+                write_string(&mut writer, &tile.texture_path)?;
+                write_u16(&mut writer, tile.state)?;
+                write_u8(&mut writer, tile_data_to_u8(&tile.tile_data))?;
+                write_bool(&mut writer, tile.is_occupied)?;
+                write_u16(&mut writer, tile.health)?;
+            }
+        }
+
+        write_usize(&mut writer, towers.tower_vec.len())?;
+        for tower in &towers.tower_vec {
+            write_index(&mut writer, tower.bottom_index)?;
+            write_index(&mut writer, tower.top_index)?;
+            write_string(&mut writer, &tower.bottom_texture_path)?;
+            write_string(&mut writer, &tower.top_texture_path)?;
+            write_i32(&mut writer, tower.attack_radius)?;
+            write_u8(&mut writer, tower.attack_speed)?;
+            write_u16(&mut writer, tower.max_health)?;
+            write_u16(&mut writer, tower.health)?;
+            write_string(&mut writer, &tower.projectile_texture)?;
+            write_u8(&mut writer, tower.projectile_damage)?;
+            write_f64(&mut writer, tower.projectile_speed)?;
+            write_u8(&mut writer, tower.projectile_radius)?;
+            write_bool(&mut writer, tower.projectile_homing)?;
+            write_effect(&mut writer, tower.projectile_effect)?;
+            write_option_status(&mut writer, tower.projectile_status)?;
+            write_u8(&mut writer, targeting_mode_to_u8(&tower.targeting_mode))?;
+            write_u8(&mut writer, tower.first_path_purchased)?;
+            write_u8(&mut writer, tower.second_path_purchased)?;
+        }
+
+        write_bool(&mut writer, buildings.base_created)?;
+        write_usize(&mut writer, buildings.building_vec.len())?;
+        for building in &buildings.building_vec {
+            write_u8(&mut writer, building_type_to_u8(&building.building_type))?;
+            write_string(&mut writer, &building.texture_path_bottom_left)?;
+            write_string(&mut writer, &building.texture_path_bottom_right)?;
+            write_string(&mut writer, &building.texture_path_top_left)?;
+            write_string(&mut writer, &building.texture_path_top_right)?;
+            write_index(&mut writer, building.grid_index)?;
+            write_i32(&mut writer, building.pixel_index.0)?;
+            write_i32(&mut writer, building.pixel_index.1)?;
+            write_u16(&mut writer, building.last_damaged)?;
+            write_u16(&mut writer, building.max_health)?;
+            write_u16(&mut writer, building.health)?;
+        }
+
+        write_u32(&mut writer, enemies.next_enemy_id)?;
+        write_usize(&mut writer, enemies.enemy_vec.len())?;
+        for enemy in &enemies.enemy_vec {
+            write_u8(&mut writer, enemy_type_to_u8(&enemy.enemy_type))?;
+            write_u32(&mut writer, enemy.id)?;
+            match &enemy.final_path {
+                Some(final_path) => {
+                    write_bool(&mut writer, true)?;
+                    write_usize(&mut writer, final_path.len())?;
+                    for step in final_path {
+                        write_index(&mut writer, *step)?;
+                    }
+                }
+                None => write_bool(&mut writer, false)?,
+            }
+            write_f32(&mut writer, enemy.cost_total)?;
+            write_option_index(&mut writer, enemy.current_target)?;
+            write_index(&mut writer, enemy.grid_index)?;
+            write_u32(&mut writer, enemy.pixel_index.0)?;
+            write_u32(&mut writer, enemy.pixel_index.1)?;
+            write_u16(&mut writer, enemy.max_health)?;
+            write_u16(&mut writer, enemy.health)?;
+            write_u16(&mut writer, enemy.movement_speed)?;
+            write_u8(&mut writer, enemy.attack_damage)?;
+            write_u8(&mut writer, enemy.attack_radius)?;
+            write_u8(&mut writer, enemy.attack_speed)?;
+            write_u8(&mut writer, direction_to_u8(&enemy.direction))?;
+            write_string(&mut writer, &enemy.texture_path)?;
+            write_u8(&mut writer, enemy.boss_phase)?;
+            write_option_index(&mut writer, enemy.siege_target)?;
+            write_usize(&mut writer, enemy.status_vec.len())?;
+            for status in &enemy.status_vec {
+                write_status(&mut writer, *status)?;
+            }
+        }
+
+        write_usize(&mut writer, projectiles.projectile_vec.len())?;
+        for projectile in &projectiles.projectile_vec {
+            write_string(&mut writer, &projectile.texture_path)?;
+            write_u8(&mut writer, projectile.time)?;
+            write_i32(&mut writer, projectile.start.0)?;
+            write_i32(&mut writer, projectile.start.1)?;
+            write_i32(&mut writer, projectile.position.0)?;
+            write_i32(&mut writer, projectile.position.1)?;
+            write_i32(&mut writer, projectile.target.0)?;
+            write_i32(&mut writer, projectile.target.1)?;
+            write_bool(&mut writer, projectile.hit_target)?;
+            write_f64(&mut writer, projectile.angle)?;
+            write_f64(&mut writer, projectile.speed)?;
+            write_u8(&mut writer, projectile.radius)?;
+            write_u8(&mut writer, projectile.damage)?;
+            write_bool(&mut writer, projectile.is_hostile)?;
+            write_bool(&mut writer, projectile.is_homing)?;
+            write_option_u32(&mut writer, projectile.homing_enemy)?;
+            write_effect(&mut writer, projectile.effect)?;
+            write_option_status(&mut writer, projectile.status)?;
+            write_usize(&mut writer, projectile.pierced.len())?;
+            for enemy_id in &projectile.pierced {
+                write_u32(&mut writer, *enemy_id)?;
+            }
+        }
+
+        write_usize(&mut writer, upgrade_menus.len())?;
+        for upgrade_menu in upgrade_menus {
+            write_index(&mut writer, upgrade_menu.grid_index)?;
+            write_bool(&mut writer, upgrade_menu.menu_active)?;
+            write_u8(&mut writer, upgrade_menu.first_path_purchased)?;
+            write_u8(&mut writer, upgrade_menu.second_path_purchased)?;
+        }
+
+        write_u8(&mut writer, wave_state_to_u8(&wave_manager.wave_state))?;
+        write_u32(&mut writer, wave_manager.current_wave)?;
+        write_f64(&mut writer, wave_manager.wave_timer)?;
+        write_f64(&mut writer, wave_manager.wave_elapsed)?;
+        write_usize(&mut writer, wave_manager.spawn_index)?;
+        write_usize(&mut writer, wave_manager.pending_spawns.len())?;
+        for pending_spawn in &wave_manager.pending_spawns {
+            write_f64(&mut writer, pending_spawn.time)?;
+            write_u8(&mut writer, enemy_type_to_u8(&pending_spawn.enemy_type))?;
+            write_bool(&mut writer, pending_spawn.spawn_point.is_some())?;
+            write_usize(&mut writer, pending_spawn.spawn_point.unwrap_or(0))?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn load_game(
+        &self,
+        game: &mut game_manager::GameManager,
+        player: &mut player_manager::PlayerManager,
+        level: &mut level_manager::LevelManager,
+        towers: &mut tower_manager::TowerManager,
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager,
+        projectiles: &mut projectile_manager::ProjectileManager,
+        upgrade_manager: &mut upgrade_manager::UpgradeManager,
+        wave_manager: &mut wave_manager::WaveManager,
+    ) -> Result<(), std::io::Error> {
+        let upgrade_menus = self.read_save(
+            game,
+            player,
+            level,
+            towers,
+            buildings,
+            enemies,
+            projectiles,
+            wave_manager
+        )?;
+        upgrade_manager.upgrade_menu_vec.clear();
+        for upgrade_menu_save in upgrade_menus {
+            let (col_index, row_index) = upgrade_menu_save.grid_index;
+            let temp_tile = &mut level.level_vec[col_index][row_index];
+            if let Err(e) = upgrade_manager.restore_upgrade_menu(
+                game,
+                towers,
+                temp_tile,
+                upgrade_menu_save.grid_index,
+                upgrade_menu_save.menu_active,
+                upgrade_menu_save.first_path_purchased,
+                upgrade_menu_save.second_path_purchased
+            ) {
+                eprintln!("Failed to restore upgrade menu: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    //loads everything but the upgrade menu buttons, returns what rebuilds them
+    pub fn read_save(
+        &self,
+        game: &mut game_manager::GameManager,
+        player: &mut player_manager::PlayerManager,
+        level: &mut level_manager::LevelManager,
+        towers: &mut tower_manager::TowerManager,
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager,
+        projectiles: &mut projectile_manager::ProjectileManager,
+        wave_manager: &mut wave_manager::WaveManager,
+    ) -> Result<Vec<UpgradeMenuSave>, std::io::Error> {
+        let file = File::open(&self.save_path)?;
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != constants::SAVE_MAGIC {
+            return Err(invalid_data("not a farm defense save file".to_string()));
+        }
+        let version = read_u32(&mut reader)?;
+        if version != constants::SAVE_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported save version {} (expected {})",
+                version,
+                constants::SAVE_VERSION
+            )));
+        }
+
+        //read everything before touching the session so a bad file leaves it intact
+        let gold_amount = read_u32(&mut reader)?;
+        let carrot_amount = read_u32(&mut reader)?;
+        let tomato_amount = read_u32(&mut reader)?;
+        let frame_time = read_u32(&mut reader)?;
+        let base_location = read_option_index(&mut reader)?;
+        let target_amount = read_count(&mut reader)?;
+        let mut target_vec = Vec::with_capacity(target_amount);
+        for _ in 0..target_amount {
+            target_vec.push(read_index(&mut reader)?);
+        }
+
+        let player_x = read_i32(&mut reader)?;
+        let player_y = read_i32(&mut reader)?;
+        let player_direction = u8_to_direction(read_u8(&mut reader)?)?;
+
+        let col_amount = read_count(&mut reader)?;
+        let mut level_vec: Vec<Vec<LevelTile>> = Vec::with_capacity(col_amount);
+        for _ in 0..col_amount {
+            let row_amount = read_count(&mut reader)?;
+            let mut col: Vec<LevelTile> = Vec::with_capacity(row_amount);
+            for _ in 0..row_amount {
+                let tile = LevelTile {
+                    tile_type: read_tile_kind(&mut reader)?,
+                    prev_type: read_tile_kind(&mut reader)?,
+                    original_type: read_tile_kind(&mut reader)?,
+                    texture_path: read_string(&mut reader)?,
+                    rect: tile_rect(),
+                    state: read_u16(&mut reader)?,
+                    tile_data: u8_to_tile_data(read_u8(&mut reader)?)?,
+                    is_occupied: read_bool(&mut reader)?,
+                    health: read_u16(&mut reader)?,
+                };
+                col.push(tile);
+            }
+            level_vec.push(col);
+        }
+
+        let tower_amount = read_count(&mut reader)?;
+        let mut tower_vec = Vec::with_capacity(tower_amount);
+        for _ in 0..tower_amount {
+            let tower = tower_manager::Tower {
+                bottom_index: read_index(&mut reader)?,
+                top_index: read_index(&mut reader)?,
+                bottom_rect: tile_rect(),
+                bottom_texture_path: read_string(&mut reader)?,
+                top_rect: tile_rect(),
+                top_texture_path: read_string(&mut reader)?,
+                attack_radius: read_i32(&mut reader)?,
+                attack_speed: read_u8(&mut reader)?,
+                max_health: read_u16(&mut reader)?,
+                health: read_u16(&mut reader)?,
+                is_attacking: false,
+                projectile_texture: read_string(&mut reader)?,
+                projectile_damage: read_u8(&mut reader)?,
+                projectile_speed: read_f64(&mut reader)?,
+                projectile_radius: read_u8(&mut reader)?,
+                projectile_homing: read_bool(&mut reader)?,
+                projectile_effect: read_effect(&mut reader)?,
+                projectile_status: read_option_status(&mut reader)?,
+                targeting_mode: u8_to_targeting_mode(read_u8(&mut reader)?)?,
+                first_path_purchased: read_u8(&mut reader)?,
+                second_path_purchased: read_u8(&mut reader)?,
+            };
+            tower_vec.push(tower);
+        }
+
+        let base_created = read_bool(&mut reader)?;
+        let building_amount = read_count(&mut reader)?;
+        let mut building_vec = Vec::with_capacity(building_amount);
+        for _ in 0..building_amount {
+            let building = building_manager::Building {
+                building_type: u8_to_building_type(read_u8(&mut reader)?)?,
+                texture_path_bottom_left: read_string(&mut reader)?,
+                texture_path_bottom_right: read_string(&mut reader)?,
+                texture_path_top_left: read_string(&mut reader)?,
+                texture_path_top_right: read_string(&mut reader)?,
+                bottom_left_rect: tile_rect(),
+                bottom_right_rect: tile_rect(),
+                top_left_rect: tile_rect(),
+                top_right_rect: tile_rect(),
+                grid_index: read_index(&mut reader)?,
+                pixel_index: (read_i32(&mut reader)?, read_i32(&mut reader)?),
+                last_damaged: read_u16(&mut reader)?,
+                max_health: read_u16(&mut reader)?,
+                health: read_u16(&mut reader)?,
+            };
+            building_vec.push(building);
+        }
+
+        let next_enemy_id = read_u32(&mut reader)?;
+        let enemy_amount = read_count(&mut reader)?;
+        let mut enemy_vec = Vec::with_capacity(enemy_amount);
+        for _ in 0..enemy_amount {
+            let enemy_type = u8_to_enemy_type(read_u8(&mut reader)?)?;
+            let id = read_u32(&mut reader)?;
+            let final_path = if read_bool(&mut reader)? {
+                let path_length = read_count(&mut reader)?;
+                let mut path = Vec::with_capacity(path_length);
+                for _ in 0..path_length {
+                    path.push(read_index(&mut reader)?);
+                }
+                Some(path)
+            }
+            else {
+                None
+            };
+            let mut enemy = enemy_manager::Enemy {
+                id,
+                enemy_type,
+                final_path,
+                cost_total: read_f32(&mut reader)?,
+                current_target: read_option_index(&mut reader)?,
+                grid_index: read_index(&mut reader)?,
+                pixel_index: (read_u32(&mut reader)?, read_u32(&mut reader)?),
+                previous_pixel_index: (0, 0),
+                max_health: read_u16(&mut reader)?,
+                health: read_u16(&mut reader)?,
+                movement_speed: read_u16(&mut reader)?,
+                attack_damage: read_u8(&mut reader)?,
+                attack_radius: read_u8(&mut reader)?,
+                attack_speed: read_u8(&mut reader)?,
+                direction: u8_to_direction(read_u8(&mut reader)?)?,
+                rect: tile_rect(),
+                texture_path: read_string(&mut reader)?,
+                boss_parts: enemy_manager::EnemyManager::create_boss_parts(enemy_type),
+                boss_phase: read_u8(&mut reader)?,
+                siege_target: read_option_index(&mut reader)?,
+                path_request: None,
+                status_vec: Vec::new(),
+            };
+            let status_amount = read_count(&mut reader)?;
+            for _ in 0..status_amount {
+                //apply_status drops anything that already ran out
+                status_manager::apply_status(&mut enemy.status_vec, read_status(&mut reader)?);
+            }
+            //queued searches aren't saved, a flyer still waiting on one asks again
+            if enemy.final_path.is_none() && enemy.enemy_type.stats().is_flying {
+                enemy.current_target = None;
+            }
+            enemy.previous_pixel_index = enemy.pixel_index;
+            enemy_vec.push(enemy);
+        }
+
+        let projectile_amount = read_count(&mut reader)?;
+        let mut projectile_vec = Vec::with_capacity(projectile_amount);
+        for _ in 0..projectile_amount {
+            let mut projectile = projectile_manager::Projectile {
+                rect: tile_rect(),
+                texture_path: read_string(&mut reader)?,
+                time: read_u8(&mut reader)?,
+                start: (read_i32(&mut reader)?, read_i32(&mut reader)?),
+                position: (read_i32(&mut reader)?, read_i32(&mut reader)?),
+                previous_position: (0, 0),
+                target: (read_i32(&mut reader)?, read_i32(&mut reader)?),
+                hit_target: read_bool(&mut reader)?,
+                angle: read_f64(&mut reader)?,
+                speed: read_f64(&mut reader)?,
+                radius: read_u8(&mut reader)?,
+                damage: read_u8(&mut reader)?,
+                is_hostile: read_bool(&mut reader)?,
+                is_homing: read_bool(&mut reader)?,
+                homing_enemy: read_option_u32(&mut reader)?,
+                effect: read_effect(&mut reader)?,
+                status: read_option_status(&mut reader)?,
+                pierced: Vec::new(),
+            };
+            let pierced_amount = read_count(&mut reader)?;
+            for _ in 0..pierced_amount {
+                projectile.pierced.push(read_u32(&mut reader)?);
+            }
+            projectile.previous_position = projectile.position;
+            projectile_vec.push(projectile);
+        }
+
+        let upgrade_menu_amount = read_count(&mut reader)?;
+        let mut upgrade_menu_saves = Vec::with_capacity(upgrade_menu_amount);
+        for _ in 0..upgrade_menu_amount {
+            let upgrade_menu_save = UpgradeMenuSave {
+                grid_index: read_index(&mut reader)?,
+                menu_active: read_bool(&mut reader)?,
+                first_path_purchased: read_u8(&mut reader)?,
+                second_path_purchased: read_u8(&mut reader)?,
+            };
+            upgrade_menu_saves.push(upgrade_menu_save);
+        }
+
+        let wave_state = u8_to_wave_state(read_u8(&mut reader)?)?;
+        let current_wave = read_u32(&mut reader)?;
+        let wave_timer = read_f64(&mut reader)?;
+        let wave_elapsed = read_f64(&mut reader)?;
+        let spawn_index = read_usize(&mut reader)?;
+        let pending_spawn_amount = read_count(&mut reader)?;
+        let mut pending_spawns = Vec::new();
+        for _ in 0..pending_spawn_amount {
+            let time = read_f64(&mut reader)?;
+            let enemy_type = u8_to_enemy_type(read_u8(&mut reader)?)?;
+            let has_spawn_point = read_bool(&mut reader)?;
+            let spawn_point = read_usize(&mut reader)?;
+            pending_spawns.push(wave_manager::PendingSpawn {
+                time,
+                enemy_type,
+                spawn_point: if has_spawn_point { Some(spawn_point) } else { None },
+            });
+        }
+
+        //anything pointing off the map would only panic later in the simulation
+        let is_on_map = |index: (usize, usize)| level_vec.get(index.0)
+            .is_some_and(|col| index.1 < col.len());
+        let check_index = |index: (usize, usize), name: &str| {
+            if is_on_map(index) {
+                Ok(())
+            }
+            else {
+                Err(invalid_data(format!("{} {:?} is off the map", name, index)))
+            }
+        };
+        if let Some(base_location) = base_location {
+            check_index(base_location, "base location")?;
+        }
+        for target in &target_vec {
+            check_index(*target, "target")?;
+        }
+        for tower in &tower_vec {
+            check_index(tower.bottom_index, "tower")?;
+            check_index(tower.top_index, "tower top")?;
+        }
+        for building in &building_vec {
+            check_index(building.grid_index, "building")?;
+            check_index((building.grid_index.0 + 1, building.grid_index.1 + 1), "building corner")?;
+        }
+        for enemy in &enemy_vec {
+            check_index(enemy.grid_index, "enemy")?;
+            if let Some(current_target) = enemy.current_target {
+                check_index(current_target, "enemy target")?;
+            }
+            if let Some(siege_target) = enemy.siege_target {
+                check_index(siege_target, "enemy siege target")?;
+            }
+            for path_index in enemy.final_path.iter().flatten() {
+                check_index(*path_index, "enemy path")?;
+            }
+        }
+        for upgrade_menu_save in &upgrade_menu_saves {
+            check_index(upgrade_menu_save.grid_index, "upgrade menu")?;
+        }
+
+        game.gold_amount = gold_amount;
+        game.carrot_amount = carrot_amount;
+        game.tomato_amount = tomato_amount;
+        game.frame_time = frame_time;
+        game.base_location = base_location;
+        game.target_vec = target_vec;
+        game.placed = false;
+        game.preview_mode = false;
+
+        player.x = player_x;
+        player.y = player_y;
+        player.direction = player_direction;
+        player.is_attacking = false;
+
+        level.level_vec = level_vec;
+        towers.tower_vec = tower_vec;
+        buildings.base_created = base_created;
+        buildings.building_vec = building_vec;
+        enemies.enemy_vec = enemy_vec;
+        enemies.next_enemy_id = next_enemy_id;
+        projectiles.projectile_vec = projectile_vec;
+        projectiles.explosion_vec.clear();
+
+        wave_manager.find_spawn_points(level);
+        wave_manager.wave_state = wave_state;
+        wave_manager.current_wave = current_wave;
+        wave_manager.wave_timer = wave_timer;
+        wave_manager.wave_elapsed = wave_elapsed;
+        wave_manager.spawn_index = spawn_index;
+        wave_manager.pending_spawns = pending_spawns;
+
+        Ok(upgrade_menu_saves)
+    }
+}
+
+fn tile_rect() -> sdl2::rect::Rect {
+    sdl2::rect::Rect::new(
+        0,
+        0,
+        constants::TILE_SIZE,
+        constants::TILE_SIZE
+    )
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn direction_to_u8(direction: &player_manager::Direction) -> u8 {
+    match direction {
+        player_manager::Direction::Up => 0,
+        player_manager::Direction::Down => 1,
+        player_manager::Direction::Left => 2,
+        player_manager::Direction::Right => 3,
+        player_manager::Direction::UpLeft => 4,
+        player_manager::Direction::UpRight => 5,
+        player_manager::Direction::DownLeft => 6,
+        player_manager::Direction::DownRight => 7,
+        player_manager::Direction::None => 8,
+    }
+}
+
+fn u8_to_direction(value: u8) -> Result<player_manager::Direction, std::io::Error> {
+    match value {
+        0 => Ok(player_manager::Direction::Up),
+        1 => Ok(player_manager::Direction::Down),
+        2 => Ok(player_manager::Direction::Left),
+        3 => Ok(player_manager::Direction::Right),
+        4 => Ok(player_manager::Direction::UpLeft),
+        5 => Ok(player_manager::Direction::UpRight),
+        6 => Ok(player_manager::Direction::DownLeft),
+        7 => Ok(player_manager::Direction::DownRight),
+        8 => Ok(player_manager::Direction::None),
+        _ => Err(invalid_data(format!("invalid direction {}", value))),
+    }
+}
+
+fn tile_data_to_u8(tile_data: &TileData) -> u8 {
+    match tile_data {
+        TileData::Base => 0,
+        TileData::ArcherTowerBottom => 1,
+        TileData::ArcherTowerTop => 2,
+        TileData::FireballTowerBottom => 3,
+        TileData::FireballTowerTop => 4,
+        TileData::Carrots => 5,
+        TileData::Tomatoes => 6,
+        TileData::Goblin => 7,
+        TileData::None => 8,
+    }
+}
+
+fn u8_to_tile_data(value: u8) -> Result<TileData, std::io::Error> {
+    match value {
+        0 => Ok(TileData::Base),
+        1 => Ok(TileData::ArcherTowerBottom),
+        2 => Ok(TileData::ArcherTowerTop),
+        3 => Ok(TileData::FireballTowerBottom),
+        4 => Ok(TileData::FireballTowerTop),
+        5 => Ok(TileData::Carrots),
+        6 => Ok(TileData::Tomatoes),
+        7 => Ok(TileData::Goblin),
+        8 => Ok(TileData::None),
+        _ => Err(invalid_data(format!("invalid tile data {}", value))),
+    }
+}
+
+fn enemy_type_to_u8(enemy_type: &enemy_manager::EnemyType) -> u8 {
+    match enemy_type {
+        enemy_manager::EnemyType::Goblin => 0,
+        enemy_manager::EnemyType::Runner => 1,
+        enemy_manager::EnemyType::Brute => 2,
+        enemy_manager::EnemyType::Archer => 3,
+        enemy_manager::EnemyType::Bat => 4,
+        enemy_manager::EnemyType::Ogre => 5,
+    }
+}
+
+fn u8_to_enemy_type(value: u8) -> Result<enemy_manager::EnemyType, std::io::Error> {
+    match value {
+        0 => Ok(enemy_manager::EnemyType::Goblin),
+        1 => Ok(enemy_manager::EnemyType::Runner),
+        2 => Ok(enemy_manager::EnemyType::Brute),
+        3 => Ok(enemy_manager::EnemyType::Archer),
+        4 => Ok(enemy_manager::EnemyType::Bat),
+        5 => Ok(enemy_manager::EnemyType::Ogre),
+        _ => Err(invalid_data(format!("invalid enemy type {}", value))),
+    }
+}
+
+fn targeting_mode_to_u8(targeting_mode: &tower_manager::TargetingMode) -> u8 {
+    match targeting_mode {
+        tower_manager::TargetingMode::First => 0,
+        tower_manager::TargetingMode::Last => 1,
+        tower_manager::TargetingMode::Strongest => 2,
+        tower_manager::TargetingMode::Weakest => 3,
+        tower_manager::TargetingMode::Closest => 4,
+    }
+}
+
+fn u8_to_targeting_mode(value: u8) -> Result<tower_manager::TargetingMode, std::io::Error> {
+    match value {
+        0 => Ok(tower_manager::TargetingMode::First),
+        1 => Ok(tower_manager::TargetingMode::Last),
+        2 => Ok(tower_manager::TargetingMode::Strongest),
+        3 => Ok(tower_manager::TargetingMode::Weakest),
+        4 => Ok(tower_manager::TargetingMode::Closest),
+        _ => Err(invalid_data(format!("invalid targeting mode {}", value))),
+    }
+}
+
+fn write_effect(
+    writer: &mut impl Write,
+    effect: projectile_manager::ProjectileEffect
+) -> Result<(), std::io::Error> {
+    match effect {
+        projectile_manager::ProjectileEffect::Single => write_u8(writer, 0),
+        projectile_manager::ProjectileEffect::Splash { radius, falloff } => {
+            write_u8(writer, 1)?;
+            write_i32(writer, radius)?;
+            write_u8(writer, falloff)
+        }
+        projectile_manager::ProjectileEffect::Burn { damage, duration } => {
+            write_u8(writer, 2)?;
+            write_u8(writer, damage)?;
+            write_u16(writer, duration)
+        }
+        projectile_manager::ProjectileEffect::Pierce { hits } => {
+            write_u8(writer, 3)?;
+            write_u8(writer, hits)
+        }
+    }
+}
+
+fn read_effect(reader: &mut impl Read) -> Result<projectile_manager::ProjectileEffect, std::io::Error> {
+    match read_u8(reader)? {
+        0 => Ok(projectile_manager::ProjectileEffect::Single),
+        1 => Ok(projectile_manager::ProjectileEffect::Splash {
+            radius: read_i32(reader)?,
+            falloff: read_u8(reader)?,
+        }),
+        2 => Ok(projectile_manager::ProjectileEffect::Burn {
+            damage: read_u8(reader)?,
+            duration: read_u16(reader)?,
+        }),
+        3 => Ok(projectile_manager::ProjectileEffect::Pierce {
+            hits: read_u8(reader)?,
+        }),
+        value => Err(invalid_data(format!("invalid projectile effect {}", value))),
+    }
+}
+
+fn write_status(
+    writer: &mut impl Write,
+    status: status_manager::StatusEffect
+) -> Result<(), std::io::Error> {
+    let kind = status_manager::StatusKind::ALL.iter()
+        .position(|kind| *kind == status.kind)
+        .unwrap_or(0);
+    write_u8(writer, kind as u8)?;
+    write_u8(writer, status.strength)?;
+    write_u16(writer, status.time)
+}
+
+fn read_status(reader: &mut impl Read) -> Result<status_manager::StatusEffect, std::io::Error> {
+    let value = read_u8(reader)?;
+    let Some(kind) = status_manager::StatusKind::ALL.get(value as usize).copied() else {
+        return Err(invalid_data(format!("invalid status kind {}", value)));
+    };
+    Ok(status_manager::StatusEffect {
+        kind,
+        strength: read_u8(reader)?,
+        time: read_u16(reader)?,
+    })
+}
+
+fn write_option_status(
+    writer: &mut impl Write,
+    status: Option<status_manager::StatusEffect>
+) -> Result<(), std::io::Error> {
+    match status {
+        Some(status) => {
+            write_bool(writer, true)?;
+            write_status(writer, status)
+        }
+        None => write_bool(writer, false),
+    }
+}
+
+fn read_option_status(reader: &mut impl Read) -> Result<Option<status_manager::StatusEffect>, std::io::Error> {
+    if read_bool(reader)? {
+        Ok(Some(read_status(reader)?))
+    }
+    else {
+        Ok(None)
+    }
+}
+
+fn write_option_u32(
+    writer: &mut impl Write,
+    value: Option<u32>
+) -> Result<(), std::io::Error> {
+    match value {
+        Some(value) => {
+            write_bool(writer, true)?;
+            write_u32(writer, value)
+        }
+        None => write_bool(writer, false),
+    }
+}
+
+fn read_option_u32(reader: &mut impl Read) -> Result<Option<u32>, std::io::Error> {
+    if read_bool(reader)? {
+        Ok(Some(read_u32(reader)?))
+    }
+    else {
+        Ok(None)
+    }
+}
+
+fn building_type_to_u8(building_type: &building_manager::BuildingType) -> u8 {
+    match building_type {
+        building_manager::BuildingType::Base => 0,
+        building_manager::BuildingType::None => 1,
+    }
+}
+
+fn u8_to_building_type(value: u8) -> Result<building_manager::BuildingType, std::io::Error> {
+    match value {
+        0 => Ok(building_manager::BuildingType::Base),
+        1 => Ok(building_manager::BuildingType::None),
+        _ => Err(invalid_data(format!("invalid building type {}", value))),
+    }
+}
+
+fn wave_state_to_u8(wave_state: &wave_manager::WaveState) -> u8 {
+    match wave_state {
+        wave_manager::WaveState::Build => 0,
+        wave_manager::WaveState::Spawning => 1,
+        wave_manager::WaveState::Active => 2,
+    }
+}
+
+fn u8_to_wave_state(value: u8) -> Result<wave_manager::WaveState, std::io::Error> {
+    match value {
+        0 => Ok(wave_manager::WaveState::Build),
+        1 => Ok(wave_manager::WaveState::Spawning),
+        2 => Ok(wave_manager::WaveState::Active),
+        _ => Err(invalid_data(format!("invalid wave state {}", value))),
+    }
+}
+
+fn write_u8(writer: &mut impl Write, value: u8) -> Result<(), std::io::Error> {
+    writer.write_all(&[value])
+}
+
+fn write_bool(writer: &mut impl Write, value: bool) -> Result<(), std::io::Error> {
+    write_u8(writer, value as u8)
+}
+
+fn write_u16(writer: &mut impl Write, value: u16) -> Result<(), std::io::Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_u32(writer: &mut impl Write, value: u32) -> Result<(), std::io::Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_i32(writer: &mut impl Write, value: i32) -> Result<(), std::io::Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_usize(writer: &mut impl Write, value: usize) -> Result<(), std::io::Error> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+fn write_f32(writer: &mut impl Write, value: f32) -> Result<(), std::io::Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_f64(writer: &mut impl Write, value: f64) -> Result<(), std::io::Error> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_char(writer: &mut impl Write, value: char) -> Result<(), std::io::Error> {
+    write_u32(writer, value as u32)
+}
+
+fn write_string(writer: &mut impl Write, value: &str) -> Result<(), std::io::Error> {
+    write_usize(writer, value.len())?;
+    writer.write_all(value.as_bytes())
+}
+
+fn write_index(writer: &mut impl Write, index: (usize, usize)) -> Result<(), std::io::Error> {
+    write_usize(writer, index.0)?;
+    write_usize(writer, index.1)
+}
+
+fn write_option_index(
+    writer: &mut impl Write,
+    index: Option<(usize, usize)>
+) -> Result<(), std::io::Error> {
+    match index {
+        Some(index) => {
+            write_bool(writer, true)?;
+            write_index(writer, index)
+        }
+        None => write_bool(writer, false),
+    }
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> Result<[u8; N], std::io::Error> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u8, std::io::Error> {
+    Ok(read_bytes::<1>(reader)?[0])
+}
+
+fn read_bool(reader: &mut impl Read) -> Result<bool, std::io::Error> {
+    match read_u8(reader)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => Err(invalid_data(format!("invalid bool {}", value))),
+    }
+}
+
+fn read_u16(reader: &mut impl Read) -> Result<u16, std::io::Error> {
+    Ok(u16::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, std::io::Error> {
+    Ok(u32::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_i32(reader: &mut impl Read) -> Result<i32, std::io::Error> {
+    Ok(i32::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_usize(reader: &mut impl Read) -> Result<usize, std::io::Error> {
+    let value = u64::from_le_bytes(read_bytes(reader)?);
+    usize::try_from(value).map_err(|_| invalid_data(format!("invalid length {}", value)))
+}
+
+fn read_f32(reader: &mut impl Read) -> Result<f32, std::io::Error> {
+    Ok(f32::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_f64(reader: &mut impl Read) -> Result<f64, std::io::Error> {
+    Ok(f64::from_le_bytes(read_bytes(reader)?))
+}
+
+fn read_char(reader: &mut impl Read) -> Result<char, std::io::Error> {
+    let value = read_u32(reader)?;
+    char::from_u32(value).ok_or_else(|| invalid_data(format!("invalid char {}", value)))
+}
+
+fn read_tile_kind(reader: &mut impl Read) -> Result<TileKind, std::io::Error> {
+    let value = read_char(reader)?;
+    TileKind::from_char(value).ok_or_else(|| invalid_data(format!("invalid tile {:?}", value)))
+}
+
+fn read_string(reader: &mut impl Read) -> Result<String, std::io::Error> {
+    let length = read_usize(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(length as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != length {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+    }
+    String::from_utf8(bytes).map_err(|e| invalid_data(e.to_string()))
+}
+
+//counts bigger than any real save are a corrupt file, not something to allocate for
+fn read_count(reader: &mut impl Read) -> Result<usize, std::io::Error> {
+    let count = read_usize(reader)?;
+    if count > constants::SAVE_MAX_COUNT {
+        return Err(invalid_data(format!("invalid count {}", count)));
+    }
+    Ok(count)
+}
+
+fn read_index(reader: &mut impl Read) -> Result<(usize, usize), std::io::Error> {
+    Ok((read_usize(reader)?, read_usize(reader)?))
+}
+
+fn read_option_index(reader: &mut impl Read) -> Result<Option<(usize, usize)>, std::io::Error> {
+    if read_bool(reader)? {
+        Ok(Some(read_index(reader)?))
+    }
+    else {
+        Ok(None)
+    }
+}
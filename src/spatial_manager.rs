@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+//uniform grid bucketing things by pixel position so range checks only look at nearby cells
+//stores indices into whatever vec it was built from, rebuild it whenever that vec changes
+pub struct SpatialGrid {
+    pub cell_size: i32,
+    //cells keep their allocation between rebuilds, empty ones are left in
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: i32) -> SpatialGrid {
+        let spatial_grid = SpatialGrid {
+            cell_size,
+            cells: HashMap::new(),
+        };
+        spatial_grid
+    }
+
+    pub fn rebuild(&mut self, positions: impl Iterator<Item = (i32, i32)>) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+        for (index, position) in positions.enumerate() {
+            self.cells.entry(self.cell_of(position)).or_default().push(index);
+        }
+    }
+
+    //every index that can be within TowerManager::is_within_area(center, position, area), in ascending order
+    //callers still do the exact check, this only throws away far cells
+    pub fn query(&self, center: (i32, i32), area: i32) -> Vec<usize> {
+        let area = area.max(0);
+        let min_cell = self.cell_of((center.0 - area, center.1 - area));
+        let max_cell = self.cell_of((center.0 + area, center.1 + area));
+        let cell_amount = (max_cell.0 - min_cell.0 + 1) as i64 * (max_cell.1 - min_cell.1 + 1) as i64;
+
+        let mut found = Vec::new();
+        //huge areas would look up more cells than exist
+        if cell_amount > self.cells.len() as i64 {
+            for (cell, indices) in &self.cells {
+                if cell.0 >= min_cell.0 && cell.0 <= max_cell.0
+                && cell.1 >= min_cell.1 && cell.1 <= max_cell.1 {
+                    found.extend_from_slice(indices);
+                }
+            }
+        }
+        else {
+            for cell_x in min_cell.0..=max_cell.0 {
+                for cell_y in min_cell.1..=max_cell.1 {
+                    if let Some(indices) = self.cells.get(&(cell_x, cell_y)) {
+                        found.extend_from_slice(indices);
+                    }
+                }
+            }
+        }
+        //same order as the vec so ties and hit order don't depend on the cell layout
+        found.sort_unstable();
+        found
+    }
+
+    fn cell_of(&self, position: (i32, i32)) -> (i32, i32) {
+        (position.0.div_euclid(self.cell_size), position.1.div_euclid(self.cell_size))
+    }
+}
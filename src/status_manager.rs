@@ -0,0 +1,136 @@
+use crate::constants;
+
+//effects that wear off on their own, anything that hits an enemy applies them with apply_status
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum StatusKind {
+    //strength is the percent of movement speed taken away
+    Slow,
+    //strength is damage every BURN_INTERVAL steps
+    Burn,
+    //strength is damage every POISON_INTERVAL steps
+    Poison,
+    //can't move or attack, strength is unused
+    Stun,
+}
+
+impl StatusKind {
+    pub const ALL: [StatusKind; 4] = [
+        StatusKind::Slow,
+        StatusKind::Burn,
+        StatusKind::Poison,
+        StatusKind::Stun,
+    ];
+
+    //how many of this kind can be on an enemy at once
+    //single ones keep the strongest strength and longest time, stacked ones each run out on their own
+    pub fn max_stacks(self) -> usize {
+        match self {
+            StatusKind::Poison => constants::POISON_MAX_STACKS,
+            _ => 1,
+        }
+    }
+
+    //steps between damage ticks, None for effects that don't hurt
+    pub fn tick_interval(self) -> Option<u16> {
+        match self {
+            StatusKind::Burn => Some(constants::BURN_INTERVAL),
+            StatusKind::Poison => Some(constants::POISON_INTERVAL),
+            StatusKind::Slow | StatusKind::Stun => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusKind::Slow => "slow",
+            StatusKind::Burn => "burn",
+            StatusKind::Poison => "poison",
+            StatusKind::Stun => "stun",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<StatusKind> {
+        StatusKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub fn icon_color(self) -> sdl2::pixels::Color {
+        match self {
+            StatusKind::Slow => constants::COLOR_STATUS_SLOW,
+            StatusKind::Burn => constants::COLOR_STATUS_BURN,
+            StatusKind::Poison => constants::COLOR_STATUS_POISON,
+            StatusKind::Stun => constants::COLOR_STATUS_STUN,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub strength: u8,
+    //steps left
+    pub time: u16,
+}
+
+pub fn apply_status(status_vec: &mut Vec<StatusEffect>, status: StatusEffect) {
+    if status.time == 0 {
+        return
+    }
+    let max_stacks = status.kind.max_stacks();
+    if max_stacks == 1 {
+        if let Some(existing) = status_vec.iter_mut().find(|existing| existing.kind == status.kind) {
+            existing.strength = existing.strength.max(status.strength);
+            existing.time = existing.time.max(status.time);
+            return
+        }
+    }
+    else if status_vec.iter().filter(|existing| existing.kind == status.kind).count() >= max_stacks {
+        //full, the stack closest to running out makes room if the new one lasts longer
+        let weakest = status_vec.iter_mut()
+            .filter(|existing| existing.kind == status.kind)
+            .min_by_key(|existing| existing.time);
+        if let Some(weakest) = weakest {
+            if weakest.time < status.time {
+                *weakest = status;
+            }
+        }
+        return
+    }
+    status_vec.push(status);
+}
+
+//one simulation step of every effect, returns the damage they did
+pub fn tick_statuses(status_vec: &mut Vec<StatusEffect>) -> u16 {
+    let mut damage: u16 = 0;
+    for status in status_vec.iter_mut() {
+        status.time = status.time.saturating_sub(1);
+        if let Some(interval) = status.kind.tick_interval() {
+            if status.time % interval == 0 {
+                damage = damage.saturating_add(status.strength as u16);
+            }
+        }
+    }
+    status_vec.retain(|status| status.time > 0);
+    damage
+}
+
+//percent of movement speed left after the strongest slow
+pub fn speed_percent(status_vec: &[StatusEffect]) -> u8 {
+    let slow = status_vec.iter()
+        .filter(|status| status.kind == StatusKind::Slow)
+        .map(|status| status.strength.min(100))
+        .max()
+        .unwrap_or(0);
+    100 - slow
+}
+
+//steps left until the enemy can move again
+pub fn stun_time(status_vec: &[StatusEffect]) -> u16 {
+    status_vec.iter()
+        .filter(|status| status.kind == StatusKind::Stun)
+        .map(|status| status.time)
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn has_status(status_vec: &[StatusEffect], kind: StatusKind) -> bool {
+    status_vec.iter().any(|status| status.kind == kind)
+}
@@ -3,9 +3,93 @@ use crate::game_manager;
 use crate::level_manager;
 use crate::level_manager::TileData;
 use crate::projectile_manager;
+use crate::status_manager;
 use crate::texture_manager;
 use crate::enemy_manager;
 use crate::gui_manager;
+use crate::pathfinding_manager;
+
+//which enemy in range a tower shoots at, switched from the upgrade menu
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TargetingMode {
+    //least path left to its target
+    First,
+    Last,
+    Strongest,
+    Weakest,
+    Closest,
+}
+
+impl TargetingMode {
+    pub fn next(self) -> TargetingMode {
+        match self {
+            TargetingMode::First => TargetingMode::Last,
+            TargetingMode::Last => TargetingMode::Strongest,
+            TargetingMode::Strongest => TargetingMode::Weakest,
+            TargetingMode::Weakest => TargetingMode::Closest,
+            TargetingMode::Closest => TargetingMode::First,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TargetingMode::First => "first",
+            TargetingMode::Last => "last",
+            TargetingMode::Strongest => "strongest",
+            TargetingMode::Weakest => "weakest",
+            TargetingMode::Closest => "closest",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TargetingMode> {
+        match name {
+            "first" => Some(TargetingMode::First),
+            "last" => Some(TargetingMode::Last),
+            "strongest" => Some(TargetingMode::Strongest),
+            "weakest" => Some(TargetingMode::Weakest),
+            "closest" => Some(TargetingMode::Closest),
+            _ => None,
+        }
+    }
+}
+
+//the two rows of the upgrade menu, each is bought in order
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum UpgradePath {
+    First,
+    Second,
+}
+
+impl UpgradePath {
+    pub fn name(self) -> &'static str {
+        match self {
+            UpgradePath::First => "first",
+            UpgradePath::Second => "second",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<UpgradePath> {
+        match name {
+            "first" => Some(UpgradePath::First),
+            "second" => Some(UpgradePath::Second),
+            _ => None,
+        }
+    }
+}
+
+//one step along an upgrade path, the upgrade menu shows these and upgrade_tower buys them
+#[derive(Clone, Copy)]
+pub struct TowerUpgrade {
+    pub name: &'static str,
+    pub cost: u32,
+    pub damage: u8,
+    pub health: u16,
+    pub radius: i32,
+    //switches what the tower's projectiles do
+    pub effect: Option<projectile_manager::ProjectileEffect>,
+    //put on enemies by the tower's projectiles from then on
+    pub status: Option<status_manager::StatusEffect>,
+}
 
 pub struct Tower {
     pub bottom_index: (usize, usize),
@@ -23,6 +107,15 @@ pub struct Tower {
     pub projectile_damage: u8,
     pub projectile_speed: f64,
     pub projectile_radius: u8,
+    //follows the enemy instead of leading it
+    pub projectile_homing: bool,
+    pub projectile_effect: projectile_manager::ProjectileEffect,
+    //put on every enemy the projectile damages
+    pub projectile_status: Option<status_manager::StatusEffect>,
+    pub targeting_mode: TargetingMode,
+    //how far along each upgrade path the tower is
+    pub first_path_purchased: u8,
+    pub second_path_purchased: u8,
 }
 
 pub struct TowerManager {
@@ -67,10 +160,16 @@ impl TowerManager {
                     max_health: constants::TOWER_ARCHER_HEALTH,
                     health: constants::TOWER_ARCHER_HEALTH,
                     is_attacking: false,
+                    targeting_mode: TargetingMode::First,
                     projectile_texture: constants::TEXTURE_PROJECTILE_ARROW.to_string(),
                     projectile_speed: constants::PROJECTILE_ARROW_SPEED,
                     projectile_radius: constants::PROJECTILE_ARROW_RADIUS,
                     projectile_damage: constants::TOWER_ARCHER_DAMAGE,
+                    projectile_homing: constants::TOWER_ARCHER_HOMING,
+                    projectile_effect: projectile_manager::ProjectileEffect::Single,
+                    projectile_status: None,
+                    first_path_purchased: 0,
+                    second_path_purchased: 0,
 
                 };
                 game.target_vec.push((tower_tile.bottom_index.0, tower_tile.bottom_index.1));
@@ -99,10 +198,19 @@ impl TowerManager {
                     max_health: constants::TOWER_FIREBALL_HEALTH,
                     health: constants::TOWER_FIREBALL_HEALTH,
                     is_attacking: false,
+                    targeting_mode: TargetingMode::First,
                     projectile_texture: constants::TEXTURE_PROJECTILE_FIREBALL.to_string(),
                     projectile_speed: constants::PROJECTILE_FIREBALL_SPEED,
                     projectile_radius: constants::PROJECTILE_FIREBALL_RADIUS,
                     projectile_damage: constants::TOWER_FIREBALL_DAMAGE,
+                    projectile_homing: constants::TOWER_FIREBALL_HOMING,
+                    projectile_effect: projectile_manager::ProjectileEffect::Splash {
+                        radius: constants::TOWER_FIREBALL_SPLASH_RADIUS,
+                        falloff: constants::TOWER_FIREBALL_SPLASH_FALLOFF,
+                    },
+                    projectile_status: None,
+                    first_path_purchased: 0,
+                    second_path_purchased: 0,
 
                 };
                 game.target_vec.push((tower_tile.bottom_index.0, tower_tile.bottom_index.1));
@@ -131,10 +239,16 @@ impl TowerManager {
                     max_health: 0,
                     health: 0,
                     is_attacking: false,
+                    targeting_mode: TargetingMode::First,
                     projectile_texture: constants::TEXTURE_DEFAULT.to_string(),
                     projectile_speed: 0.0,
                     projectile_radius: 0,
                     projectile_damage: 0,
+                    projectile_homing: false,
+                    projectile_effect: projectile_manager::ProjectileEffect::Single,
+                    projectile_status: None,
+                    first_path_purchased: 0,
+                    second_path_purchased: 0,
 
                 };
                 game.target_vec.push((
@@ -145,8 +259,141 @@ impl TowerManager {
             }
         }
     }
+    //what each path of a tower kind offers, in the order it's bought
+    pub fn upgrade_path(tile_kind: level_manager::TileKind, path: UpgradePath) -> Vec<TowerUpgrade> {
+        let upgrade = |name, cost, damage, health, radius| TowerUpgrade {
+            name,
+            cost,
+            damage,
+            health,
+            radius,
+            effect: None,
+            status: None,
+        };
+        match (tile_kind, path) {
+            (level_manager::TileKind::ArcherBottom, UpgradePath::First) => vec![
+                TowerUpgrade {
+                    status: Some(status_manager::StatusEffect {
+                        kind: status_manager::StatusKind::Poison,
+                        strength: constants::TOWER_ARCHER_POISON_DAMAGE,
+                        time: constants::TOWER_ARCHER_POISON_DURATION,
+                    }),
+                    ..upgrade("broadheads", 5, constants::TOWER_ARCHER_DAMAGE * 2, 0, 0)
+                },
+                TowerUpgrade {
+                    effect: Some(projectile_manager::ProjectileEffect::Pierce {
+                        hits: constants::TOWER_ARCHER_PIERCE_HITS,
+                    }),
+                    ..upgrade("extra training", 1, constants::TOWER_ARCHER_DAMAGE * 3, 0, 5)
+                },
+            ],
+            (level_manager::TileKind::ArcherBottom, UpgradePath::Second) => vec![
+                upgrade("reinforced tower", 5, 0, constants::TOWER_ARCHER_HEALTH * 2, 0),
+                upgrade("more reinforceteststststststststststst", 15, 0, constants::TOWER_ARCHER_HEALTH * 3, 0),
+            ],
+            (level_manager::TileKind::FireballBottom, UpgradePath::First) => vec![
+                TowerUpgrade {
+                    status: Some(status_manager::StatusEffect {
+                        kind: status_manager::StatusKind::Stun,
+                        strength: 0,
+                        time: constants::TOWER_FIREBALL_STUN_DURATION,
+                    }),
+                    ..upgrade("bigger balls", 5, constants::TOWER_FIREBALL_DAMAGE * 2, 0, 5)
+                },
+                TowerUpgrade {
+                    effect: Some(projectile_manager::ProjectileEffect::Burn {
+                        damage: constants::TOWER_FIREBALL_BURN_DAMAGE,
+                        duration: constants::TOWER_FIREBALL_BURN_DURATION,
+                    }),
+                    ..upgrade("extra hot balls", 1, constants::TOWER_FIREBALL_DAMAGE * 3, 0, 0)
+                },
+            ],
+            (level_manager::TileKind::FireballBottom, UpgradePath::Second) => vec![
+                upgrade("reinforced tower", 5, 0, constants::TOWER_FIREBALL_HEALTH * 2, 0),
+                upgrade("m", 15, 0, constants::TOWER_FIREBALL_HEALTH * 3, 0),
+            ],
+            _ => Vec::new(),
+        }
+    }
+    //found by tile instead of vec position, towers shift when one before them is destroyed
+    //the next upgrade on the path is bought if there is one and the gold is there
+    pub fn upgrade_tower(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &level_manager::LevelManager,
+        index: (usize, usize),
+        path: UpgradePath,
+    ) {
+        let Some(tower) = self.tower_vec.iter_mut().find(|tower| tower.bottom_index == index) else {
+            return
+        };
+        let purchased = match path {
+            UpgradePath::First => &mut tower.first_path_purchased,
+            UpgradePath::Second => &mut tower.second_path_purchased,
+        };
+        let tile_kind = level.level_vec[index.0][index.1].tile_type;
+        let Some(upgrade) = Self::upgrade_path(tile_kind, path).get(*purchased as usize).copied() else {
+            return
+        };
+        if game.gold_amount < upgrade.cost {
+            return
+        }
+        game.gold_amount -= upgrade.cost;
+        *purchased += 1;
+        tower.health += upgrade.health;
+        tower.max_health += upgrade.health;
+        tower.projectile_damage += upgrade.damage;
+        tower.attack_radius += upgrade.radius;
+        if let Some(effect) = upgrade.effect {
+            tower.projectile_effect = effect;
+        }
+        if let Some(status) = upgrade.status {
+            tower.projectile_status = Some(status);
+        }
+        println!("NEXT DAMAGE: {}", tower.projectile_damage);
+    }
+    pub fn set_targeting_mode(
+        &mut self,
+        index: (usize, usize),
+        targeting_mode: TargetingMode,
+    ) {
+        if let Some(tower) = self.tower_vec.iter_mut().find(|tower| tower.bottom_index == index) {
+            tower.targeting_mode = targeting_mode;
+        }
+    }
+    //index into enemy_vec of the living enemy in range picked by the tower's targeting mode
+    //ties go to the enemy earliest in enemy_vec, enemy_grid has to be up to date
+    pub fn choose_target(
+        tower: &Tower,
+        tower_pos: (i32, i32),
+        enemies: &enemy_manager::EnemyManager,
+        pathfinding_manager: &pathfinding_manager::PathfindingManager,
+    ) -> Option<usize> {
+        let in_range = enemies.enemy_grid.query(tower_pos, tower.attack_radius)
+            .into_iter()
+            .map(|enemy_index| (enemy_index, &enemies.enemy_vec[enemy_index]))
+            .filter(|(_, enemy)| {
+                let enemy_pos = (enemy.pixel_index.0 as i32, enemy.pixel_index.1 as i32);
+                enemy.health != 0
+                && enemy_pos != tower_pos
+                && Self::is_within_area(tower_pos, enemy_pos, tower.attack_radius)
+            });
+        let target = match tower.targeting_mode {
+            TargetingMode::First => in_range.min_by_key(|(_, enemy)| pathfinding_manager.remaining_distance(enemy)),
+            TargetingMode::Last => in_range.min_by_key(|(_, enemy)| std::cmp::Reverse(pathfinding_manager.remaining_distance(enemy))),
+            TargetingMode::Strongest => in_range.min_by_key(|(_, enemy)| std::cmp::Reverse(enemy.health)),
+            TargetingMode::Weakest => in_range.min_by_key(|(_, enemy)| enemy.health),
+            TargetingMode::Closest => in_range.min_by_key(|(_, enemy)| {
+                let dx = enemy.pixel_index.0 as i64 - tower_pos.0 as i64;
+                let dy = enemy.pixel_index.1 as i64 - tower_pos.1 as i64;
+                dx * dx + dy * dy
+            }),
+        };
+        target.map(|(enemy_index, _)| enemy_index)
+    }
     pub fn render_towers(&mut self, 
-        game: &mut game_manager::GameManager, 
+        game: &mut game_manager::GameManager,
+        canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
         tex_man: &mut texture_manager::TextureManager<sdl2::video::WindowContext>, 
         health_bars: &mut gui_manager::GUIManager,
     ) -> Result<(), String> {
@@ -160,7 +407,7 @@ impl TowerManager {
             tower_bottom.bottom_rect.set_y(pixel_index.1 - game.cam_y);
 
             let bottom_texture = tex_man.load(&tower_bottom.bottom_texture_path)?;
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &bottom_texture,
                 None,
                 tower_bottom.bottom_rect,
@@ -181,7 +428,7 @@ impl TowerManager {
             tower_top.top_rect.set_y(pixel_index.1 - game.cam_y);
 
             let top_texture = tex_man.load(&tower_top.top_texture_path)?;
-            game.canvas.copy_ex(
+            canvas.copy_ex(
                 &top_texture,
                 None,
                 tower_top.top_rect,
@@ -191,7 +438,7 @@ impl TowerManager {
                 false,
             )?;
             if tower_top.health < tower_top.max_health {
-                health_bars.render_health_bar_tower(game, tower_top);
+                health_bars.render_health_bar_tower(canvas, tower_top);
             }
         }
         Ok(())
@@ -1,15 +1,11 @@
 use crate::building_manager;
 use crate::constants;
 use crate::event_manager;
-use crate::gui_manager;
 use crate::level_manager;
 use crate::tower_manager;
+use crate::tower_manager::UpgradePath;
 use crate::game_manager;
-
-// pub enum UpgradePath {
-//     First,
-//     Second,
-// }
+use crate::replay_manager;
 
 pub enum BuildingType {
     Archer,
@@ -27,10 +23,15 @@ pub enum UpgradeType {
 pub struct Upgrade <'a> {
     texture_surface: sdl2::surface::Surface<'a>,
     upgrade_rect: sdl2::rect::Rect,
-    cost: u32,
-    damage: u8,
-    health: u16,
-    radius: i32,
+    upgrade: tower_manager::TowerUpgrade,
+    last_clicked: i32,
+}
+
+//cycles the tower's targeting mode, the mode shown is the last one picked from this menu
+pub struct TargetingButton <'a> {
+    texture_surface: sdl2::surface::Surface<'a>,
+    targeting_rect: sdl2::rect::Rect,
+    targeting_mode: tower_manager::TargetingMode,
     last_clicked: i32,
 }
 
@@ -40,9 +41,12 @@ pub struct UpgradeMenu <'a> {
     upgrades_second_path: std::collections::LinkedList<Upgrade <'a>>,
     current_first_path: Option<Upgrade<'a>>,
     current_second_path: Option<Upgrade<'a>>,
-    menu_active: bool,
-    grid_index: (usize, usize),
+    targeting_button: TargetingButton<'a>,
+    pub menu_active: bool,
+    pub grid_index: (usize, usize),
     building_index: usize,
+    pub first_path_purchased: u8,
+    pub second_path_purchased: u8,
 }
 
 pub struct UpgradeManager <'a> {
@@ -53,9 +57,9 @@ pub struct UpgradeManager <'a> {
 }
 
 impl<'a> UpgradeManager <'a> {
-    pub fn new(game:&mut game_manager::GameManager, font: &'a sdl2::ttf::Font<'a, 'a>) -> UpgradeManager<'a> {
+    pub fn new(canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, font: &'a sdl2::ttf::Font<'a, 'a>) -> UpgradeManager<'a> {
         let upgrades = UpgradeManager {
-            texture_creator: game.canvas.texture_creator(),
+            texture_creator: canvas.texture_creator(),
             upgrade_menu_vec: Vec::new(),
             font,
         };
@@ -65,7 +69,6 @@ impl<'a> UpgradeManager <'a> {
         game: &mut game_manager::GameManager,
         towers: &mut tower_manager::TowerManager, 
         buildings: &mut building_manager::BuildingManager, 
-        gui_manager: &mut gui_manager::GUIManager, 
         temp_tile: &mut level_manager::LevelTile, 
         grid_index: (usize, usize)) 
     -> Result<(), String> {
@@ -82,17 +85,17 @@ impl<'a> UpgradeManager <'a> {
 
         if !upgrade_exists {
             match temp_tile.tile_type {
-                constants::TILE_TYPE_ARCHER_BOTTOM => {
+                level_manager::TileKind::ArcherBottom => {
                     self.create_upgrades(
-                        gui_manager,
+                        game,
                         towers,
                         BuildingType::Archer,
                         grid_index
                     );
                 },
-                constants::TILE_TYPE_FIREBALL_BOTTOM => {
+                level_manager::TileKind::FireballBottom => {
                     self.create_upgrades(
-                        gui_manager,
+                        game,
                         towers,
                         BuildingType::Fireball,
                         grid_index
@@ -104,141 +107,92 @@ impl<'a> UpgradeManager <'a> {
         Ok(())
     }
     pub fn create_upgrades(&mut self, 
-        gui_manager: &mut gui_manager::GUIManager, 
+        game: &mut game_manager::GameManager, 
         towers: &mut tower_manager::TowerManager, 
         building_type: BuildingType, 
         grid_index: (usize, usize)) 
     -> Result<(), String> {
-        match building_type {
-            BuildingType::Archer => {
-                for tower_index in 0..towers.tower_vec.len() {
-                    let tower = &mut towers.tower_vec[tower_index];
-                    if tower.bottom_index == grid_index {
-                        let upgrade_menu: Option<UpgradeMenu> 
-                        = match Self::initialize_upgrade_menu(grid_index, tower_index) {
-                            Ok(upgrade_menu) => Some(upgrade_menu),
-                            Err(e) => None,
-                        };
-                        match upgrade_menu {
-                            Some(mut upgrade_menu) => {
-                                if let Ok(upgrade_first_0) 
-                                = self.create_upgrade_button(
-                                    "broadheads",
-                                    0,
-                                    constants::TOWER_ARCHER_DAMAGE * 2,
-                                    0,
-                                    5
-                                ) {
-                                    upgrade_menu.upgrades_first_path.push_back(upgrade_first_0);
-                                }
-                                if let Ok(upgrade_first_1) 
-                                = self.create_upgrade_button(
-                                    "extra training",
-                                    0,
-                                    constants::TOWER_ARCHER_DAMAGE * 3,
-                                    5,
-                                    1
-                                ) {
-                                    upgrade_menu.upgrades_first_path.push_back(upgrade_first_1);
-                                }
-                                if let Ok(upgrade_second_0) 
-                                = self.create_upgrade_button(
-                                    "reinforced tower",
-                                    constants::TOWER_ARCHER_HEALTH * 2,
-                                    0,
-                                    0,
-                                    5
-                                ) {
-                                    upgrade_menu.upgrades_second_path.push_back(upgrade_second_0);
-                                }
-                                if let Ok(upgrade_second_1) 
-                                = self.create_upgrade_button(
-                                    "more reinforceteststststststststststst",
-                                    constants::TOWER_ARCHER_HEALTH * 3,
-                                    0,
-                                    0,
-                                    15
-                                ) {
-                                    upgrade_menu.upgrades_second_path.push_back(upgrade_second_1);
-                                }
-
-                                self.upgrade_menu_vec.push(upgrade_menu);
-                                gui_manager.create_message("upgrade started".to_string(), 256);
+        let tile_kind = match building_type {
+            BuildingType::Archer => level_manager::TileKind::ArcherBottom,
+            BuildingType::Fireball => level_manager::TileKind::FireballBottom,
+            BuildingType::Base | BuildingType::None => return Ok(()),
+        };
+        for tower_index in 0..towers.tower_vec.len() {
+            let tower = &mut towers.tower_vec[tower_index];
+            if tower.bottom_index == grid_index {
+                let upgrade_menu: Option<UpgradeMenu> 
+                = match Self::initialize_upgrade_menu(
+                    self.font,
+                    grid_index,
+                    tower_index,
+                    tower.targeting_mode
+                ) {
+                    Ok(upgrade_menu) => Some(upgrade_menu),
+                    Err(e) => None,
+                };
+                match upgrade_menu {
+                    Some(mut upgrade_menu) => {
+                        for upgrade in tower_manager::TowerManager::upgrade_path(tile_kind, UpgradePath::First) {
+                            if let Ok(upgrade_button) = self.create_upgrade_button(upgrade) {
+                                upgrade_menu.upgrades_first_path.push_back(upgrade_button);
                             }
-                            None => gui_manager.create_message(
-                                "error creating upgrade... why".to_string(),
-                                1028
-                            ),
                         }
-                    }
-                }
-            },
-            BuildingType::Fireball => {
-                for tower_index in 0..towers.tower_vec.len() {
-                    let tower = &mut towers.tower_vec[tower_index];
-                    if tower.bottom_index == grid_index {
-                        let upgrade_menu: Option<UpgradeMenu> 
-                        = match Self::initialize_upgrade_menu(grid_index, tower_index) {
-                            Ok(upgrade_menu) => Some(upgrade_menu),
-                            Err(e) => None,
-                        };
-                        match upgrade_menu {
-                            Some(mut upgrade_menu) => { 
-                                if let Ok(upgrade_first_0) = self.create_upgrade_button(
-                                    "bigger balls",
-                                    0,
-                                    constants::TOWER_FIREBALL_DAMAGE * 2,
-                                    5,
-                                    5
-                                ) {
-                                    upgrade_menu.upgrades_first_path.push_back(upgrade_first_0);
-                                }
-                                if let Ok(upgrade_first_1) = self.create_upgrade_button(
-                                    "extra hot balls",
-                                    0,
-                                    constants::TOWER_FIREBALL_DAMAGE * 3,
-                                    0,
-                                    1
-                                ) {
-                                    upgrade_menu.upgrades_first_path.push_back(upgrade_first_1);
-                                }
-                                if let Ok(upgrade_second_0) = self.create_upgrade_button(
-                                    "reinforced tower",
-                                    constants::TOWER_FIREBALL_HEALTH * 2,
-                                    0,
-                                    0,
-                                    5
-                                ) {
-                                    upgrade_menu.upgrades_second_path.push_back(upgrade_second_0);
-                                }
-                                if let Ok(upgrade_second_1) = self.create_upgrade_button(
-                                    "m",
-                                    constants::TOWER_FIREBALL_HEALTH * 3,
-                                    0,
-                                    0,
-                                    15
-                                ) {
-                                    upgrade_menu.upgrades_second_path.push_back(upgrade_second_1);
-                                }
-
-                                self.upgrade_menu_vec.push(upgrade_menu);
-                                gui_manager.create_message("upgrade started".to_string(), 256);
+                        for upgrade in tower_manager::TowerManager::upgrade_path(tile_kind, UpgradePath::Second) {
+                            if let Ok(upgrade_button) = self.create_upgrade_button(upgrade) {
+                                upgrade_menu.upgrades_second_path.push_back(upgrade_button);
                             }
-                            None => gui_manager.create_message(
-                                "error creating upgrade... why".to_string(),
-                                1028
-                            ),
                         }
+
+                        self.upgrade_menu_vec.push(upgrade_menu);
+                        game.create_message("upgrade started".to_string(), 256);
                     }
+                    None => game.create_message(
+                        "error creating upgrade... why".to_string(),
+                        1028
+                    ),
                 }
+            }
+        }
+        Ok(())
+    }
+    pub fn restore_upgrade_menu(&mut self,
+        game: &mut game_manager::GameManager,
+        towers: &mut tower_manager::TowerManager,
+        temp_tile: &mut level_manager::LevelTile,
+        grid_index: (usize, usize),
+        menu_active: bool,
+        first_path_purchased: u8,
+        second_path_purchased: u8)
+    -> Result<(), String> {
+        let building_type = match temp_tile.tile_type {
+            level_manager::TileKind::ArcherBottom => BuildingType::Archer,
+            level_manager::TileKind::FireballBottom => BuildingType::Fireball,
+            _ => BuildingType::None,
+        };
+        let menu_amount = self.upgrade_menu_vec.len();
+        self.create_upgrades(game, towers, building_type, grid_index)?;
 
-            },
-            BuildingType::Base => {},
-            BuildingType::None => {},
+        //purchased upgrades are already applied to the saved tower stats
+        if self.upgrade_menu_vec.len() > menu_amount {
+            if let Some(upgrade_menu) = self.upgrade_menu_vec.last_mut() {
+                for _ in 0..first_path_purchased {
+                    upgrade_menu.upgrades_first_path.pop_front();
+                }
+                for _ in 0..second_path_purchased {
+                    upgrade_menu.upgrades_second_path.pop_front();
+                }
+                upgrade_menu.menu_active = menu_active;
+                upgrade_menu.first_path_purchased = first_path_purchased;
+                upgrade_menu.second_path_purchased = second_path_purchased;
+            }
         }
         Ok(())
     }
-    fn initialize_upgrade_menu(grid_index: (usize, usize), tower_index: usize) 
+    fn initialize_upgrade_menu(
+        font: &'a sdl2::ttf::Font<'a, 'a>,
+        grid_index: (usize, usize),
+        tower_index: usize,
+        targeting_mode: tower_manager::TargetingMode)
     -> Result<UpgradeMenu<'a>, String> {
         let upgrade_menu = UpgradeMenu {
             background_rect: sdl2::rect::Rect::new(
@@ -251,26 +205,30 @@ impl<'a> UpgradeManager <'a> {
             upgrades_second_path: std::collections::LinkedList::new(),
             current_first_path: None,
             current_second_path: None,
+            targeting_button: TargetingButton {
+                texture_surface: Self::create_targeting_surface(font, targeting_mode)?,
+                targeting_rect: sdl2::rect::Rect::new(0, 0, 0, 0),
+                targeting_mode,
+                last_clicked: 0,
+            },
             menu_active: true,
             grid_index,
             building_index: tower_index,
+            first_path_purchased: 0,
+            second_path_purchased: 0,
         };
 
         Ok(upgrade_menu)
     }
 
     fn create_upgrade_button(&mut self, 
-        text: &str, 
-        health: u16, 
-        damage: u8, 
-        radius: i32, 
-        cost: u32) 
+        upgrade: tower_manager::TowerUpgrade) 
     -> Result<Upgrade<'a>, String> {
         let texture_surface = self.font.render(
             &format!(
             "{} {}",
-            cost,
-            text
+            upgrade.cost,
+            upgrade.name
         ).to_string()
         )
             .blended(constants::COLOR_BACKGROUND)
@@ -279,20 +237,42 @@ impl<'a> UpgradeManager <'a> {
             texture_surface,
             upgrade_rect: sdl2::rect::Rect::new(0, 0, 0, 0),
             last_clicked: 0,
-            cost,
-            damage,
-            health,
-            radius,
+            upgrade,
         };
         Ok(upgrade)
     }
+    fn create_targeting_surface(
+        font: &'a sdl2::ttf::Font<'a, 'a>,
+        targeting_mode: tower_manager::TargetingMode)
+    -> Result<sdl2::surface::Surface<'a>, String> {
+        font.render(&format!("target {}", targeting_mode.name()))
+            .blended(constants::COLOR_BACKGROUND)
+            .map_err(|e| e.to_string())
+    }
     pub fn update_upgrade_menus(&mut self, 
         game: &mut game_manager::GameManager, 
         events: &mut event_manager::EventManager, 
-        towers: &mut tower_manager::TowerManager) {
+        towers: &tower_manager::TowerManager,
+        replay_manager: &mut replay_manager::ReplayManager) {
         if !game.build_mode && !game.seed_mode {
+            let font = self.font;
             for upgrade_index in  0..self.upgrade_menu_vec.len() {
                 let upgrade = &mut self.upgrade_menu_vec[upgrade_index];
+                //the tower knows what was actually bought, a queued upgrade without the gold leaves its button up
+                if let Some(tower) = towers.tower_vec.iter().find(|tower| tower.bottom_index == upgrade.grid_index) {
+                    while upgrade.first_path_purchased < tower.first_path_purchased {
+                        if upgrade.current_first_path.take().is_none() {
+                            upgrade.upgrades_first_path.pop_front();
+                        }
+                        upgrade.first_path_purchased += 1;
+                    }
+                    while upgrade.second_path_purchased < tower.second_path_purchased {
+                        if upgrade.current_second_path.take().is_none() {
+                            upgrade.upgrades_second_path.pop_front();
+                        }
+                        upgrade.second_path_purchased += 1;
+                    }
+                }
                 if upgrade.current_first_path.is_none() {
                     if let Some(current_first) 
                     = upgrade.upgrades_first_path.pop_front() {
@@ -310,16 +290,15 @@ impl<'a> UpgradeManager <'a> {
                         if current_first.upgrade_rect.contains_point(game.mouse_point) 
                         && game.mouse_button == sdl2::mouse::MouseButton::Left 
                         && current_first.last_clicked > 64 
-                        && game.gold_amount >= current_first.cost as u32 {
-                            Self::upgrade_current_upgrade(
-                                game,
-                                towers,
-                                current_first,
-                                upgrade_index
+                        && game.gold_amount >= current_first.upgrade.cost {
+                            Self::queue_upgrade(
+                                replay_manager,
+                                upgrade.grid_index,
+                                UpgradePath::First
                             );
                             upgrade.background_rect.set_width(0);
                             /*                             println!("PREV DAMAGE: {}, PREV RADIUS: {}, UPGRADE DAMAGE: {}, UPGRADE_RADIUS: {}", towers.tower_vec[upgrade_index].projectile_damage, towers.tower_vec[upgrade.building_index].attack_radius, current_first.damage, current_first.radius);  */
-                            upgrade.current_first_path = None;
+                            current_first.last_clicked = 0;
                         }
                         else {
                             current_first.last_clicked += 1;
@@ -330,23 +309,42 @@ impl<'a> UpgradeManager <'a> {
                         if current_second.upgrade_rect.contains_point(game.mouse_point) 
                         && game.mouse_button == sdl2::mouse::MouseButton::Left 
                         && current_second.last_clicked > 64 
-                        && game.gold_amount >= current_second.cost as u32 {
-                            Self::upgrade_current_upgrade(
-                                game,
-                                towers,
-                                current_second,
-                                upgrade_index
+                        && game.gold_amount >= current_second.upgrade.cost {
+                            Self::queue_upgrade(
+                                replay_manager,
+                                upgrade.grid_index,
+                                UpgradePath::Second
                             );
                             upgrade.background_rect.set_width(0);
                             /*                             println!("PREV HEALTH: {}, PREV RADIUS: {}, UPGRADE HEALTH: {}, UPGRADE_RADIUS: {}", towers.tower_vec[upgrade_index].health, towers.tower_vec[upgrade.building_index].attack_radius, current_second.damage, current_second.radius);  */
-                            upgrade.current_second_path = None;
+                            current_second.last_clicked = 0;
                         }
                         else {
                             current_second.last_clicked += 1;
                         }
                     }
+                    let targeting = &mut upgrade.targeting_button;
+                    if targeting.targeting_rect.contains_point(game.mouse_point) 
+                    && game.mouse_button == sdl2::mouse::MouseButton::Left 
+                    && targeting.last_clicked > 64 {
+                        //switched on the next simulation step so replays switch on the same step
+                        targeting.targeting_mode = targeting.targeting_mode.next();
+                        replay_manager.queue_action(replay_manager::ReplayAction::Target {
+                            index: upgrade.grid_index,
+                            targeting_mode: targeting.targeting_mode,
+                        });
+                        match Self::create_targeting_surface(font, targeting.targeting_mode) {
+                            Ok(texture_surface) => targeting.texture_surface = texture_surface,
+                            Err(err) => eprintln!("Failed to render targeting mode:\t{}", err),
+                        }
+                        targeting.last_clicked = 0;
+                    }
+                    else {
+                        targeting.last_clicked += 1;
+                    }
                 }
                 else {
+                    upgrade.targeting_button.last_clicked = 0;
                     if let Some(current_first) = &mut upgrade.current_first_path {
                         current_first.last_clicked = 0;
                     }
@@ -360,30 +358,19 @@ impl<'a> UpgradeManager <'a> {
 
         }
     }
-    fn upgrade_current_upgrade(
-        game: &mut game_manager::GameManager, 
-        towers: &mut tower_manager::TowerManager, 
-        current: &mut Upgrade<'a>, 
-        upgrade_index: usize) {
-        if current.upgrade_rect.contains_point(game.mouse_point) 
-        && game.mouse_button == sdl2::mouse::MouseButton::Left 
-        && current.last_clicked > 32 
-        && game.gold_amount >= current.cost as u32 {
-            game.gold_amount -= current.cost as u32;
-            towers.tower_vec[upgrade_index].health += current.health;
-            towers.tower_vec[upgrade_index].max_health += current.health;
-            towers.tower_vec[upgrade_index].projectile_damage += current.damage;
-            towers.tower_vec[upgrade_index].attack_radius += current.radius;
-            println!("NEXT DAMAGE: {}", towers.tower_vec[upgrade_index].projectile_damage);
-        }
-        else {
-            current.last_clicked += 1;
-        }
-
+    //bought on the next simulation step so replays buy it on the same step
+    fn queue_upgrade(
+        replay_manager: &mut replay_manager::ReplayManager, 
+        grid_index: (usize, usize),
+        path: UpgradePath) {
+        replay_manager.queue_action(replay_manager::ReplayAction::Upgrade {
+            index: grid_index,
+            path,
+        });
     }
 
 
-    pub fn render_upgrade_menus(&mut self, game: &mut game_manager::GameManager) {
+    pub fn render_upgrade_menus(&mut self, game: &mut game_manager::GameManager, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>) -> Result<(), String> {
         if !game.build_mode && !game.seed_mode {
             for upgrade in &mut self.upgrade_menu_vec {
                 if upgrade.menu_active {
@@ -394,8 +381,8 @@ impl<'a> UpgradeManager <'a> {
                         upgrade.grid_index.1 as i32 * constants::TILE_SIZE as i32
                     ) - game.cam_y);
 
-                    game.canvas.set_draw_color(sdl2::pixels::Color::WHITE);
-                    game.canvas.fill_rect(upgrade.background_rect);
+                    canvas.set_draw_color(sdl2::pixels::Color::WHITE);
+                    canvas.fill_rect(upgrade.background_rect);
 
                     if let Some(upgrades) = &mut upgrade.current_first_path {
                         upgrades.upgrade_rect.set_x(
@@ -425,15 +412,15 @@ impl<'a> UpgradeManager <'a> {
                         };
 
                         if let Err(err) 
-                        = game.canvas.copy(&texture, None, upgrades.upgrade_rect) {
+                        = canvas.copy(&texture, None, upgrades.upgrade_rect) {
                             eprintln!("Failed to copy texture to canvas:\t{}", err);
                         }
                     }
                     else {
                         println!("first empty");
 
-                        game.canvas.set_draw_color(sdl2::pixels::Color::RED);
-                        game.canvas.fill_rect(upgrade.background_rect);
+                        canvas.set_draw_color(sdl2::pixels::Color::RED);
+                        canvas.fill_rect(upgrade.background_rect);
                         //~!~!~GET RID OF UNWRAP~!~!~
                         // let texture_surface = self.font.render("empty")
                         //     .blended(constants::COLOR_BACKGROUND)
@@ -471,12 +458,45 @@ impl<'a> UpgradeManager <'a> {
                             }
                         };
 
-                        if let Err(err) = game.canvas.copy(&texture, None, upgrades.upgrade_rect) {
+                        if let Err(err) = canvas.copy(&texture, None, upgrades.upgrade_rect) {
                             eprintln!("Failed to copy texture to canvas:\t{}", err);
                         }
                     }
+
+                    //below the tower, the upgrade paths cover it
+                    let targeting = &mut upgrade.targeting_button;
+                    targeting.targeting_rect.set_x(
+                        upgrade.grid_index.0 as i32 
+                        * constants::TILE_SIZE as i32 
+                        - game.cam_x
+                    ); 
+                    targeting.targeting_rect.set_y(
+                        (upgrade.grid_index.1 as i32 + 1) 
+                        * constants::TILE_SIZE as i32 
+                        - game.cam_y
+                    );
+                    targeting.targeting_rect.set_width(targeting.texture_surface.width());
+                    targeting.targeting_rect.set_height(targeting.texture_surface.height());
+                    canvas.set_draw_color(sdl2::pixels::Color::WHITE);
+                    canvas.fill_rect(targeting.targeting_rect)?;
+
+                    let texture_result 
+                    = self.texture_creator.create_texture_from_surface(&targeting.texture_surface);
+
+                    let texture = match texture_result {
+                        Ok(texture) => texture,
+                        Err(err) => {
+                            eprintln!("Failed to create texture from surface:\t{}", err);
+                            continue; 
+                        }
+                    };
+
+                    if let Err(err) = canvas.copy(&texture, None, targeting.targeting_rect) {
+                        eprintln!("Failed to copy texture to canvas:\t{}", err);
+                    }
                 }
             }
         }
+        Ok(())
     }
 }
@@ -2,7 +2,6 @@ use crate::constants;
 use crate::event_manager;
 use crate::level_manager;
 use crate::game_manager;
-use crate::player_manager;
 use crate::enemy_manager;
 
 //this file should be utils.rs
@@ -24,7 +23,7 @@ pub fn clamp_speed(speed: u16, max_speed: u16) -> u16 {
 
 fn tile_collidable(tile: &level_manager::LevelTile) -> bool {
     match tile.tile_type {
-        constants::TILE_TYPE_WALL => {
+        level_manager::TileKind::Wall => {
             true
         },
         _ => false,
@@ -35,8 +34,28 @@ pub fn tile_pathable(tile: &level_manager::LevelTile) -> bool {
     return !tile.is_occupied && !tile_collidable(tile);
 }
 
+pub fn tile_cost(tile: &level_manager::LevelTile) -> u32 {
+    match tile.tile_type {
+        level_manager::TileKind::Floor => constants::TILE_COST_FLOOR,
+        level_manager::TileKind::FieldEmpty
+        | level_manager::TileKind::FieldGrowing
+        | level_manager::TileKind::FieldHarvestable => constants::TILE_COST_FIELD,
+        level_manager::TileKind::Mud => constants::TILE_COST_MUD,
+        level_manager::TileKind::Water => constants::TILE_COST_WATER,
+        _ => constants::TILE_COST_GRASS,
+    }
+}
+
+//blends the last two simulation positions, alpha is how far into the next step the frame is
+pub fn interpolate(previous: (i32, i32), current: (i32, i32), alpha: f64) -> (i32, i32) {
+    let alpha = alpha.clamp(0.0, 1.0);
+    (
+        previous.0 + ((current.0 - previous.0) as f64 * alpha).round() as i32,
+        previous.1 + ((current.1 - previous.1) as f64 * alpha).round() as i32
+    )
+}
+
 pub fn check_player_collisions(
-    player: &mut player_manager::PlayerManager,
     game: &mut game_manager::GameManager,
     events: &mut event_manager::EventManager,
     new_position: (i32, i32),
@@ -54,8 +73,8 @@ pub fn check_player_collisions(
 
     //allows for rect collisions but might be slower
     let new_rect = sdl2::rect::Rect::new(
-        centered_new_x - player.x + new_offset,
-        centered_new_y - player.y + new_offset,
+        centered_new_x - game.cam_x + new_offset,
+        centered_new_y - game.cam_y + new_offset,
         tile_size_offset as u32,
         tile_size_offset as u32
     );
@@ -111,22 +130,22 @@ pub fn check_enemy_collisions (
 }
 
 pub fn draw_rect_outline(
-    game: &mut game_manager::GameManager,
+    canvas: &mut sdl2::render::Canvas<sdl2::video::Window>,
     rect: sdl2::rect::Rect
 ) {
-    game.canvas.set_draw_color(constants::COLOR_OUTLINE);
-    game.canvas.draw_line(
+    canvas.set_draw_color(constants::COLOR_OUTLINE);
+    canvas.draw_line(
         rect.top_left(),
         rect.top_right()
     ).unwrap();
-    game.canvas.draw_line(
+    canvas.draw_line(
         rect.bottom_left(),
         rect.bottom_right()
     ).unwrap();
-    game.canvas.draw_line(
+    canvas.draw_line(
         rect.top_left(
         ), rect.bottom_left()).unwrap();
-    game.canvas.draw_line(
+    canvas.draw_line(
         rect.top_right(),
         rect.bottom_right()
     ).unwrap();
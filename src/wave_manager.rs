@@ -0,0 +1,390 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::constants;
+use crate::game_manager;
+use crate::level_manager;
+use crate::building_manager;
+use crate::enemy_manager;
+use crate::enemy_manager::EnemyType;
+
+//wave script format, one entry per line, '#' starts a comment:
+//  wave <build time seconds> [health multiplier]
+//  <enemy> <count> <spawn point index | *> <spacing seconds> <delay seconds>
+//enemy lines belong to the wave above them, '*' cycles through every spawn point
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum WaveState {
+    Build,
+    Spawning,
+    Active,
+}
+
+#[derive(Clone)]
+pub struct SpawnGroup {
+    pub enemy_type: EnemyType,
+    pub count: u16,
+    pub spawn_point: Option<usize>,
+    pub spacing: f64,
+    pub delay: f64,
+    pub line: usize,
+}
+
+#[derive(Clone)]
+pub struct WaveDefinition {
+    pub build_time: f64,
+    pub health_multiplier: f64,
+    pub groups: Vec<SpawnGroup>,
+}
+
+pub struct PendingSpawn {
+    pub time: f64,
+    pub enemy_type: EnemyType,
+    pub spawn_point: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct WaveParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for WaveParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+pub struct WaveManager {
+    pub spawn_points: Vec<(usize, usize)>,
+    pub wave_script: Vec<WaveDefinition>,
+    pub wave_state: WaveState,
+    pub current_wave: u32,
+    pub wave_timer: f64,
+    pub wave_elapsed: f64,
+    pub pending_spawns: Vec<PendingSpawn>,
+    pub spawn_index: usize,
+}
+
+impl WaveManager {
+    pub fn new() -> WaveManager {
+        let wave_manager = WaveManager {
+            spawn_points: Vec::new(),
+            wave_script: Vec::new(),
+            wave_state: WaveState::Build,
+            current_wave: 0,
+            wave_timer: constants::WAVE_BUILD_TIME,
+            wave_elapsed: 0.0,
+            pending_spawns: Vec::new(),
+            spawn_index: 0,
+        };
+        wave_manager
+    }
+
+    pub fn find_spawn_points(&mut self, level: &level_manager::LevelManager) {
+        self.spawn_points.clear();
+        for col_index in 0..level.level_vec.len() {
+            for row_index in 0..level.level_vec[col_index].len() {
+                if level.level_vec[col_index][row_index].original_type == level_manager::TileKind::Spawn {
+                    self.spawn_points.push((col_index, row_index));
+                }
+            }
+        }
+    }
+
+    //reads the wave script next to the level file, ex: farm.txt -> farm.waves
+    pub fn load_wave_script(
+        &mut self,
+        level_path: &str,
+        game: &mut game_manager::GameManager,
+    ) {
+        let script_path = std::path::Path::new(level_path).with_extension(constants::WAVE_SCRIPT_EXTENSION);
+        self.wave_script.clear();
+        self.wave_state = WaveState::Build;
+        self.current_wave = 0;
+        self.pending_spawns.clear();
+        //* groups take turns from here, replays need the same turns
+        self.spawn_index = 0;
+
+        match Self::read_wave_script(&script_path, self.spawn_points.len()) {
+            Ok(wave_script) => {
+                println!("LOADED {} WAVES FROM {:?}", wave_script.len(), script_path);
+                self.wave_script = wave_script;
+            }
+            Err(error) => {
+                eprintln!("Failed to load wave script {:?}: {}", script_path, error);
+                eprintln!("Using default waves");
+                if script_path.exists() {
+                    game.create_message(format!("wave script {}", error), 512);
+                }
+            }
+        }
+        self.wave_timer = self.wave_definition(1).build_time;
+    }
+
+    pub fn read_wave_script(
+        script_path: &std::path::Path,
+        spawn_point_amount: usize,
+    ) -> Result<Vec<WaveDefinition>, WaveParseError> {
+        let file = File::open(script_path).map_err(|e| WaveParseError {
+            line: 0,
+            message: e.to_string(),
+        })?;
+        let mut lines = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| WaveParseError {
+                line: lines.len() + 1,
+                message: e.to_string(),
+            })?;
+            lines.push(line);
+        }
+        let wave_script = Self::parse_wave_script(&lines.join("\n"))?;
+
+        for wave in &wave_script {
+            for group in &wave.groups {
+                if let Some(spawn_point) = group.spawn_point {
+                    if spawn_point >= spawn_point_amount {
+                        return Err(WaveParseError {
+                            line: group.line,
+                            message: format!(
+                                "spawn point {} does not exist, level has {}",
+                                spawn_point,
+                                spawn_point_amount
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(wave_script)
+    }
+
+    pub fn parse_wave_script(script: &str) -> Result<Vec<WaveDefinition>, WaveParseError> {
+        let mut wave_script: Vec<WaveDefinition> = Vec::new();
+
+        for (line_index, line) in script.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() {
+                continue;
+            }
+            let error = |message: String| WaveParseError {
+                line: line_number,
+                message,
+            };
+
+            if words[0] == "wave" {
+                if words.len() < 2 || words.len() > 3 {
+                    return Err(error("expected: wave <build time> [health multiplier]".to_string()));
+                }
+                let build_time = Self::parse_seconds(words[1], "build time").map_err(error)?;
+                let health_multiplier = match words.get(2) {
+                    Some(word) => Self::parse_seconds(word, "health multiplier").map_err(error)?,
+                    None => 1.0,
+                };
+                wave_script.push(WaveDefinition {
+                    build_time,
+                    health_multiplier,
+                    groups: Vec::new(),
+                });
+                continue;
+            }
+
+            let enemy_type = match EnemyType::from_name(words[0]) {
+                Some(enemy_type) => enemy_type,
+                None => return Err(error(format!("unknown enemy type '{}'", words[0]))),
+            };
+            if words.len() != 5 {
+                return Err(error("expected: <enemy> <count> <spawn point> <spacing> <delay>".to_string()));
+            }
+            let count: u16 = words[1].parse().map_err(|_| {
+                error(format!("invalid enemy count '{}'", words[1]))
+            })?;
+            let spawn_point = match words[2] {
+                "*" => None,
+                word => Some(word.parse::<usize>().map_err(|_| {
+                    error(format!("invalid spawn point '{}'", word))
+                })?),
+            };
+            let spacing = Self::parse_seconds(words[3], "spacing").map_err(error)?;
+            let delay = Self::parse_seconds(words[4], "delay").map_err(error)?;
+
+            match wave_script.last_mut() {
+                Some(wave) => wave.groups.push(SpawnGroup {
+                    enemy_type,
+                    count,
+                    spawn_point,
+                    spacing,
+                    delay,
+                    line: line_number,
+                }),
+                None => return Err(error("enemy listed before the first wave".to_string())),
+            }
+        }
+
+        if wave_script.is_empty() {
+            return Err(WaveParseError {
+                line: 0,
+                message: "script has no waves".to_string(),
+            });
+        }
+        Ok(wave_script)
+    }
+
+    fn parse_seconds(word: &str, name: &str) -> Result<f64, String> {
+        match word.parse::<f64>() {
+            Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+            _ => Err(format!("invalid {} '{}'", name, word)),
+        }
+    }
+
+    pub fn wave_enemy_amount(wave: u32) -> u16 {
+        constants::WAVE_BASE_ENEMY_AMOUNT
+            + constants::WAVE_ENEMY_GROWTH * wave.saturating_sub(1) as u16
+    }
+
+    pub fn wave_spawn_interval(wave: u32) -> f64 {
+        (constants::WAVE_SPAWN_INTERVAL
+            * constants::WAVE_SPAWN_INTERVAL_DECAY.powi(wave.saturating_sub(1) as i32))
+            .max(constants::WAVE_MIN_SPAWN_INTERVAL)
+    }
+
+    pub fn wave_health_multiplier(wave: u32) -> f64 {
+        1.0 + constants::WAVE_HEALTH_GROWTH * wave.saturating_sub(1) as f64
+    }
+
+    //built in waves, used past the end of the script or when there is no script
+    pub fn default_wave(wave: u32) -> WaveDefinition {
+        let enemy_amount = Self::wave_enemy_amount(wave);
+        let spawn_interval = Self::wave_spawn_interval(wave);
+        let mut groups = vec![SpawnGroup {
+            enemy_type: EnemyType::Goblin,
+            count: enemy_amount,
+            spawn_point: None,
+            spacing: spawn_interval,
+            delay: 0.0,
+            line: 0,
+        }];
+        //a boss closes out every few waves
+        if wave.is_multiple_of(constants::WAVE_BOSS_INTERVAL) {
+            groups.push(SpawnGroup {
+                enemy_type: EnemyType::Ogre,
+                count: wave as u16 / constants::WAVE_BOSS_INTERVAL as u16,
+                spawn_point: None,
+                spacing: spawn_interval,
+                delay: enemy_amount as f64 * spawn_interval,
+                line: 0,
+            });
+        }
+        WaveDefinition {
+            build_time: constants::WAVE_BUILD_TIME,
+            health_multiplier: Self::wave_health_multiplier(wave),
+            groups,
+        }
+    }
+
+    fn wave_definition(&self, wave: u32) -> WaveDefinition {
+        match self.wave_script.get(wave.saturating_sub(1) as usize) {
+            Some(definition) => definition.clone(),
+            None => Self::default_wave(wave),
+        }
+    }
+
+    pub fn update_waves(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &mut level_manager::LevelManager,
+        buildings: &mut building_manager::BuildingManager,
+        enemies: &mut enemy_manager::EnemyManager,
+    ) {
+        if self.spawn_points.is_empty() {
+            return
+        }
+        match self.wave_state {
+            WaveState::Build => {
+                //waves only start counting down once there is something to defend
+                if !buildings.base_created {
+                    if self.current_wave == 0 {
+                        game.create_unique_message("build a base to start the waves".to_string(), 128);
+                    }
+                    return
+                }
+                self.wave_timer -= game.delta_time.max(constants::MIN_GAME_RATE);
+                if self.wave_timer <= 0.0 {
+                    self.start_wave(game);
+                }
+            }
+            WaveState::Spawning => {
+                self.wave_elapsed += game.delta_time.max(constants::MIN_GAME_RATE);
+                let health_multiplier = self.wave_definition(self.current_wave).health_multiplier;
+                while !self.pending_spawns.is_empty()
+                && self.pending_spawns[0].time <= self.wave_elapsed {
+                    let pending_spawn = self.pending_spawns.remove(0);
+                    self.spawn_enemy(game, level, enemies, &pending_spawn, health_multiplier);
+                }
+                if self.pending_spawns.is_empty() {
+                    self.wave_state = WaveState::Active;
+                }
+            }
+            WaveState::Active => {
+                if enemies.enemy_vec.is_empty() {
+                    self.wave_state = WaveState::Build;
+                    self.wave_timer = self.wave_definition(self.current_wave + 1).build_time;
+                    game.create_message(format!("wave {} cleared", self.current_wave), 256);
+                }
+            }
+        }
+    }
+
+    fn start_wave(&mut self, game: &mut game_manager::GameManager) {
+        self.current_wave += 1;
+        self.wave_elapsed = 0.0;
+        self.pending_spawns.clear();
+
+        let definition = self.wave_definition(self.current_wave);
+        for group in &definition.groups {
+            for spawn_index in 0..group.count {
+                self.pending_spawns.push(PendingSpawn {
+                    time: group.delay + group.spacing * spawn_index as f64,
+                    enemy_type: group.enemy_type,
+                    spawn_point: group.spawn_point,
+                });
+            }
+        }
+        self.pending_spawns.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        self.wave_state = WaveState::Spawning;
+        game.create_message(format!("wave {} incoming", self.current_wave), 256);
+    }
+
+    fn spawn_enemy(
+        &mut self,
+        game: &mut game_manager::GameManager,
+        level: &mut level_manager::LevelManager,
+        enemies: &mut enemy_manager::EnemyManager,
+        pending_spawn: &PendingSpawn,
+        health_multiplier: f64,
+    ) {
+        let spawn_point = match pending_spawn.spawn_point {
+            Some(spawn_point) => self.spawn_points[spawn_point % self.spawn_points.len()],
+            None => {
+                self.spawn_index += 1;
+                self.spawn_points[(self.spawn_index - 1) % self.spawn_points.len()]
+            }
+        };
+
+        let temp_tile = &level.level_vec[spawn_point.0][spawn_point.1];
+        enemies.place_enemy(game, temp_tile, pending_spawn.enemy_type, spawn_point);
+
+        if let Some(enemy) = enemies.enemy_vec.last_mut() {
+            let health = (enemy.max_health as f64 * health_multiplier)
+                .clamp(1.0, u16::MAX as f64) as u16;
+            enemy.max_health = health;
+            enemy.health = health;
+        }
+    }
+}
@@ -1,132 +1,524 @@
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_pathfinding() {
-        // Set up the necessary data for testing
-        let mut enemy = Enemy {
-            grid_index: (0, 0),  // Set the initial position of the enemy
-            final_path: None,   // Initialize the final path as None
-            // Initialize other enemy properties as needed
-        };
-
-        let target = (3, 3);  // Set the target coordinates
-
-        let level_vec = vec![
-            // Initialize the level vector with appropriate LevelTile values
-            // ...
-        ];
-
-        // Perform the pathfinding
-        astar(&mut enemy, target, &level_vec);
-
-        // Assert the expected outcome or behavior
-        assert!(enemy.final_path.is_some());
-        // Add more assertions as needed
-    }}
-
-pub fn astar(enemy: &mut Enemy, target: (usize, usize), level_vec: &[Vec<LevelTile>]) {
-    let initial_state = PathState {
-        position: enemy.grid_index,
-        priority: heuristic(enemy.grid_index, target),
-    };
-
-    let mut frontier: std::collections::BinaryHeap<PathState> = [initial_state].into();
-    let mut priorities: std::collections::HashMap<(usize, usize), usize> = std::collections::HashMap::new();
-    let mut came_from: std::collections::HashMap<(usize, usize), (usize, usize)> = std::collections::HashMap::new();
+use farm_defense::{
+    constants,
+    level_manager,
+    pathfinding_manager,
+    utilities,
+};
+use level_manager::{LevelTile, TileKind};
+use pathfinding_manager::PathfindingManager;
 
-    priorities.insert(enemy.grid_index, initial_state.priority);
+//rows of the picture are rows of the level, level_vec itself is [col][row]
+//  . grass  # wall  o occupied grass  , floor  f field  m mud  w water
+//  s start  t target, both on grass
+struct Grid {
+    level_vec: Vec<Vec<LevelTile>>,
+    start: (usize, usize),
+    target: (usize, usize),
+}
 
-    while let Some(current_state) = frontier.pop() {
-        let current = current_state.position;
+fn grid(picture: &[&str]) -> Grid {
+    let rect = sdl2::rect::Rect::new(0, 0, constants::TILE_SIZE, constants::TILE_SIZE);
+    let width = picture[0].len();
+    let mut level_vec: Vec<Vec<LevelTile>> = (0..width).map(|_| Vec::new()).collect();
+    let mut start = (0, 0);
+    let mut target = (0, 0);
 
-        if current == target {
-            let mut path = vec![current];
-            let mut current = current;
-            while let Some(&prev) = came_from.get(&current) {
-                path.push(prev);
-                current = prev;
+    for (row, line) in picture.iter().enumerate() {
+        assert_eq!(line.len(), width, "row {} is a different width", row);
+        for (col, ch) in line.chars().enumerate() {
+            let tile_type = match ch {
+                '#' => TileKind::Wall,
+                ',' => TileKind::Floor,
+                'f' => TileKind::FieldEmpty,
+                'm' => TileKind::Mud,
+                'w' => TileKind::Water,
+                _ => TileKind::Grass,
+            };
+            let mut tile = LevelTile::new(tile_type, rect);
+            tile.is_occupied = ch == 'o';
+            match ch {
+                's' => start = (col, row),
+                't' => target = (col, row),
+                _ => {}
             }
-            path.reverse();
-            enemy.final_path = Some(path);
+            level_vec[col].push(tile);
         }
+    }
+    Grid {
+        level_vec,
+        start,
+        target,
+    }
+}
 
-        let neighbors = get_neighbors(current, level_vec);
+fn find_path(grid: &Grid) -> Option<Vec<(usize, usize)>> {
+    PathfindingManager::find_path(grid.start, grid.target, false, &grid.level_vec)
+}
+
+fn cost(grid: &Grid, path: &[(usize, usize)]) -> u32 {
+    PathfindingManager::path_cost(path, false, &grid.level_vec)
+}
+
+//every step is onto a neighbor that can be walked on, without cutting a blocked corner
+fn assert_walkable(grid: &Grid, path: &[(usize, usize)]) {
+    assert_eq!(path.first(), Some(&grid.start));
+    assert_eq!(path.last(), Some(&grid.target));
+    for step in path.windows(2) {
+        let (from, to) = (step[0], step[1]);
+        assert!(from.0.abs_diff(to.0) <= 1 && from.1.abs_diff(to.1) <= 1 && from != to, "{:?} to {:?} is not a step", from, to);
+        assert!(utilities::tile_pathable(&grid.level_vec[to.0][to.1]), "stepped onto {:?}", to);
+        if from.0 != to.0 && from.1 != to.1 {
+            assert!(utilities::tile_pathable(&grid.level_vec[to.0][from.1]), "cut the corner at {:?}", (to.0, from.1));
+            assert!(utilities::tile_pathable(&grid.level_vec[from.0][to.1]), "cut the corner at {:?}", (from.0, to.1));
+        }
+    }
+}
 
-        for next in neighbors {
-            let new_cost = 1;
-            let priority = new_cost + heuristic(next, target);
+//neighbors the same way the search sees them, written out again on purpose
+fn neighbors(level_vec: &[Vec<LevelTile>], tile: (usize, usize)) -> Vec<(usize, usize)> {
+    let is_open = |col: isize, row: isize| {
+        col >= 0 && row >= 0
+        && (col as usize) < level_vec.len() && (row as usize) < level_vec[0].len()
+        && utilities::tile_pathable(&level_vec[col as usize][row as usize])
+    };
+    let mut neighbors = Vec::new();
+    for dx in -1..=1isize {
+        for dy in -1..=1isize {
+            let (col, row) = (tile.0 as isize + dx, tile.1 as isize + dy);
+            if (dx, dy) == (0, 0) || !is_open(col, row) {
+                continue;
+            }
+            if dx != 0 && dy != 0 && (!is_open(col, tile.1 as isize) || !is_open(tile.0 as isize, row)) {
+                continue;
+            }
+            neighbors.push((col as usize, row as usize));
+        }
+    }
+    neighbors
+}
 
-            if !priorities.contains_key(&next) || priority < priorities[&next] {
-                priorities.insert(next, priority);
-                frontier.push(PathState {
-                    position: next,
-                    priority,
-                });
-                came_from.insert(next, current);
+//fewest steps from start to every tile, none where it can't be reached
+fn bfs_steps(level_vec: &[Vec<LevelTile>], start: (usize, usize)) -> Vec<Vec<Option<usize>>> {
+    let mut steps: Vec<Vec<Option<usize>>> = level_vec.iter().map(|col| vec![None; col.len()]).collect();
+    let mut queue = std::collections::VecDeque::from([start]);
+    steps[start.0][start.1] = Some(0);
+    while let Some(current) = queue.pop_front() {
+        let current_steps = steps[current.0][current.1].unwrap();
+        for next in neighbors(level_vec, current) {
+            if steps[next.0][next.1].is_none() {
+                steps[next.0][next.1] = Some(current_steps + 1);
+                queue.push_back(next);
             }
         }
     }
+    steps
+}
 
-    fn heuristic(position: (usize, usize), goal: (usize, usize)) -> usize {
-        let (x1, y1) = position;
-        let (x2, y2) = goal;
+//cheapest cost from start to every tile, a tile goes back in the queue whenever it gets cheaper
+fn bfs_costs(level_vec: &[Vec<LevelTile>], start: (usize, usize)) -> Vec<Vec<Option<u32>>> {
+    let mut costs: Vec<Vec<Option<u32>>> = level_vec.iter().map(|col| vec![None; col.len()]).collect();
+    let mut queue = std::collections::VecDeque::from([start]);
+    costs[start.0][start.1] = Some(0);
+    while let Some(current) = queue.pop_front() {
+        let current_cost = costs[current.0][current.1].unwrap();
+        for next in neighbors(level_vec, current) {
+            let is_diagonal = next.0 != current.0 && next.1 != current.1;
+            let step_length = if is_diagonal { constants::PATH_STEP_DIAGONAL } else { constants::PATH_STEP_STRAIGHT };
+            let new_cost = current_cost + utilities::tile_cost(&level_vec[current.0][current.1]) * step_length;
+            if costs[next.0][next.1].is_none_or(|cost| new_cost < cost) {
+                costs[next.0][next.1] = Some(new_cost);
+                queue.push_back(next);
+            }
+        }
+    }
+    costs
+}
 
-        let dx = (x1 as isize - x2 as isize).abs() as usize;
-        let dy = (y1 as isize - y2 as isize).abs() as usize;
+//xorshift, the same seed always makes the same grids
+struct Random(u64);
 
-        dx + dy
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
     }
-    fn get_neighbors(position: (usize, usize), level_vec: &[Vec<LevelTile>]) -> Vec<(usize, usize)> {
-        let (x, y) = position;
-        let width = level_vec[0].len();
-        let height = level_vec.len();
-        let mut neighbors = Vec::with_capacity(8);
-        let top_tile = &level_vec[x][y - 1];
-        let bottom_tile = &level_vec[x][y + 1]; 
-        let left_tile = &level_vec[x - 1][y];
-        let right_tile = &level_vec[x + 1][y];
-        let top_left_tile = &level_vec[x - 1][y - 1];
-        let top_right_tile = &level_vec[x - 1][y + 1];
-        let bottom_left_tile = &level_vec[x + 1][y - 1];
-        let bottom_right_tile = &level_vec[x + 1][y + 1];
-
-        let tile_types_to_avoid = [
-            constants::TILE_TYPE_WALL,
-        ];
-
-        //Up
-        if y > 0 && !tile_types_to_avoid.contains(&top_tile.tile_type) && !top_tile.is_occupied {
-            neighbors.push((x, y - 1));
-        }
-        //Down
-        if y < height - 1 && !tile_types_to_avoid.contains(&bottom_tile.tile_type) && !bottom_tile.is_occupied {
-            neighbors.push((x, y + 1));
-        }
-        //Left
-        if x > 0 && !tile_types_to_avoid.contains(&left_tile.tile_type) && !left_tile.is_occupied {
-            neighbors.push((x - 1, y));
-        }
-        //Right
-        if x < width - 1 && !tile_types_to_avoid.contains(&right_tile.tile_type) && !right_tile.is_occupied {
-            neighbors.push((x + 1, y));
+
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+}
+
+fn random_grid(random: &mut Random, terrain: &[TileKind]) -> Grid {
+    let rect = sdl2::rect::Rect::new(0, 0, constants::TILE_SIZE, constants::TILE_SIZE);
+    let (width, height) = (2 + random.below(12), 2 + random.below(12));
+    let level_vec: Vec<Vec<LevelTile>> = (0..width)
+        .map(|_| (0..height)
+            .map(|_| {
+                let mut tile = LevelTile::new(TileKind::Grass, rect);
+                match random.below(10) {
+                    0..=2 => tile.tile_type = TileKind::Wall,
+                    3 => tile.is_occupied = true,
+                    4..=5 => tile.tile_type = terrain[random.below(terrain.len())],
+                    _ => {}
+                }
+                tile
+            })
+            .collect())
+        .collect();
+    let mut random_tile = || (random.below(width), random.below(height));
+    let (start, target) = (random_tile(), random_tile());
+    let mut grid = Grid {
+        level_vec,
+        start,
+        target,
+    };
+    //enemies stand on the start tile, the target has to be open to be reached at all
+    grid.level_vec[start.0][start.1] = LevelTile::new(TileKind::Grass, rect);
+    grid.level_vec[target.0][target.1] = LevelTile::new(TileKind::Grass, rect);
+    grid
+}
+
+#[test]
+fn test_straight_line_on_open_grass() {
+    let grid = grid(&[
+        "s....t",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert_eq!(path, vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]);
+    assert_eq!(cost(&grid, &path), 5 * constants::TILE_COST_GRASS * constants::PATH_STEP_STRAIGHT);
+}
+
+#[test]
+fn test_diagonal_is_taken_when_it_is_shorter() {
+    let grid = grid(&[
+        "s...",
+        "....",
+        "....",
+        "...t",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert_eq!(path.len(), 4);
+    assert_eq!(cost(&grid, &path), 3 * constants::TILE_COST_GRASS * constants::PATH_STEP_DIAGONAL);
+}
+
+#[test]
+fn test_start_is_target() {
+    let grid = grid(&[
+        "...",
+        ".s.",
+        "...",
+    ]);
+    let path = PathfindingManager::find_path(grid.start, grid.start, false, &grid.level_vec).unwrap();
+
+    assert_eq!(path, vec![grid.start]);
+}
+
+#[test]
+fn test_goes_around_walls() {
+    let grid = grid(&[
+        "......",
+        ".####.",
+        "s#..#t",
+        ".####.",
+        "......",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert!(path.iter().all(|tile| tile.1 != 2 || tile.0 == 0 || tile.0 == 5));
+    assert_eq!(cost(&grid, &path), bfs_costs(&grid.level_vec, grid.start)[grid.target.0][grid.target.1].unwrap());
+}
+
+#[test]
+fn test_does_not_cut_wall_corners() {
+    let grid = grid(&[
+        "s#",
+        "#t",
+    ]);
+
+    assert_eq!(find_path(&grid), None);
+}
+
+#[test]
+fn test_unreachable_target() {
+    let grid = grid(&[
+        "s..#...",
+        "...#.t.",
+        "...#...",
+    ]);
+
+    assert_eq!(find_path(&grid), None);
+}
+
+#[test]
+fn test_walled_in_target() {
+    let grid = grid(&[
+        "s....",
+        "..###",
+        "..#t#",
+        "..###",
+    ]);
+
+    assert_eq!(find_path(&grid), None);
+}
+
+#[test]
+fn test_target_off_the_map() {
+    let grid = grid(&[
+        "s..",
+        "...",
+    ]);
+
+    assert_eq!(PathfindingManager::find_path(grid.start, (3, 0), false, &grid.level_vec), None);
+    assert_eq!(PathfindingManager::find_path((0, 2), (1, 1), false, &grid.level_vec), None);
+}
+
+#[test]
+fn test_occupied_tiles_are_avoided() {
+    let grid = grid(&[
+        "......",
+        "s.oo.t",
+        "..oo..",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert!(path.iter().all(|tile| !grid.level_vec[tile.0][tile.1].is_occupied));
+}
+
+#[test]
+fn test_occupied_tiles_can_block_completely() {
+    let grid = grid(&[
+        "s.o..",
+        "..o.t",
+        "..o..",
+    ]);
+
+    assert_eq!(find_path(&grid), None);
+}
+
+#[test]
+fn test_flying_ignores_walls_and_occupied_tiles() {
+    let grid = grid(&[
+        "s#o#t",
+    ]);
+
+    assert_eq!(find_path(&grid), None);
+    let path = PathfindingManager::find_path(grid.start, grid.target, true, &grid.level_vec).unwrap();
+    assert_eq!(path.len(), 5);
+}
+
+#[test]
+fn test_slow_terrain_is_walked_around_when_cheaper() {
+    let grid = grid(&[
+        ".....",
+        "swwwt",
+        ".....",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert!(path.iter().all(|tile| grid.level_vec[tile.0][tile.1].tile_type != TileKind::Water));
+}
+
+#[test]
+fn test_floor_is_preferred_over_grass() {
+    let grid = grid(&[
+        ".,,,.",
+        "s...t",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert!(path.contains(&(2, 0)));
+    assert_eq!(cost(&grid, &path), bfs_costs(&grid.level_vec, grid.start)[grid.target.0][grid.target.1].unwrap());
+}
+
+#[test]
+fn test_ties_give_an_optimal_path_every_time() {
+    //every route around the pillar costs the same
+    let grid = grid(&[
+        ".....",
+        ".....",
+        "s.#.t",
+        ".....",
+        ".....",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert_eq!(cost(&grid, &path), bfs_costs(&grid.level_vec, grid.start)[grid.target.0][grid.target.1].unwrap());
+    for _ in 0..10 {
+        assert_eq!(find_path(&grid).unwrap(), path);
+    }
+}
+
+#[test]
+fn test_map_edges_and_corners() {
+    let grid = grid(&[
+        "s.......",
+        "#######.",
+        "t.......",
+    ]);
+    let path = find_path(&grid).unwrap();
+
+    assert_walkable(&grid, &path);
+    assert!(path.contains(&(7, 1)));
+    assert_eq!(path.len(), bfs_steps(&grid.level_vec, grid.start)[grid.target.0][grid.target.1].unwrap() + 1);
+
+    let column = self::grid(&[
+        "t",
+        ".",
+        ".",
+        "s",
+    ]);
+    assert_eq!(find_path(&column).unwrap(), vec![(0, 3), (0, 2), (0, 1), (0, 0)]);
+}
+
+#[test]
+fn test_matches_bfs_on_random_grass_grids() {
+    let mut random = Random(0x2545f4914f6cdd1d);
+    for _ in 0..500 {
+        let grid = random_grid(&mut random, &[TileKind::Grass]);
+        let steps = bfs_steps(&grid.level_vec, grid.start);
+        let costs = bfs_costs(&grid.level_vec, grid.start);
+
+        match find_path(&grid) {
+            Some(path) => {
+                assert_walkable(&grid, &path);
+                assert_eq!(Some(cost(&grid, &path)), costs[grid.target.0][grid.target.1]);
+                //the cheapest path is never shorter than the fewest steps
+                assert!(path.len() > steps[grid.target.0][grid.target.1].unwrap());
+            }
+            None => assert_eq!(steps[grid.target.0][grid.target.1], None),
         }
-        // Top-left
-        if x > 0 && y > 0 && !tile_types_to_avoid.contains(&top_left_tile.tile_type) && !top_left_tile.is_occupied {
-            neighbors.push((x - 1, y - 1));
+    }
+}
+
+#[test]
+fn test_matches_bfs_on_random_mixed_terrain() {
+    let mut random = Random(0x9e3779b97f4a7c15);
+    let terrain = [TileKind::Floor, TileKind::FieldEmpty, TileKind::Mud, TileKind::Water];
+    for _ in 0..500 {
+        let grid = random_grid(&mut random, &terrain);
+        let costs = bfs_costs(&grid.level_vec, grid.start);
+
+        match find_path(&grid) {
+            Some(path) => {
+                assert_walkable(&grid, &path);
+                assert_eq!(Some(cost(&grid, &path)), costs[grid.target.0][grid.target.1]);
+            }
+            None => assert_eq!(costs[grid.target.0][grid.target.1], None),
         }
-        // Top-right
-        if x > 0 && y < height - 1 && !tile_types_to_avoid.contains(&top_right_tile.tile_type) && !top_right_tile.is_occupied {
-            neighbors.push((x - 1, y + 1));
+    }
+}
+
+#[test]
+fn test_tile_changes_only_restart_searches_that_reached_them() {
+    //walled in target, the search runs out of budget long before it runs out of tiles
+    let picture: Vec<String> = (0..40).map(|row| (0..40).map(|col| match (col, row) {
+        (2, 2) => 's',
+        (35, 35) => 't',
+        (34..=36, 34..=36) | (6, 0..=4) => '#',
+        _ => '.',
+    }).collect()).collect();
+    let picture: Vec<&str> = picture.iter().map(|line| line.as_str()).collect();
+    let mut grid = grid(&picture);
+    let mut pathfinding = PathfindingManager::new();
+    pathfinding.path_queue.push_back(PathfindingManager::create_frontier(0, grid.start, grid.target, false));
+    pathfinding.process_path_queue(&grid.level_vec);
+    let explored = pathfinding.path_queue[0].costs.len();
+    assert!(explored > 1 && explored < 40 * 40);
+
+    //nowhere near anything it looked at
+    let untouched = (0..40).flat_map(|col| (0..40).map(move |row| (col, row)))
+        .find(|tile| !neighbors(&grid.level_vec, *tile).iter().chain([tile])
+            .any(|index| pathfinding.path_queue[0].costs.contains_key(index)))
+        .unwrap();
+    grid.level_vec[untouched.0][untouched.1].is_occupied = true;
+    pathfinding.mark_dirty(untouched);
+    assert_eq!(pathfinding.path_queue[0].costs.len(), explored);
+
+    //a wall the search went past opening up, it was never explored itself
+    let opened = (6, 2);
+    assert!(!pathfinding.path_queue[0].costs.contains_key(&opened));
+    grid.level_vec[opened.0][opened.1] = LevelTile::new(TileKind::Grass, grid.level_vec[opened.0][opened.1].rect);
+    pathfinding.mark_dirty(opened);
+    assert_eq!(pathfinding.path_queue[0].costs.len(), 1);
+
+    let path = loop {
+        pathfinding.process_path_queue(&grid.level_vec);
+        if let Some(path) = pathfinding.take_path(0) {
+            break path
         }
-        // Bottom-left
-        if x < width - 1 && y > 0 && !tile_types_to_avoid.contains(&bottom_left_tile.tile_type) && !bottom_left_tile.is_occupied {
-            neighbors.push((x + 1, y - 1));
+    };
+    assert!(path.is_empty());
+}
+
+//changes the tile, lets the manager patch its field and checks it against one built from scratch
+fn change_and_compare(pathfinding: &mut PathfindingManager, grid: &mut Grid, tile: (usize, usize), tile_type: TileKind) {
+    let rect = grid.level_vec[tile.0][tile.1].rect;
+    grid.level_vec[tile.0][tile.1] = LevelTile::new(tile_type, rect);
+    pathfinding.mark_dirty(tile);
+    pathfinding.update_flow_fields(&[grid.target], &grid.level_vec);
+    let fresh = PathfindingManager::create_flow_field(grid.target, &grid.level_vec, false);
+    assert_eq!(pathfinding.flow_fields[0].distance, fresh.distance, "after {:?} became {:?}", tile, tile_type);
+}
+
+#[test]
+fn test_flow_field_updates_match_a_fresh_field() {
+    let mut grid = grid(&[
+        "..........",
+        "..........",
+        "....m.....",
+        "..,,,.....",
+        ".........t",
+        "..........",
+    ]);
+    let mut pathfinding = PathfindingManager::new();
+    pathfinding.next_step(grid.target, grid.target, &grid.level_vec);
+
+    change_and_compare(&mut pathfinding, &mut grid, (5, 3), TileKind::Wall);
+    change_and_compare(&mut pathfinding, &mut grid, (5, 4), TileKind::Wall);
+    change_and_compare(&mut pathfinding, &mut grid, (5, 3), TileKind::Grass);
+    //a full column of wall cuts the left side off
+    for row in 0..6 {
+        change_and_compare(&mut pathfinding, &mut grid, (6, row), TileKind::Wall);
+    }
+    assert_eq!(pathfinding.flow_fields[0].distance[0][0], u32::MAX);
+    //and a gap lets it back in
+    change_and_compare(&mut pathfinding, &mut grid, (6, 2), TileKind::Grass);
+    assert!(pathfinding.flow_fields[0].distance[0][0] < u32::MAX);
+    //cheaper and dearer ground in the gap
+    change_and_compare(&mut pathfinding, &mut grid, (6, 2), TileKind::Floor);
+    change_and_compare(&mut pathfinding, &mut grid, (6, 2), TileKind::Mud);
+    for row in 0..6 {
+        change_and_compare(&mut pathfinding, &mut grid, (6, row), TileKind::Grass);
+    }
+}
+
+#[test]
+fn test_flow_field_updates_match_on_random_grids() {
+    let mut random = Random(0x2545f4914f6cdd1d);
+    let terrain = [TileKind::Floor, TileKind::FieldEmpty, TileKind::Mud, TileKind::Water];
+    for _ in 0..200 {
+        let mut grid = random_grid(&mut random, &terrain);
+        let mut pathfinding = PathfindingManager::new();
+        pathfinding.next_step(grid.target, grid.target, &grid.level_vec);
+        for _ in 0..10 {
+            let tile = (random.below(grid.level_vec.len()), random.below(grid.level_vec[0].len()));
+            let tile_type = match random.below(3) {
+                0 => TileKind::Wall,
+                1 => TileKind::Grass,
+                _ => terrain[random.below(terrain.len())],
+            };
+            change_and_compare(&mut pathfinding, &mut grid, tile, tile_type);
         }
-        // Bottom-right
-        if x < width - 1 && y < height - 1 && !tile_types_to_avoid.contains(&bottom_right_tile.tile_type) && !bottom_right_tile.is_occupied {
-            neighbors.push((x + 1, y + 1));
-        }            
-        neighbors
     }
 }
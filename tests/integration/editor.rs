@@ -0,0 +1,109 @@
+use farm_defense::constants;
+use farm_defense::editor_manager::{EditorChange, EditorManager};
+use farm_defense::level_manager::{LevelManager, TileData, TileKind};
+
+fn tiles(level: &LevelManager) -> Vec<Vec<TileKind>> {
+    level.level_vec.iter()
+        .map(|col| col.iter().map(|tile| tile.tile_type).collect())
+        .collect()
+}
+
+//walls, water, a spawn, the base and both towers, each as its own undo step
+fn edit_level(editor: &mut EditorManager, level: &mut LevelManager) {
+    let changes = EditorManager::rectangle_fill(level, (2, 2), (6, 5), TileKind::Wall);
+    editor.commit(level, changes);
+    let changes = EditorManager::flood_fill(level, (0, 7), TileKind::Water);
+    editor.commit(level, changes);
+    editor.commit(level, vec![EditorChange::Tile { index: (0, 0), before: TileKind::Water, after: TileKind::Spawn }]);
+    editor.commit(level, vec![EditorChange::Base { before: None, after: Some((7, 6)) }]);
+    editor.commit(level, vec![
+        EditorChange::Tower { index: (8, 3), before: None, after: Some(TileData::ArcherTowerBottom) },
+        EditorChange::Tower { index: (1, 1), before: None, after: Some(TileData::FireballTowerBottom) },
+    ]);
+}
+
+#[test]
+fn test_saved_level_parses_back_the_same() {
+    let mut editor = EditorManager::new();
+    let mut level = LevelManager::blank("edited", (10, 8));
+    edit_level(&mut editor, &mut level);
+    assert_eq!(level.level_vec[4][3].tile_type, TileKind::Wall);
+    assert_eq!(level.level_vec[9][0].tile_type, TileKind::Water);
+
+    let path = std::env::temp_dir().join(format!("farm_defense_editor_{}.txt", std::process::id()));
+    let path = path.to_str().unwrap();
+    level.write_file(path).unwrap();
+    let saved = std::fs::read_to_string(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(saved, level.to_level_string());
+
+    let parsed = LevelManager::parse_level(&saved, "edited").unwrap();
+    assert_eq!(tiles(&parsed), tiles(&level));
+    assert_eq!(parsed.header.name, "edited");
+    assert_eq!(parsed.header.base_location, Some((7, 6)));
+    assert_eq!(parsed.level_vec[0][0].tile_type, TileKind::Spawn);
+    //base and two towers
+    assert_eq!(parsed.entities.len(), level.entities.len());
+    assert_eq!(parsed.entities.len(), 3);
+    assert_eq!(parsed.to_level_string(), saved);
+}
+
+#[test]
+fn test_undo_and_redo_step_through_edits() {
+    let mut editor = EditorManager::new();
+    let mut level = LevelManager::blank("edited", (10, 8));
+    let mut history = vec![level.to_level_string()];
+    let steps: Vec<Vec<EditorChange>> = vec![
+        EditorManager::rectangle_fill(&level, (2, 2), (6, 5), TileKind::Wall),
+        vec![EditorChange::Base { before: None, after: Some((7, 6)) }],
+        //moving the base is one step, undoing it puts it back
+        vec![EditorChange::Base { before: Some((7, 6)), after: Some((0, 0)) }],
+        vec![EditorChange::Tower { index: (8, 3), before: None, after: Some(TileData::ArcherTowerBottom) }],
+    ];
+    for changes in steps {
+        editor.commit(&mut level, changes);
+        history.push(level.to_level_string());
+    }
+    assert!(editor.is_modified);
+    assert_eq!(editor.undo_vec.len(), 4);
+
+    for state in history.iter().rev().skip(1) {
+        editor.undo(&mut level);
+        assert_eq!(&level.to_level_string(), state);
+    }
+    assert!(editor.undo_vec.is_empty());
+    //nothing left, stays put
+    editor.undo(&mut level);
+    assert_eq!(level.to_level_string(), history[0]);
+
+    for state in history.iter().skip(1) {
+        editor.redo(&mut level);
+        assert_eq!(&level.to_level_string(), state);
+    }
+    assert_eq!(level.header.base_location, Some((0, 0)));
+    assert_eq!(level.entities.len(), 2);
+
+    //a new edit drops what could have been redone
+    editor.undo(&mut level);
+    editor.undo(&mut level);
+    editor.commit(&mut level, vec![EditorChange::Tile { index: (9, 7), before: TileKind::Grass, after: TileKind::Mud }]);
+    assert!(editor.redo_vec.is_empty());
+    editor.redo(&mut level);
+    assert_eq!(level.header.base_location, Some((7, 6)));
+    assert_eq!(level.level_vec[9][7].tile_type, TileKind::Mud);
+}
+
+#[test]
+fn test_undo_history_is_limited() {
+    let mut editor = EditorManager::new();
+    let mut level = LevelManager::blank("edited", (10, 8));
+    for step in 0..constants::EDITOR_UNDO_LIMIT + 5 {
+        let after = if step % 2 == 0 { TileKind::Wall } else { TileKind::Grass };
+        let changes = EditorManager::rectangle_fill(&level, (0, 0), (0, 0), after);
+        editor.commit(&mut level, changes);
+    }
+    assert_eq!(editor.undo_vec.len(), constants::EDITOR_UNDO_LIMIT);
+    //empty steps aren't kept
+    editor.commit(&mut level, Vec::new());
+    assert_eq!(editor.undo_vec.len(), constants::EDITOR_UNDO_LIMIT);
+}
@@ -0,0 +1,78 @@
+use farm_defense::level_manager::{LevelManager, LevelParseError, TileKind};
+
+//4 columns of 3 tiles, entity lines start on line 10
+fn parse_with_entities(entities: &str) -> Result<LevelManager, LevelParseError> {
+    let contents = format!(
+        "[header]\nname test\nsize 4 3\n[tiles]\n000\n000\n000\n000\n[entities]\n{}",
+        entities
+    );
+    LevelManager::parse_level(&contents, "test")
+}
+
+fn error_line(result: Result<LevelManager, LevelParseError>) -> usize {
+    match result {
+        Ok(_) => panic!("level should not parse"),
+        Err(error) => error.line,
+    }
+}
+
+#[test]
+fn test_entities_fit_on_map() {
+    let level = parse_with_entities("tower archer 3 1\nbuilding base 2 1\nenemy goblin 3 2\n").unwrap();
+    assert_eq!(level.entities.len(), 3);
+
+    assert_eq!(error_line(parse_with_entities("crop carrot 1 1\nenemy goblin 4 0\n")), 11);
+    //the top of the tower would be on row -1
+    assert_eq!(error_line(parse_with_entities("tower archer 2 0\n")), 10);
+    //2 by 2 from column 3 runs off the right side
+    assert_eq!(error_line(parse_with_entities("tower fireball 1 1\nbuilding base 3 1\n")), 11);
+    assert_eq!(error_line(parse_with_entities("building base 1 2\n")), 10);
+}
+
+#[test]
+fn test_header_positions_name_their_line() {
+    let contents = "[header]\nsize 4 3\nspawn 0 0\nspawn 0 3\n[tiles]\n000\n000\n000\n000\n";
+    assert_eq!(error_line(LevelManager::parse_level(contents, "test")), 4);
+    let contents = "[header]\nsize 4 3\nbase 3 0\n[tiles]\n000\n000\n000\n000\n";
+    assert_eq!(error_line(LevelManager::parse_level(contents, "test")), 3);
+}
+
+#[test]
+fn test_legend_character_means_one_tile_kind() {
+    let contents = "[legend]\n~ water\n. grass\n[tiles]\n~.\n.~\n";
+    let level = LevelManager::parse_level(contents, "test").unwrap();
+    assert_eq!(level.level_vec[0][0].tile_type, TileKind::Water);
+    assert_eq!(level.level_vec[0][1].tile_type, TileKind::Grass);
+
+    let contents = "[legend]\n~ water\n~ mud\n[tiles]\n~~\n";
+    assert_eq!(error_line(LevelManager::parse_level(contents, "test")), 3);
+}
+
+#[test]
+fn test_header_size_must_match_tiles() {
+    let contents = "[header]\nsize 3 3\n[tiles]\n000\n000\n000\n000\n";
+    assert!(LevelManager::parse_level(contents, "test").is_err());
+    let contents = "[header]\nsize 4 2\n[tiles]\n000\n000\n000\n000\n";
+    assert!(LevelManager::parse_level(contents, "test").is_err());
+    let contents = "[header]\nsize 4 3\n[tiles]\n000\n000\n000\n000\n";
+    assert!(LevelManager::parse_level(contents, "test").is_ok());
+}
+
+#[test]
+fn test_legacy_levels_load_unchanged() {
+    for path in ["levels/farm.txt", "levels/dungeon.txt", "levels/asylum.txt", "levels/level0.txt"] {
+        let contents = std::fs::read_to_string(path).unwrap();
+        let level = LevelManager::parse_level(&contents, "legacy")
+            .unwrap_or_else(|error| panic!("{} {}", path, error));
+        let tile_lines: Vec<&str> = contents.lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.is_empty())
+            .collect();
+        assert_eq!(level.level_vec.len(), tile_lines.len(), "{}", path);
+        for (col, line) in level.level_vec.iter().zip(&tile_lines) {
+            let tiles: String = col.iter().map(|tile| tile.tile_type.to_char()).collect();
+            assert_eq!(&tiles, line, "{}", path);
+        }
+        assert!(level.entities.is_empty());
+    }
+}
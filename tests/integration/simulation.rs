@@ -0,0 +1,1168 @@
+use farm_defense::{
+    building_manager,
+    constants,
+    enemy_manager,
+    game_manager,
+    level_manager,
+    pathfinding_manager,
+    player_manager,
+    projectile_manager,
+    replay_manager,
+    save_manager,
+    spatial_manager,
+    status_manager,
+    tower_manager,
+    wave_manager,
+};
+use level_manager::{LevelEntity, TileData, TileKind};
+use replay_manager::ReplayAction;
+
+//everything update_simulation needs, no window or canvas anywhere
+struct Simulation {
+    game: game_manager::GameManager,
+    level: level_manager::LevelManager,
+    towers: tower_manager::TowerManager,
+    buildings: building_manager::BuildingManager,
+    enemies: enemy_manager::EnemyManager,
+    projectiles: projectile_manager::ProjectileManager,
+    pathfinding_manager: pathfinding_manager::PathfindingManager,
+    wave_manager: wave_manager::WaveManager,
+    replay_manager: replay_manager::ReplayManager,
+}
+
+impl Simulation {
+    fn new(base: Option<(usize, usize)>, entities: Vec<LevelEntity>) -> Simulation {
+        let mut level = level_manager::LevelManager::blank("test", (24, 24));
+        if let Some(index) = base {
+            level.header.base_location = Some(index);
+            level.entities.push(LevelEntity::Building {
+                building_type: building_manager::BuildingType::Base,
+                index,
+            });
+        }
+        level.entities.extend(entities);
+
+        let mut simulation = Simulation {
+            game: game_manager::GameManager::new(),
+            level,
+            towers: tower_manager::TowerManager::new(),
+            buildings: building_manager::BuildingManager::new(),
+            enemies: enemy_manager::EnemyManager::new(),
+            projectiles: projectile_manager::ProjectileManager::new(),
+            pathfinding_manager: pathfinding_manager::PathfindingManager::new(),
+            wave_manager: wave_manager::WaveManager::new(),
+            replay_manager: replay_manager::ReplayManager::new(),
+        };
+        simulation.level.place_entities(
+            &mut simulation.game,
+            &mut simulation.towers,
+            &mut simulation.buildings,
+            &mut simulation.enemies
+        );
+        simulation.wave_manager.find_spawn_points(&simulation.level);
+        simulation.replay_manager.start_recording("test");
+        simulation
+    }
+
+    fn step(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.game.update_simulation(
+                &mut self.level,
+                &mut self.towers,
+                &mut self.buildings,
+                &mut self.enemies,
+                &mut self.projectiles,
+                &mut self.pathfinding_manager,
+                &mut self.wave_manager,
+                &mut self.replay_manager
+            );
+        }
+    }
+
+    fn save(&self, save_manager: &save_manager::SaveManager) -> Result<(), std::io::Error> {
+        save_manager.write_save(
+            &self.game,
+            &player(),
+            &self.level,
+            &self.towers,
+            &self.buildings,
+            &self.enemies,
+            &self.projectiles,
+            &[],
+            &self.wave_manager
+        )
+    }
+
+    //what main does after a load, fresh searches and flow fields
+    fn load(&mut self, save_manager: &save_manager::SaveManager) -> Result<(), std::io::Error> {
+        save_manager.read_save(
+            &mut self.game,
+            &mut player(),
+            &mut self.level,
+            &mut self.towers,
+            &mut self.buildings,
+            &mut self.enemies,
+            &mut self.projectiles,
+            &mut self.wave_manager
+        )?;
+        self.pathfinding_manager.clear_flow_fields();
+        self.pathfinding_manager.clear_path_requests();
+        Ok(())
+    }
+
+    //what game_loop does with a frame of the given length
+    fn frame(&mut self, frame_delta: f64) -> u32 {
+        self.game.advance_simulation(
+            frame_delta,
+            &mut self.level,
+            &mut self.towers,
+            &mut self.buildings,
+            &mut self.enemies,
+            &mut self.projectiles,
+            &mut self.pathfinding_manager,
+            &mut self.wave_manager,
+            &mut self.replay_manager
+        )
+    }
+}
+
+fn player() -> player_manager::PlayerManager {
+    player_manager::PlayerManager {
+        up: false,
+        down: false,
+        left: false,
+        right: false,
+        colliding: false,
+        is_attacking: false,
+        x: 0,
+        y: 0,
+        texture_path: "".to_string(),
+        rect: sdl2::rect::Rect::new(0, 0, constants::TILE_SIZE, constants::TILE_SIZE),
+        direction: player_manager::Direction::Up,
+        menu_selection: 0,
+    }
+}
+
+fn save_file(name: &str) -> save_manager::SaveManager {
+    let mut save_manager = save_manager::SaveManager::new();
+    save_manager.save_path = std::env::temp_dir()
+        .join(format!("farm_defense_{}_{}.bin", name, std::process::id()))
+        .to_string_lossy()
+        .to_string();
+    save_manager
+}
+
+fn goblin(index: (usize, usize)) -> LevelEntity {
+    LevelEntity::Enemy {
+        enemy_type: enemy_manager::EnemyType::from_name("goblin").unwrap(),
+        index,
+    }
+}
+
+#[test]
+fn test_tower_kills_enemy_and_pays_gold() {
+    let mut simulation = Simulation::new(Some((2, 2)), vec![
+        LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+        goblin((14, 12)),
+    ]);
+    let starting_gold = simulation.game.gold_amount;
+
+    let mut steps = 0;
+    while !simulation.enemies.enemy_vec.is_empty() && steps < 5000 {
+        simulation.step(1);
+        steps += 1;
+    }
+
+    assert!(simulation.enemies.enemy_vec.is_empty(), "goblin still alive after {} steps", steps);
+    assert_eq!(simulation.game.gold_amount, starting_gold + 1);
+}
+
+#[test]
+fn test_enemy_moves_toward_base() {
+    let base = (2, 2);
+    let mut simulation = Simulation::new(Some(base), vec![goblin((18, 18))]);
+    let distance = |index: (usize, usize)| index.0.abs_diff(base.0) + index.1.abs_diff(base.1);
+    let start_distance = distance(simulation.enemies.enemy_vec[0].grid_index);
+
+    simulation.step(300);
+
+    let enemy = &simulation.enemies.enemy_vec[0];
+    assert!(distance(enemy.grid_index) < start_distance);
+}
+
+#[test]
+fn test_walled_in_base_is_sieged_through_weakest_wall() {
+    let base = (12, 12);
+    let mut simulation = Simulation::new(Some(base), vec![goblin((4, 10))]);
+    //a ring of wall with room inside, one cracked wall on the goblin's side
+    let weakest = (10, 13);
+    for col in 10..=15 {
+        for row in 10..=15 {
+            if col == 10 || col == 15 || row == 10 || row == 15 {
+                let rect = simulation.level.level_vec[col][row].rect;
+                simulation.level.level_vec[col][row] = level_manager::LevelTile::new(TileKind::Wall, rect);
+            }
+        }
+    }
+    simulation.level.level_vec[weakest.0][weakest.1].health = constants::WALL_HEALTH / 4;
+    let inside = |index: (usize, usize)| (11..15).contains(&index.0) && (11..15).contains(&index.1);
+
+    let mut sieged = Vec::new();
+    let mut steps = 0;
+    while !inside(simulation.enemies.enemy_vec[0].grid_index) && steps < 3000 {
+        simulation.step(1);
+        sieged.extend(simulation.enemies.enemy_vec[0].siege_target);
+        steps += 1;
+    }
+
+    assert!(inside(simulation.enemies.enemy_vec[0].grid_index), "goblin at {:?}", simulation.enemies.enemy_vec[0].grid_index);
+    sieged.dedup();
+    assert_eq!(sieged, vec![weakest]);
+    assert_eq!(simulation.level.level_vec[weakest.0][weakest.1].tile_type, TileKind::Floor);
+    let walls_left = (10..=15).flat_map(|col| (10..=15).map(move |row| (col, row)))
+        .filter(|index| simulation.level.level_vec[index.0][index.1].tile_type == TileKind::Wall)
+        .count();
+    assert_eq!(walls_left, 19);
+}
+
+#[test]
+fn test_crop_grows_to_harvest() {
+    let crop = (5, 5);
+    let mut simulation = Simulation::new(None, vec![
+        LevelEntity::Crop { tile_data: TileData::Carrots, index: crop },
+    ]);
+
+    simulation.step(constants::CROP_TIME as u32);
+    assert_eq!(simulation.level.level_vec[crop.0][crop.1].tile_type, TileKind::FieldGrowing);
+
+    simulation.step(constants::CROP_TIME as u32);
+    assert_eq!(simulation.level.level_vec[crop.0][crop.1].tile_type, TileKind::FieldHarvestable);
+    assert_eq!(simulation.level.level_vec[crop.0][crop.1].texture_path, constants::TEXTURE_FIELD_CARROT);
+}
+
+#[test]
+fn test_simulation_steps_frame_time() {
+    let mut simulation = Simulation::new(None, Vec::new());
+    let frame_time = simulation.game.frame_time;
+
+    simulation.step(10);
+
+    assert_eq!(simulation.game.frame_time, frame_time + 10);
+}
+
+#[test]
+fn test_frame_rate_does_not_change_gameplay() {
+    let entities = || vec![
+        LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+        LevelEntity::Crop { tile_data: TileData::Tomatoes, index: (5, 5) },
+        goblin((20, 12)),
+    ];
+    let mut slow = Simulation::new(Some((2, 2)), entities());
+    let mut fast = Simulation::new(Some((2, 2)), entities());
+    let steps = 2 * constants::SIMULATION_RATE as u32;
+
+    let mut frames: u32 = 0;
+    while slow.game.frame_time <= steps {
+        slow.frame(1.0 / 30.0);
+        frames += 1;
+    }
+    assert!(frames.abs_diff(steps / 2) <= 1, "30 fps took {} frames", frames);
+    //one step at most per frame, so it lands exactly where the slow run stopped
+    while fast.game.frame_time < slow.game.frame_time {
+        assert!(fast.frame(1.0 / 240.0) <= 1);
+    }
+
+    assert_eq!(slow.game.frame_time, fast.game.frame_time);
+    assert_eq!(slow.level.level_vec[5][5].state, fast.level.level_vec[5][5].state);
+    assert_eq!(slow.enemies.enemy_vec.len(), fast.enemies.enemy_vec.len());
+    for (slow_enemy, fast_enemy) in slow.enemies.enemy_vec.iter().zip(&fast.enemies.enemy_vec) {
+        assert_eq!(slow_enemy.pixel_index, fast_enemy.pixel_index);
+        assert_eq!(slow_enemy.health, fast_enemy.health);
+    }
+    assert_eq!(slow.projectiles.projectile_vec.len(), fast.projectiles.projectile_vec.len());
+}
+
+#[test]
+fn test_long_frame_catches_up_a_limited_amount() {
+    let mut simulation = Simulation::new(None, Vec::new());
+
+    let steps = simulation.frame(5.0);
+
+    assert_eq!(steps, constants::MAX_SIMULATION_STEPS);
+    assert!(simulation.game.interpolation >= 0.0 && simulation.game.interpolation < 1.0);
+}
+
+//a few steps of play with input in between, the way a player would queue it
+fn play_recorded_run(simulation: &mut Simulation) {
+    let actions = [
+        (10, ReplayAction::Build { build: constants::CURRENT_BUILD_ARCHER_TOWER, index: (12, 12) }),
+        (40, ReplayAction::Seed { seed: constants::CURRENT_SEED_HO, index: (5, 5) }),
+        (41, ReplayAction::Seed { seed: constants::CURRENT_SEED_CARROT, index: (5, 5) }),
+        (60, ReplayAction::Shoot { start: (200, 400), target: (640, 400) }),
+        (90, ReplayAction::Upgrade { index: (12, 12), path: tower_manager::UpgradePath::First }),
+        (91, ReplayAction::Upgrade { index: (12, 12), path: tower_manager::UpgradePath::First }),
+        (120, ReplayAction::Build { build: constants::CURRENT_BUILD_WALL, index: (16, 10) }),
+    ];
+    for (tick, action) in actions {
+        while simulation.game.frame_time < tick {
+            simulation.step(1);
+        }
+        simulation.replay_manager.queue_action(action);
+    }
+    simulation.step(400);
+}
+
+#[test]
+fn test_replay_reproduces_run() {
+    let entities = || vec![goblin((20, 12)), goblin((20, 14))];
+    let mut recorded = Simulation::new(Some((2, 2)), entities());
+    play_recorded_run(&mut recorded);
+    assert_eq!(recorded.replay_manager.input_vec.len(), 7);
+
+    let replay = replay_manager::ReplayManager::parse_replay(
+        &recorded.replay_manager.to_replay_string()
+    ).unwrap();
+    assert_eq!(replay.level_path, "test");
+    assert_eq!(replay.input_vec, recorded.replay_manager.input_vec);
+
+    let mut replayed = Simulation::new(Some((2, 2)), entities());
+    replayed.replay_manager.start_playback(replay.input_vec);
+    //live input is ignored while the replay plays
+    replayed.replay_manager.queue_action(ReplayAction::Build { build: constants::CURRENT_BUILD_WALL, index: (3, 3) });
+    while replayed.game.frame_time < recorded.game.frame_time {
+        replayed.frame(1.0 / 144.0);
+    }
+
+    assert_eq!(replayed.game.frame_time, recorded.game.frame_time);
+    assert_eq!(replayed.game.gold_amount, recorded.game.gold_amount);
+    assert!(replayed.level.level_vec[5][5].tile_data == TileData::Carrots);
+    assert_eq!(replayed.level.level_vec[16][10].tile_type, TileKind::Wall);
+    assert_eq!(replayed.level.level_vec[3][3].tile_type, TileKind::Grass);
+    assert_eq!(replayed.towers.tower_vec.len(), 1);
+    assert_eq!(replayed.towers.tower_vec[0].attack_radius, recorded.towers.tower_vec[0].attack_radius);
+    assert_eq!(replayed.towers.tower_vec[0].first_path_purchased, 2);
+    assert_eq!(replayed.enemies.enemy_vec.len(), recorded.enemies.enemy_vec.len());
+    for (replayed_enemy, recorded_enemy) in replayed.enemies.enemy_vec.iter().zip(&recorded.enemies.enemy_vec) {
+        assert_eq!(replayed_enemy.pixel_index, recorded_enemy.pixel_index);
+        assert_eq!(replayed_enemy.health, recorded_enemy.health);
+    }
+    assert_eq!(replayed.projectiles.projectile_vec.len(), recorded.projectiles.projectile_vec.len());
+}
+
+//spawns on the left, base on the right, the built in waves only have * groups
+//takes the wave manager the way main keeps one for the whole session
+fn wave_simulation(wave_manager: wave_manager::WaveManager) -> Simulation {
+    let mut simulation = Simulation::new(Some((20, 12)), Vec::new());
+    for spawn in [(1, 4), (1, 20)] {
+        let rect = simulation.level.level_vec[spawn.0][spawn.1].rect;
+        simulation.level.level_vec[spawn.0][spawn.1] = level_manager::LevelTile::new(TileKind::Spawn, rect);
+    }
+    simulation.wave_manager = wave_manager;
+    simulation.wave_manager.find_spawn_points(&simulation.level);
+    simulation.wave_manager.load_wave_script("test", &mut simulation.game);
+    simulation
+}
+
+fn enemy_positions(simulation: &Simulation) -> Vec<(u32, (u32, u32))> {
+    simulation.enemies.enemy_vec.iter().map(|enemy| (enemy.id, enemy.pixel_index)).collect()
+}
+
+#[test]
+fn test_replay_spawns_the_same_later_in_the_session() {
+    let mut recorded = wave_simulation(wave_manager::WaveManager::new());
+    recorded.replay_manager.queue_action(ReplayAction::Build { build: constants::CURRENT_BUILD_WALL, index: (10, 10) });
+    while recorded.wave_manager.current_wave < 1 || !recorded.wave_manager.pending_spawns.is_empty() {
+        recorded.step(1);
+    }
+    //an odd wave leaves the * turns part way round the spawns
+    assert_eq!(recorded.enemies.enemy_vec.len() % 2, 1);
+    let positions = enemy_positions(&recorded);
+    let replay = replay_manager::ReplayManager::parse_replay(&recorded.replay_manager.to_replay_string()).unwrap();
+
+    let mut wave_manager = std::mem::replace(&mut recorded.wave_manager, wave_manager::WaveManager::new());
+    for _ in 0..2 {
+        let mut replayed = wave_simulation(wave_manager);
+        replayed.replay_manager.start_playback(replay.input_vec.clone());
+        while replayed.game.frame_time < recorded.game.frame_time {
+            replayed.step(1);
+        }
+        assert_eq!(enemy_positions(&replayed), positions);
+        wave_manager = replayed.wave_manager;
+    }
+}
+
+#[test]
+fn test_wave_director_builds_then_spawns_a_wave() {
+    use wave_manager::WaveState;
+    let mut simulation = wave_simulation(wave_manager::WaveManager::new());
+    let rate = constants::SIMULATION_RATE as u32;
+
+    let mut build_steps: u32 = 0;
+    while simulation.wave_manager.wave_state == WaveState::Build {
+        assert!(simulation.enemies.enemy_vec.is_empty());
+        simulation.step(1);
+        build_steps += 1;
+    }
+    assert!(build_steps.abs_diff(constants::WAVE_BUILD_TIME as u32 * rate) <= 1, "built for {} steps", build_steps);
+    assert_eq!(simulation.wave_manager.current_wave, 1);
+
+    //(step, spawn tile) of every enemy as it shows up
+    let mut spawns: Vec<(u32, (u32, u32))> = Vec::new();
+    let mut steps: u32 = 0;
+    while simulation.wave_manager.wave_state == WaveState::Spawning {
+        simulation.step(1);
+        steps += 1;
+        for enemy in &simulation.enemies.enemy_vec[spawns.len()..] {
+            spawns.push((steps, (
+                enemy.previous_pixel_index.0 / constants::TILE_SIZE,
+                enemy.previous_pixel_index.1 / constants::TILE_SIZE
+            )));
+        }
+    }
+    assert!(simulation.wave_manager.wave_state == WaveState::Active);
+    assert!(simulation.wave_manager.pending_spawns.is_empty());
+    assert_eq!(spawns.len(), wave_manager::WaveManager::wave_enemy_amount(1) as usize);
+    //* groups go round the spawns in turn
+    for (spawn_index, (_, tile)) in spawns.iter().enumerate() {
+        let expected = if spawn_index % 2 == 0 { (1, 4) } else { (1, 20) };
+        assert_eq!(*tile, expected, "spawn {}", spawn_index);
+    }
+    let spacing = (wave_manager::WaveManager::wave_spawn_interval(1) * constants::SIMULATION_RATE).round() as u32;
+    for pair in spawns.windows(2) {
+        assert!((pair[1].0 - pair[0].0).abs_diff(spacing) <= 1, "spawned at {:?}", spawns);
+    }
+
+    //clearing the wave starts building for the next one
+    for enemy in &mut simulation.enemies.enemy_vec {
+        enemy.health = 0;
+    }
+    simulation.step(2);
+    assert!(simulation.wave_manager.wave_state == WaveState::Build);
+    assert_eq!(simulation.wave_manager.current_wave, 1);
+    let build_time = wave_manager::WaveManager::default_wave(2).build_time;
+    assert!(simulation.wave_manager.wave_timer <= build_time);
+    assert!(simulation.wave_manager.wave_timer > build_time - constants::SIMULATION_STEP * 2.0);
+}
+
+fn build_wall(simulation: &mut Simulation, index: (usize, usize)) {
+    simulation.buildings.place_build(
+        &mut simulation.game,
+        &mut simulation.level,
+        &mut simulation.towers,
+        &mut simulation.enemies,
+        &mut simulation.pathfinding_manager,
+        &simulation.wave_manager,
+        constants::CURRENT_BUILD_WALL,
+        index
+    );
+}
+
+#[test]
+fn test_wall_that_seals_the_path_is_refused() {
+    let mut simulation = wave_simulation(wave_manager::WaveManager::new());
+    let starting_gold = simulation.game.gold_amount;
+    //a wall across the whole level, with the last gap in the middle
+    let gap = (10, 12);
+    for row_index in (0..24).filter(|row_index| *row_index != gap.1) {
+        build_wall(&mut simulation, (gap.0, row_index));
+        assert_eq!(simulation.level.level_vec[gap.0][row_index].tile_type, TileKind::Wall, "row {}", row_index);
+    }
+
+    simulation.game.level_updated = false;
+    build_wall(&mut simulation, gap);
+    assert_eq!(simulation.level.level_vec[gap.0][gap.1].tile_type, TileKind::Grass);
+    assert_eq!(simulation.level.level_vec[gap.0][gap.1].health, 0);
+    assert!(!simulation.game.level_updated);
+    assert_eq!(simulation.game.gold_amount, starting_gold);
+
+    //walls that leave a way round still go up
+    build_wall(&mut simulation, (5, 12));
+    assert_eq!(simulation.level.level_vec[5][12].tile_type, TileKind::Wall);
+    assert!(simulation.game.level_updated);
+    assert_eq!(simulation.game.gold_amount, starting_gold);
+}
+
+//sets the boss health, returns whatever update_bosses summoned for it
+fn hurt_boss(simulation: &mut Simulation, health_percentage: f64) -> Vec<(enemy_manager::EnemyType, (usize, usize))> {
+    let already_placed = simulation.enemies.enemy_vec.len();
+    let boss = &mut simulation.enemies.enemy_vec[0];
+    boss.health = (boss.max_health as f64 * health_percentage) as u16;
+    simulation.enemies.update_bosses(&mut simulation.game, &mut simulation.level);
+    simulation.enemies.enemy_vec[already_placed..].iter()
+        .map(|enemy| (enemy.enemy_type, enemy.grid_index))
+        .collect()
+}
+
+#[test]
+fn test_ogre_boss_changes_phase_as_it_is_hurt() {
+    let ogre = enemy_manager::EnemyType::from_name("ogre").unwrap();
+    let phases = ogre.stats().boss_phases;
+    let boss_index = (12, 12);
+    let mut simulation = Simulation::new(Some((2, 2)), vec![LevelEntity::Enemy { enemy_type: ogre, index: boss_index }]);
+    let summoned_next_to_boss = |summons: &[(enemy_manager::EnemyType, (usize, usize))]| summons.iter().all(|(_, index)| {
+        (boss_index.0 - 1..=boss_index.0 + 2).contains(&index.0)
+        && (boss_index.1 - 2..=boss_index.1 + 1).contains(&index.1)
+    });
+
+    assert!(hurt_boss(&mut simulation, 0.9).is_empty());
+    assert_eq!(simulation.enemies.enemy_vec[0].boss_phase, 0);
+    assert_eq!(simulation.enemies.enemy_vec[0].movement_speed, ogre.stats().movement_speed);
+    assert_eq!(simulation.enemies.enemy_vec[0].attack_speed, ogre.stats().attack_speed);
+
+    let mut messages = simulation.game.message_vec.len();
+    for (phase_index, phase) in phases.iter().enumerate() {
+        let summons = hurt_boss(&mut simulation, phase.health_threshold - 0.01);
+        let boss = &simulation.enemies.enemy_vec[0];
+        assert_eq!(boss.boss_phase as usize, phase_index + 1);
+        assert_eq!(boss.movement_speed, phase.movement_speed);
+        assert_eq!(boss.attack_speed, phase.attack_speed);
+        assert_eq!(summons.len(), phase.summon_amount as usize, "phase {}", phase_index);
+        assert!(summons.iter().all(|(enemy_type, _)| Some(*enemy_type) == phase.summon_type));
+        assert!(summoned_next_to_boss(&summons), "{:?}", summons);
+        assert_eq!(simulation.game.message_vec.len(), messages + 1);
+        messages += 1;
+
+        //staying in the phase doesn't summon again
+        assert!(hurt_boss(&mut simulation, phase.health_threshold - 0.02).is_empty());
+        assert_eq!(simulation.game.message_vec.len(), messages);
+    }
+    //enraged by the last phase
+    assert!(simulation.enemies.enemy_vec[0].movement_speed > ogre.stats().movement_speed);
+    assert!(simulation.enemies.enemy_vec[0].attack_speed < ogre.stats().attack_speed);
+
+    //a big hit goes through every phase at once
+    let mut simulation = Simulation::new(Some((2, 2)), vec![LevelEntity::Enemy { enemy_type: ogre, index: boss_index }]);
+    let summons = hurt_boss(&mut simulation, 0.1);
+    assert_eq!(simulation.enemies.enemy_vec[0].boss_phase as usize, phases.len());
+    assert_eq!(summons.len(), phases.iter().map(|phase| phase.summon_amount as usize).sum::<usize>());
+    assert!(summoned_next_to_boss(&summons));
+}
+
+#[test]
+fn test_upgrades_are_only_bought_with_gold() {
+    use tower_manager::UpgradePath::{First, Second};
+    let mut simulation = Simulation::new(None, vec![
+        LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+    ]);
+    let first_cost = tower_manager::TowerManager::upgrade_path(TileKind::ArcherBottom, First)[0].cost;
+    simulation.game.gold_amount = first_cost;
+    //both rows clicked on the same frame, only the first is paid for
+    simulation.replay_manager.queue_action(ReplayAction::Upgrade { index: (12, 12), path: First });
+    simulation.replay_manager.queue_action(ReplayAction::Upgrade { index: (12, 12), path: Second });
+    simulation.step(1);
+    let tower = &simulation.towers.tower_vec[0];
+    assert_eq!((tower.first_path_purchased, tower.second_path_purchased), (1, 0));
+    assert_eq!(simulation.game.gold_amount, 0);
+    assert_eq!(tower.projectile_status.map(|status| status.kind), Some(status_manager::StatusKind::Poison));
+
+    //nothing past the end of a path
+    simulation.game.gold_amount = 1000;
+    for _ in 0..3 {
+        simulation.replay_manager.queue_action(ReplayAction::Upgrade { index: (12, 12), path: First });
+    }
+    simulation.step(1);
+    assert_eq!(simulation.towers.tower_vec[0].first_path_purchased, 2);
+
+    let replay = replay_manager::ReplayManager::parse_replay(&simulation.replay_manager.to_replay_string()).unwrap();
+    assert_eq!(replay.input_vec, simulation.replay_manager.input_vec);
+    assert!(replay_manager::ReplayManager::parse_replay("level test\n5 upgrade 12 12 10 5 0 32\n").is_err());
+}
+
+#[test]
+fn test_replay_fast_forward_steps_faster() {
+    let mut simulation = Simulation::new(None, Vec::new());
+    simulation.replay_manager.start_playback(vec![replay_manager::ReplayInput {
+        tick: 1000,
+        action: ReplayAction::Seed { seed: constants::CURRENT_SEED_HO, index: (5, 5) },
+    }]);
+    simulation.replay_manager.fast_forward = true;
+
+    let steps = simulation.frame(constants::SIMULATION_STEP * 1.5);
+
+    //one and a half steps of frame time at eight times the speed
+    assert_eq!(steps, (1.5 * constants::REPLAY_FAST_FORWARD_SPEED as f64).round() as u32);
+}
+
+#[test]
+fn test_replay_parse_errors_name_the_line() {
+    let error = replay_manager::ReplayManager::parse_replay("level levels/farm.txt\n20 build 0 4 4\n10 seed 2 4 4\n")
+        .err()
+        .unwrap();
+    assert_eq!(error.line, 3);
+
+    let error = replay_manager::ReplayManager::parse_replay("level levels/farm.txt\n20 jump 0 4\n")
+        .err()
+        .unwrap();
+    assert_eq!(error.line, 2);
+    assert!(replay_manager::ReplayManager::parse_replay("20 build 0 4 4\n").is_err());
+}
+
+//archer tower at (12, 12) shooting from (12, 11), goblins placed by tile with their health
+//every goblin is headed for base, first and last go by its flow field
+fn targeting_layout(base: (usize, usize), layout: &[((usize, usize), u16)]) -> Simulation {
+    let mut entities = vec![
+        LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+    ];
+    entities.extend(layout.iter().map(|(index, _)| goblin(*index)));
+    let mut simulation = Simulation::new(Some(base), entities);
+    for (enemy, (_, health)) in simulation.enemies.enemy_vec.iter_mut().zip(layout) {
+        enemy.current_target = Some(base);
+        enemy.health = *health;
+    }
+    build_flow_field(&mut simulation, base);
+    simulation
+}
+
+fn build_flow_field(simulation: &mut Simulation, target: (usize, usize)) {
+    simulation.pathfinding_manager.clear_flow_fields();
+    simulation.pathfinding_manager.next_step(target, target, &simulation.level.level_vec);
+}
+
+//pixel the tower's projectile was fired at on an attack step
+fn fired_at(simulation: &mut Simulation) -> (i32, i32) {
+    simulation.projectiles.projectile_vec.clear();
+    simulation.game.frame_time = constants::TOWER_ARCHER_ATTACK_SPEED as u32;
+    level_manager::LevelManager::check_attacks(
+        &mut simulation.game,
+        &simulation.level,
+        &mut simulation.enemies,
+        &mut simulation.towers,
+        &mut simulation.buildings,
+        &mut simulation.projectiles,
+        &simulation.pathfinding_manager
+    );
+    assert_eq!(simulation.projectiles.projectile_vec.len(), 1);
+    simulation.projectiles.projectile_vec[0].target
+}
+
+fn tile_pixel(index: (usize, usize)) -> (i32, i32) {
+    (index.0 as i32 * constants::TILE_SIZE as i32, index.1 as i32 * constants::TILE_SIZE as i32)
+}
+
+#[test]
+fn test_targeting_modes_pick_their_enemy() {
+    let closest = (13, 11);
+    let first_and_weakest = (15, 11);
+    let last_and_strongest = (9, 11);
+    let mut simulation = targeting_layout((20, 11), &[
+        (closest, 50),
+        (first_and_weakest, 20),
+        (last_and_strongest, 80),
+        //out of range
+        ((22, 13), 1),
+        //dead, waiting to be removed
+        ((12, 13), 0),
+    ]);
+
+    let expected = [
+        (tower_manager::TargetingMode::First, first_and_weakest),
+        (tower_manager::TargetingMode::Last, last_and_strongest),
+        (tower_manager::TargetingMode::Strongest, last_and_strongest),
+        (tower_manager::TargetingMode::Weakest, first_and_weakest),
+        (tower_manager::TargetingMode::Closest, closest),
+    ];
+    for (targeting_mode, target) in expected {
+        simulation.towers.tower_vec[0].targeting_mode = targeting_mode;
+        assert_eq!(fired_at(&mut simulation), tile_pixel(target), "{:?}", targeting_mode);
+    }
+}
+
+#[test]
+fn test_targeting_ties_go_to_earlier_enemy() {
+    let mut simulation = targeting_layout((12, 20), &[
+        ((14, 11), 60),
+        ((10, 11), 60),
+    ]);
+    assert_eq!(
+        simulation.pathfinding_manager.remaining_distance(&simulation.enemies.enemy_vec[0]),
+        simulation.pathfinding_manager.remaining_distance(&simulation.enemies.enemy_vec[1])
+    );
+    for targeting_mode in [
+        tower_manager::TargetingMode::First,
+        tower_manager::TargetingMode::Last,
+        tower_manager::TargetingMode::Strongest,
+        tower_manager::TargetingMode::Weakest,
+        tower_manager::TargetingMode::Closest,
+    ] {
+        simulation.towers.tower_vec[0].targeting_mode = targeting_mode;
+        assert_eq!(fired_at(&mut simulation), tile_pixel((14, 11)), "{:?}", targeting_mode);
+    }
+}
+
+#[test]
+fn test_first_and_last_go_by_path_left() {
+    //the near goblin is walled off from base and has to go all the way around
+    let base = (18, 11);
+    let walled_off = (15, 11);
+    let open = (17, 6);
+    let mut simulation = targeting_layout(base, &[
+        (walled_off, 60),
+        (open, 60),
+    ]);
+    for row in 0..23 {
+        simulation.level.level_vec[16][row].tile_type = TileKind::Wall;
+    }
+    build_flow_field(&mut simulation, base);
+
+    simulation.towers.tower_vec[0].targeting_mode = tower_manager::TargetingMode::First;
+    assert_eq!(fired_at(&mut simulation), tile_pixel(open));
+    simulation.towers.tower_vec[0].targeting_mode = tower_manager::TargetingMode::Last;
+    assert_eq!(fired_at(&mut simulation), tile_pixel(walled_off));
+}
+
+#[test]
+fn test_targeting_mode_switch_is_replayed() {
+    let mut simulation = Simulation::new(None, vec![
+        LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+    ]);
+    assert_eq!(simulation.towers.tower_vec[0].targeting_mode, tower_manager::TargetingMode::First);
+    simulation.replay_manager.queue_action(ReplayAction::Target {
+        index: (12, 12),
+        targeting_mode: tower_manager::TargetingMode::Weakest,
+    });
+    simulation.step(1);
+    assert_eq!(simulation.towers.tower_vec[0].targeting_mode, tower_manager::TargetingMode::Weakest);
+
+    let replay = replay_manager::ReplayManager::parse_replay(
+        &simulation.replay_manager.to_replay_string()
+    ).unwrap();
+    assert_eq!(replay.input_vec, simulation.replay_manager.input_vec);
+    assert!(replay_manager::ReplayManager::parse_replay("level levels/farm.txt\n5 target 12 12 random\n").is_err());
+}
+
+#[test]
+fn test_spatial_grid_matches_every_pair_check() {
+    //xorshift, the same layout every run
+    let mut state: u64 = 0x9e3779b97f4a7c15;
+    let mut random = |range: i32| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % range as u64) as i32
+    };
+    let positions: Vec<(i32, i32)> = (0..300).map(|_| (random(1600) - 100, random(1600) - 100)).collect();
+    let mut spatial_grid = spatial_manager::SpatialGrid::new(constants::SPATIAL_CELL_SIZE);
+    spatial_grid.rebuild(positions.iter().copied());
+
+    for _ in 0..500 {
+        let center = (random(1800) - 200, random(1800) - 200);
+        let area = random(700);
+        let expected: Vec<usize> = (0..positions.len())
+            .filter(|index| tower_manager::TowerManager::is_within_area(center, positions[*index], area))
+            .collect();
+        let found: Vec<usize> = spatial_grid.query(center, area).into_iter()
+            .filter(|index| tower_manager::TowerManager::is_within_area(center, positions[*index], area))
+            .collect();
+        assert_eq!(found, expected, "center {:?} area {}", center, area);
+    }
+}
+
+#[test]
+fn test_crowded_level_keeps_stepping() {
+    let mut entities = Vec::new();
+    for column in 0..8 {
+        for row in 0..5 {
+            entities.push(LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (4 + column * 2, 3 + row * 2) });
+        }
+    }
+    //two to a tile below the towers
+    for column in 0..20 {
+        for row in 0..10 {
+            entities.push(goblin((2 + column, 14 + row)));
+            entities.push(goblin((2 + column, 14 + row)));
+        }
+    }
+    let mut simulation = Simulation::new(Some((1, 1)), entities);
+    assert_eq!(simulation.enemies.enemy_vec.len(), 400);
+
+    simulation.step(120);
+
+    assert!(simulation.enemies.enemy_vec.len() < 400 || simulation.enemies.enemy_vec.iter().any(|enemy| enemy.health < enemy.max_health));
+}
+
+//goblin at (8, 8) walking right along row 8, the tower at (12, 12) shoots from below it
+fn crossing_goblin(tile_data: TileData) -> Simulation {
+    let mut simulation = Simulation::new(None, vec![
+        LevelEntity::Tower { tile_data, index: (12, 12) },
+        goblin((8, 8)),
+    ]);
+    simulation.enemies.enemy_vec[0].final_path = Some((9..=22).map(|column| (column, 8)).collect());
+    simulation
+}
+
+//moves and hits without firing again
+fn fly(simulation: &mut Simulation, steps: u32) {
+    for _ in 0..steps {
+        simulation.enemies.move_enemies(
+            &mut simulation.game,
+            &mut simulation.level,
+            &simulation.towers,
+            &mut simulation.pathfinding_manager
+        );
+        simulation.projectiles.move_projectiles(&simulation.game, &simulation.enemies);
+        simulation.projectiles.check_projectile_hit(
+            &mut simulation.enemies,
+            &mut simulation.towers,
+            &mut simulation.buildings
+        );
+    }
+}
+
+#[test]
+fn test_tower_leads_moving_enemy() {
+    let mut simulation = crossing_goblin(TileData::ArcherTowerBottom);
+    let enemy_pos_pixel = tile_pixel((8, 8));
+    let aim = fired_at(&mut simulation);
+    assert_eq!(aim.1, enemy_pos_pixel.1);
+    assert!(aim.0 > enemy_pos_pixel.0 + constants::TILE_SIZE as i32, "aimed at {:?}", aim);
+    fly(&mut simulation, 40);
+    assert!(simulation.projectiles.projectile_vec[0].hit_target);
+    assert!(simulation.enemies.enemy_vec[0].health < simulation.enemies.enemy_vec[0].max_health);
+
+    //where it used to aim, the goblin is long gone when the arrow gets there
+    let mut simulation = crossing_goblin(TileData::ArcherTowerBottom);
+    fired_at(&mut simulation);
+    simulation.projectiles.projectile_vec[0].target = enemy_pos_pixel;
+    fly(&mut simulation, 40);
+    assert!(!simulation.projectiles.projectile_vec[0].hit_target);
+}
+
+#[test]
+fn test_homing_projectile_follows_enemy() {
+    let mut simulation = crossing_goblin(TileData::FireballTowerBottom);
+    simulation.game.frame_time = constants::TOWER_FIREBALL_ATTACK_SPEED as u32;
+    level_manager::LevelManager::check_attacks(
+        &mut simulation.game,
+        &simulation.level,
+        &mut simulation.enemies,
+        &mut simulation.towers,
+        &mut simulation.buildings,
+        &mut simulation.projectiles,
+        &simulation.pathfinding_manager
+    );
+    assert!(simulation.projectiles.projectile_vec[0].is_homing);
+    assert_eq!(simulation.projectiles.projectile_vec[0].target, tile_pixel((8, 8)));
+
+    fly(&mut simulation, 1);
+    let enemy = &simulation.enemies.enemy_vec[0];
+    assert_eq!(simulation.projectiles.projectile_vec[0].target, (enemy.pixel_index.0 as i32, enemy.pixel_index.1 as i32));
+    fly(&mut simulation, 40);
+    assert!(simulation.projectiles.projectile_vec[0].hit_target);
+}
+
+#[test]
+fn test_homing_projectile_flies_straight_once_target_dies() {
+    let mut simulation = crossing_goblin(TileData::FireballTowerBottom);
+    //standing right next to the first one's path
+    simulation.enemies.place_enemy(
+        &mut simulation.game,
+        &simulation.level.level_vec[9][9],
+        enemy_manager::EnemyType::Goblin,
+        (9, 9)
+    );
+    simulation.game.frame_time = constants::TOWER_FIREBALL_ATTACK_SPEED as u32;
+    level_manager::LevelManager::check_attacks(
+        &mut simulation.game,
+        &simulation.level,
+        &mut simulation.enemies,
+        &mut simulation.towers,
+        &mut simulation.buildings,
+        &mut simulation.projectiles,
+        &simulation.pathfinding_manager
+    );
+    assert_eq!(simulation.projectiles.projectile_vec[0].homing_enemy, Some(simulation.enemies.enemy_vec[0].id));
+
+    fly(&mut simulation, 1);
+    simulation.enemies.enemy_vec[0].health = 0;
+    let last_seen = simulation.projectiles.projectile_vec[0].target;
+    fly(&mut simulation, 5);
+    assert_eq!(simulation.projectiles.projectile_vec[0].homing_enemy, None);
+    assert_eq!(simulation.projectiles.projectile_vec[0].target, last_seen);
+}
+
+//hits without anything moving but the projectiles
+fn fly_still(simulation: &mut Simulation, steps: u32) {
+    for _ in 0..steps {
+        simulation.projectiles.move_projectiles(&simulation.game, &simulation.enemies);
+        simulation.projectiles.check_projectile_hit(
+            &mut simulation.enemies,
+            &mut simulation.towers,
+            &mut simulation.buildings
+        );
+    }
+}
+
+fn healths(simulation: &Simulation) -> Vec<u16> {
+    simulation.enemies.enemy_vec.iter().map(|enemy| enemy.health).collect()
+}
+
+#[test]
+fn test_splash_damage_falls_off() {
+    let mut simulation = Simulation::new(None, vec![
+        LevelEntity::Tower { tile_data: TileData::FireballTowerBottom, index: (12, 12) },
+        goblin((8, 8)),
+        goblin((9, 8)),
+        goblin((10, 8)),
+        goblin((11, 8)),
+    ]);
+    let impact = tile_pixel((8, 8));
+    let tower = &mut simulation.towers.tower_vec[0];
+    tower.projectile_effect = projectile_manager::ProjectileEffect::Splash { radius: 64, falloff: 25 };
+    simulation.projectiles.spawn_tower_projectile(tower, impact, impact, impact, None);
+    fly_still(&mut simulation, 1);
+
+    //50 damage, 25% of it left at 64 pixels
+    assert_eq!(healths(&simulation), vec![50, 69, 87, 100]);
+    assert_eq!(simulation.projectiles.explosion_vec.len(), 1);
+    assert_eq!(simulation.projectiles.explosion_vec[0].position, impact);
+    fly_still(&mut simulation, constants::EXPLOSION_DURATION as u32 + 1);
+    assert!(simulation.projectiles.explosion_vec.is_empty());
+}
+
+#[test]
+fn test_burn_damages_over_time() {
+    let mut simulation = Simulation::new(None, vec![
+        LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+        goblin((8, 8)),
+    ]);
+    let impact = tile_pixel((8, 8));
+    let tower = &mut simulation.towers.tower_vec[0];
+    tower.projectile_effect = projectile_manager::ProjectileEffect::Burn { damage: 5, duration: 60 };
+    simulation.projectiles.spawn_tower_projectile(tower, impact, impact, impact, None);
+    fly_still(&mut simulation, 1);
+    assert_eq!(healths(&simulation), vec![90]);
+
+    for _ in 0..100 {
+        simulation.enemies.update_status_effects();
+    }
+    //one tick every BURN_INTERVAL steps
+    let ticks = 60 / constants::BURN_INTERVAL;
+    assert_eq!(healths(&simulation), vec![90 - ticks * 5]);
+    assert!(simulation.enemies.enemy_vec[0].status_vec.is_empty());
+}
+
+#[test]
+fn test_pierce_hits_enemies_along_line() {
+    let mut simulation = targeting_layout((12, 20), &[
+        ((14, 11), 100),
+        ((16, 11), 100),
+        ((18, 11), 100),
+    ]);
+    simulation.towers.tower_vec[0].targeting_mode = tower_manager::TargetingMode::Closest;
+    simulation.towers.tower_vec[0].projectile_effect = projectile_manager::ProjectileEffect::Pierce { hits: 2 };
+    assert_eq!(fired_at(&mut simulation).1, tile_pixel((14, 11)).1);
+    fly_still(&mut simulation, 60);
+
+    //each one hit once, the arrow is spent after the second
+    assert_eq!(healths(&simulation), vec![90, 90, 100]);
+    assert!(simulation.projectiles.projectile_vec[0].hit_target);
+}
+
+fn status(kind: status_manager::StatusKind, strength: u8, time: u16) -> status_manager::StatusEffect {
+    status_manager::StatusEffect { kind, strength, time }
+}
+
+#[test]
+fn test_status_stacking_rules() {
+    use status_manager::StatusKind::{Poison, Slow};
+    let mut status_vec = Vec::new();
+    status_manager::apply_status(&mut status_vec, status(Slow, 30, 100));
+    status_manager::apply_status(&mut status_vec, status(Slow, 50, 20));
+    assert_eq!(status_vec, vec![status(Slow, 50, 100)]);
+    assert_eq!(status_manager::speed_percent(&status_vec), 50);
+
+    //poison stacks up to POISON_MAX_STACKS, then the one closest to running out is replaced
+    let mut status_vec = Vec::new();
+    for time in 1..=constants::POISON_MAX_STACKS as u16 {
+        status_manager::apply_status(&mut status_vec, status(Poison, 2, time * 60));
+    }
+    status_manager::apply_status(&mut status_vec, status(Poison, 4, 30));
+    assert_eq!(status_vec.len(), constants::POISON_MAX_STACKS);
+    assert!(!status_vec.contains(&status(Poison, 4, 30)));
+    status_manager::apply_status(&mut status_vec, status(Poison, 4, 600));
+    assert_eq!(status_vec[0], status(Poison, 4, 600));
+
+    //every stack ticks on its own
+    let mut damage = 0;
+    for _ in 0..constants::POISON_INTERVAL {
+        damage += status_manager::tick_statuses(&mut status_vec);
+    }
+    assert_eq!(damage, 4 + 2 * (constants::POISON_MAX_STACKS as u16 - 1));
+}
+
+#[test]
+fn test_slow_and_stun_hold_enemies_back() {
+    let mut distances = Vec::new();
+    for applied in [
+        None,
+        Some(status(status_manager::StatusKind::Slow, 50, 200)),
+        Some(status(status_manager::StatusKind::Stun, 0, 10)),
+    ] {
+        let mut simulation = crossing_goblin(TileData::ArcherTowerBottom);
+        let start = simulation.enemies.enemy_vec[0].pixel_index;
+        if let Some(applied) = applied {
+            status_manager::apply_status(&mut simulation.enemies.enemy_vec[0].status_vec, applied);
+        }
+        for _ in 0..10 {
+            fly(&mut simulation, 1);
+            simulation.enemies.update_status_effects();
+        }
+        distances.push(simulation.enemies.enemy_vec[0].pixel_index.0 - start.0);
+    }
+    assert!(distances[1] < distances[0], "moved {:?}", distances);
+    assert_eq!(distances[2], 0);
+}
+
+#[test]
+fn test_tomato_field_slows_enemy() {
+    let mut simulation = crossing_goblin(TileData::ArcherTowerBottom);
+    let tile = &mut simulation.level.level_vec[8][8];
+    tile.tile_type = TileKind::FieldHarvestable;
+    tile.tile_data = TileData::Tomatoes;
+    fly(&mut simulation, 1);
+    assert!(status_manager::has_status(
+        &simulation.enemies.enemy_vec[0].status_vec,
+        status_manager::StatusKind::Slow
+    ));
+}
+
+#[test]
+fn test_stunned_enemy_does_not_attack_tower() {
+    let mut tower_healths = Vec::new();
+    for stunned in [false, true] {
+        let mut simulation = Simulation::new(None, vec![
+            LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+            goblin((13, 11)),
+        ]);
+        for frame_time in 0..64 {
+            if stunned {
+                status_manager::apply_status(
+                    &mut simulation.enemies.enemy_vec[0].status_vec,
+                    status(status_manager::StatusKind::Stun, 0, 10)
+                );
+            }
+            simulation.game.frame_time = frame_time;
+            level_manager::LevelManager::check_attacks(
+                &mut simulation.game,
+                &simulation.level,
+                &mut simulation.enemies,
+                &mut simulation.towers,
+                &mut simulation.buildings,
+                &mut simulation.projectiles,
+                &simulation.pathfinding_manager
+            );
+        }
+        tower_healths.push(simulation.towers.tower_vec[0].health);
+    }
+    let max_health = constants::TOWER_ARCHER_HEALTH;
+    assert!(tower_healths[0] < max_health, "healths {:?}", tower_healths);
+    assert_eq!(tower_healths[1], max_health);
+}
+
+//what a save has to bring back for the run to carry on the same
+fn snapshot(simulation: &Simulation) -> Vec<String> {
+    let mut snapshot = vec![
+        format!("{} {} {:?}", simulation.game.gold_amount, simulation.game.frame_time, simulation.game.target_vec),
+    ];
+    for col in &simulation.level.level_vec {
+        snapshot.push(col.iter().map(|tile| tile.tile_type.to_char()).collect());
+    }
+    for tower in &simulation.towers.tower_vec {
+        snapshot.push(format!("{:?} {} {}", tower.bottom_index, tower.health, tower.projectile_damage));
+    }
+    for enemy in &simulation.enemies.enemy_vec {
+        snapshot.push(format!(
+            "{} {:?} {:?} {} {:?}",
+            enemy.id,
+            enemy.grid_index,
+            enemy.pixel_index,
+            enemy.health,
+            enemy.status_vec
+        ));
+    }
+    for projectile in &simulation.projectiles.projectile_vec {
+        snapshot.push(format!("{:?} {:?} {}", projectile.position, projectile.target, projectile.hit_target));
+    }
+    snapshot
+}
+
+fn saved_run() -> Simulation {
+    let mut simulation = Simulation::new(Some((2, 2)), vec![
+        LevelEntity::Tower { tile_data: TileData::ArcherTowerBottom, index: (12, 12) },
+        goblin((18, 18)),
+        goblin((20, 14)),
+    ]);
+    simulation.step(150);
+    status_manager::apply_status(
+        &mut simulation.enemies.enemy_vec[0].status_vec,
+        status(status_manager::StatusKind::Poison, 3, 200)
+    );
+    simulation
+}
+
+#[test]
+fn test_save_round_trip_keeps_run() {
+    let save_manager = save_file("round_trip");
+    let mut simulation = saved_run();
+    simulation.save(&save_manager).unwrap();
+
+    let mut loaded = Simulation::new(None, Vec::new());
+    loaded.load(&save_manager).unwrap();
+    assert_eq!(snapshot(&loaded), snapshot(&simulation));
+
+    simulation.step(200);
+    loaded.step(200);
+    assert_eq!(snapshot(&loaded), snapshot(&simulation));
+    std::fs::remove_file(&save_manager.save_path).ok();
+}
+
+#[test]
+fn test_corrupt_save_is_rejected() {
+    let save_manager = save_file("corrupt");
+    let simulation = saved_run();
+    simulation.save(&save_manager).unwrap();
+    let bytes = std::fs::read(&save_manager.save_path).unwrap();
+    let untouched = Simulation::new(None, vec![goblin((3, 3))]);
+    let expect_rejected = |bytes: &[u8]| {
+        std::fs::write(&save_manager.save_path, bytes).unwrap();
+        let mut loaded = Simulation::new(None, vec![goblin((3, 3))]);
+        assert!(loaded.load(&save_manager).is_err());
+        assert_eq!(snapshot(&loaded), snapshot(&untouched));
+    };
+
+    expect_rejected(&bytes[..bytes.len() / 2]);
+
+    //target count, after magic, version, four u32s and the base location
+    let target_count = 4 + 4 + 16 + 1 + 16;
+    let mut huge_count = bytes.clone();
+    huge_count[target_count..target_count + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+    expect_rejected(&huge_count);
+
+    let mut off_map = saved_run();
+    off_map.enemies.enemy_vec[0].grid_index = (999, 0);
+    off_map.save(&save_manager).unwrap();
+    expect_rejected(&std::fs::read(&save_manager.save_path).unwrap());
+    std::fs::remove_file(&save_manager.save_path).ok();
+}
+
+#[test]
+fn test_flyer_waiting_on_path_moves_after_load() {
+    let save_manager = save_file("flyer");
+    let mut simulation = Simulation::new(Some((2, 2)), vec![
+        LevelEntity::Enemy {
+            enemy_type: enemy_manager::EnemyType::from_name("bat").unwrap(),
+            index: (20, 20),
+        },
+    ]);
+    simulation.step(1);
+    let bat = &simulation.enemies.enemy_vec[0];
+    assert!(bat.path_request.is_some() && bat.final_path.is_none());
+    simulation.save(&save_manager).unwrap();
+
+    let mut loaded = Simulation::new(None, Vec::new());
+    loaded.load(&save_manager).unwrap();
+    let start = loaded.enemies.enemy_vec[0].pixel_index;
+    loaded.step(60);
+    assert_ne!(loaded.enemies.enemy_vec[0].pixel_index, start);
+    std::fs::remove_file(&save_manager.save_path).ok();
+}
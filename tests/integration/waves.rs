@@ -0,0 +1,78 @@
+use farm_defense::{constants, game_manager, wave_manager::{WaveManager, WaveParseError}};
+
+fn error_line(script: &str) -> usize {
+    match WaveManager::parse_wave_script(script) {
+        Ok(_) => panic!("script should not parse"),
+        Err(error) => error.line,
+    }
+}
+
+//level file in the temp dir with the given script next to it, None for no script
+fn level_with_script(name: &str, script: Option<&str>) -> String {
+    let level_path = std::env::temp_dir().join(format!("farm_defense_{}_{}.txt", name, std::process::id()));
+    let script_path = level_path.with_extension(constants::WAVE_SCRIPT_EXTENSION);
+    match script {
+        Some(script) => std::fs::write(&script_path, script).unwrap(),
+        None => {
+            let _ = std::fs::remove_file(&script_path);
+        }
+    }
+    level_path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_wave_script_parses() {
+    let script = "# comment\n\nwave 30\ngoblin 4 0 1.5 0 # trailing\n\nwave 20 1.5\nbat 6 * 0.5 8\nogre 1 1 0 14\n";
+    let wave_script = WaveManager::parse_wave_script(script).unwrap();
+    assert_eq!(wave_script.len(), 2);
+    assert_eq!(wave_script[0].build_time, 30.0);
+    assert_eq!(wave_script[0].health_multiplier, 1.0);
+    assert_eq!(wave_script[1].health_multiplier, 1.5);
+    assert_eq!(wave_script[1].groups.len(), 2);
+    assert_eq!(wave_script[1].groups[0].spawn_point, None);
+    assert_eq!(wave_script[1].groups[1].spawn_point, Some(1));
+    assert_eq!(wave_script[1].groups[1].line, 8);
+
+    assert!(WaveManager::read_wave_script(std::path::Path::new("levels/farm.waves"), 2).is_ok());
+}
+
+#[test]
+fn test_malformed_line_is_reported() {
+    let valid = "# comment\nwave 30\ngoblin 4 0 1.5 0\n";
+    assert_eq!(error_line(&format!("{}dragon 1 0 1 0\n", valid)), 4);
+    assert_eq!(error_line(&format!("{}goblin 4 0 1.5\n", valid)), 4);
+    assert_eq!(error_line(&format!("{}\n\ngoblin many 0 1.5 0\n", valid)), 6);
+    assert_eq!(error_line(&format!("{}goblin 4 -1 1.5 0\n", valid)), 4);
+    assert_eq!(error_line(&format!("{}wave soon\n", valid)), 4);
+    assert_eq!(error_line(&format!("{}wave 30 -2\n", valid)), 4);
+    assert_eq!(error_line("# comment\ngoblin 4 0 1.5 0\nwave 30\n"), 2);
+    //no waves at all isn't on any one line
+    assert_eq!(error_line("# comment\n\n"), 0);
+
+    //spawn points are checked against the level once the script parsed
+    let level_path = level_with_script("spawn_point", Some(valid));
+    let script_path = std::path::Path::new(&level_path).with_extension(constants::WAVE_SCRIPT_EXTENSION);
+    let error: WaveParseError = WaveManager::read_wave_script(&script_path, 0).err().unwrap();
+    assert_eq!(error.line, 3);
+    assert!(WaveManager::read_wave_script(&script_path, 1).is_ok());
+}
+
+#[test]
+fn test_missing_or_broken_script_falls_back_to_default_waves() {
+    for (name, script) in [("missing", None), ("broken", Some("wave 30\ngoblin 4 0\n"))] {
+        let mut game = game_manager::GameManager::new();
+        let mut wave_manager = WaveManager::new();
+        wave_manager.load_wave_script(&level_with_script(name, script), &mut game);
+
+        assert!(wave_manager.wave_script.is_empty(), "{}", name);
+        assert_eq!(wave_manager.wave_timer, WaveManager::default_wave(1).build_time, "{}", name);
+        //only a script that is there but broken is worth telling the player about
+        assert_eq!(game.message_vec.len(), script.is_some() as usize, "{}", name);
+    }
+
+    let default_wave = WaveManager::default_wave(constants::WAVE_BOSS_INTERVAL);
+    assert_eq!(default_wave.build_time, constants::WAVE_BUILD_TIME);
+    assert_eq!(default_wave.groups.len(), 2);
+    assert_eq!(default_wave.groups[0].count, WaveManager::wave_enemy_amount(constants::WAVE_BOSS_INTERVAL));
+    assert_eq!(WaveManager::default_wave(1).groups.len(), 1);
+}